@@ -0,0 +1,308 @@
+/// Re-anchor stored comments when the document has changed since the review
+///
+/// Stored comments record a line/col range plus the selected text snippet. The snippet
+/// is looked up in the current source: first at its original position, then as an exact
+/// match anywhere (nearest to the original position wins), then as the most similar
+/// window of lines. Comments whose text can't be found are flagged outdated and keep
+/// their original range.
+use crate::models::extract_text_snippet;
+use crate::parsing::LineOffsets;
+use crate::storage::ReviewComment;
+use serde::Serialize;
+use similar::TextDiff;
+use std::collections::HashSet;
+
+/// Minimum similarity ratio (0.0-1.0) for a fuzzy match
+pub const FUZZY_THRESHOLD: f32 = 0.6;
+
+/// Most line windows diffed per fuzzy search
+const MAX_FUZZY_CANDIDATES: usize = 64;
+
+/// How a stored comment was relocated in the current source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnchorStatus {
+    /// Snippet still at its original position
+    Unchanged,
+    /// Snippet found verbatim at a different position
+    Moved,
+    /// Similar text found (the snippet was edited)
+    Fuzzy,
+    /// Snippet no longer exists in the document
+    Outdated,
+}
+
+/// Position of a stored comment in the current source (cols as in `Comment`: 0 = whole lines)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Anchor {
+    pub line_start: usize,
+    pub col_start: usize,
+    pub line_end: usize,
+    pub col_end: usize,
+    pub status: AnchorStatus,
+}
+
+impl Anchor {
+    pub fn is_outdated(&self) -> bool {
+        self.status == AnchorStatus::Outdated
+    }
+}
+
+/// Relocate a stored review comment in the current source
+pub fn relocate_comment(source: &str, comment: &ReviewComment) -> Anchor {
+    relocate(
+        source,
+        &comment.text,
+        comment.selection.start.line,
+        comment.selection.start.col,
+        comment.selection.end.line,
+        comment.selection.end.col,
+    )
+}
+
+/// Relocate a snippet originally at the given range in the current source
+pub fn relocate(
+    source: &str,
+    snippet: &str,
+    line_start: usize,
+    col_start: usize,
+    line_end: usize,
+    col_end: usize,
+) -> Anchor {
+    let original = |status| Anchor {
+        line_start,
+        col_start,
+        line_end,
+        col_end,
+        status,
+    };
+
+    // Nothing to verify against, or still in place
+    if snippet.is_empty()
+        || extract_text_snippet(source, line_start, col_start, line_end, col_end) == snippet
+    {
+        return original(AnchorStatus::Unchanged);
+    }
+
+    let whole_lines = col_start == 0 || col_end == 0;
+
+    if let Some(anchor) = find_exact(source, snippet, line_start, whole_lines) {
+        return anchor;
+    }
+
+    if let Some(anchor) = find_fuzzy(source, snippet, line_start) {
+        return anchor;
+    }
+
+    // Keep the original range, clamped to the current document
+    let line_count = source.lines().count().max(1);
+    Anchor {
+        line_start: line_start.min(line_count),
+        col_start,
+        line_end: line_end.min(line_count),
+        col_end,
+        status: AnchorStatus::Outdated,
+    }
+}
+
+/// Nearest verbatim occurrence of the snippet (whole-line snippets must cover whole lines)
+fn find_exact(source: &str, snippet: &str, near_line: usize, whole_lines: bool) -> Option<Anchor> {
+    let offsets = LineOffsets::new(source);
+
+    source
+        .match_indices(snippet)
+        .filter_map(|(start, matched)| {
+            let end = start + matched.len();
+            let (line_start, col_start) = offsets.byte_to_line_col(source, start);
+            let (line_end, col_end) = offsets.byte_to_line_col(source, end);
+
+            if whole_lines {
+                let at_line_end = end == source.len() || source[end..].starts_with('\n');
+                if col_start != 1 || !at_line_end {
+                    return None;
+                }
+                return Some((line_start, 0, line_end, 0));
+            }
+
+            Some((line_start, col_start, line_end, col_end))
+        })
+        .min_by_key(|&(line, ..)| line.abs_diff(near_line))
+        .map(|(line_start, col_start, line_end, col_end)| Anchor {
+            line_start,
+            col_start,
+            line_end,
+            col_end,
+            status: AnchorStatus::Moved,
+        })
+}
+
+/// Most similar window of lines (same line count as the snippet), if similar enough
+///
+/// Only windows sharing words with the snippet (and long enough to reach the threshold)
+/// are diffed, best word overlap first, up to `MAX_FUZZY_CANDIDATES`. The bound is on work,
+/// not time, so the same source always anchors the same way.
+fn find_fuzzy(source: &str, snippet: &str, near_line: usize) -> Option<Anchor> {
+    let lines: Vec<&str> = source.lines().collect();
+    let window = snippet.lines().count().max(1);
+    if lines.len() < window {
+        return None;
+    }
+
+    let snippet_words = words(snippet);
+    let snippet_len = snippet.chars().count();
+
+    // Per-line shared word counts and lengths, summed over each window
+    let hits: Vec<usize> = lines
+        .iter()
+        .map(|line| words(line).intersection(&snippet_words).count())
+        .collect();
+    let lens: Vec<usize> = lines.iter().map(|line| line.chars().count()).collect();
+    let mut window_hits: usize = hits[..window].iter().sum();
+    let mut window_len: usize = lens[..window].iter().sum::<usize>() + window - 1;
+
+    let mut candidates = Vec::new();
+    for start in 0..=lines.len() - window {
+        if start > 0 {
+            let (out, into) = (start - 1, start + window - 1);
+            window_hits = window_hits - hits[out] + hits[into];
+            window_len = window_len - lens[out] + lens[into];
+        }
+
+        // Ratio is at most 2 * shorter / (sum of lengths)
+        let bound = 2.0 * snippet_len.min(window_len) as f32 / (snippet_len + window_len) as f32;
+        if window_hits > 0 && bound >= FUZZY_THRESHOLD {
+            candidates.push((window_hits, start));
+        }
+    }
+    candidates
+        .sort_by_key(|&(hits, start)| (std::cmp::Reverse(hits), (start + 1).abs_diff(near_line)));
+    candidates.truncate(MAX_FUZZY_CANDIDATES);
+
+    let mut best: Option<(f32, usize)> = None;
+    for (_, start) in candidates {
+        let candidate = lines[start..start + window].join("\n");
+        let ratio = TextDiff::from_chars(snippet, candidate.as_str()).ratio();
+
+        let better = match best {
+            None => true,
+            Some((best_ratio, best_start)) => {
+                ratio > best_ratio
+                    || (ratio == best_ratio
+                        && (start + 1).abs_diff(near_line) < (best_start + 1).abs_diff(near_line))
+            }
+        };
+        if better {
+            best = Some((ratio, start));
+        }
+    }
+
+    let (ratio, start) = best?;
+    (ratio >= FUZZY_THRESHOLD).then_some(Anchor {
+        line_start: start + 1,
+        col_start: 0,
+        line_end: start + window,
+        col_end: 0,
+        status: AnchorStatus::Fuzzy,
+    })
+}
+
+/// Distinct alphanumeric words of a text
+fn words(text: &str) -> HashSet<&str> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "# Spec\n\nThe system must be fast.\n\nIt stores data in JSON.\n";
+
+    #[test]
+    fn test_unchanged_anchor() {
+        let anchor = relocate(SOURCE, "The system must be fast.", 3, 0, 3, 0);
+        assert_eq!(anchor.status, AnchorStatus::Unchanged);
+        assert_eq!((anchor.line_start, anchor.line_end), (3, 3));
+    }
+
+    #[test]
+    fn test_moved_whole_lines() {
+        let source = "# Spec\n\nIntro added.\n\nThe system must be fast.\n";
+        let anchor = relocate(source, "The system must be fast.", 3, 0, 3, 0);
+
+        assert_eq!(anchor.status, AnchorStatus::Moved);
+        assert_eq!((anchor.line_start, anchor.col_start), (5, 0));
+        assert_eq!((anchor.line_end, anchor.col_end), (5, 0));
+    }
+
+    #[test]
+    fn test_moved_column_anchor_picks_nearest() {
+        let source = "fast\n\nnew line\nThe system must be fast.\n";
+        // "fast" was at line 3, cols 20-24
+        let anchor = relocate(source, "fast", 3, 20, 3, 24);
+
+        assert_eq!(anchor.status, AnchorStatus::Moved);
+        assert_eq!(
+            (
+                anchor.line_start,
+                anchor.col_start,
+                anchor.line_end,
+                anchor.col_end
+            ),
+            (4, 20, 4, 24)
+        );
+    }
+
+    #[test]
+    fn test_whole_line_snippet_ignores_partial_matches() {
+        let source = "Prefix: The system must be fast. Suffix\n";
+        let anchor = relocate(source, "The system must be fast.", 3, 0, 3, 0);
+        assert_ne!(anchor.status, AnchorStatus::Moved);
+    }
+
+    #[test]
+    fn test_fuzzy_match_after_edit() {
+        let source = "# Spec\n\nThe system must be very fast.\n\nIt stores data in JSON.\n";
+        let anchor = relocate(source, "The system must be fast.", 1, 0, 1, 0);
+
+        assert_eq!(anchor.status, AnchorStatus::Fuzzy);
+        assert_eq!((anchor.line_start, anchor.line_end), (3, 3));
+    }
+
+    #[test]
+    fn test_fuzzy_match_in_large_document() {
+        let mut source: String = (1..=11_000)
+            .map(|n| format!("The paragraph {} must describe item {}.\n", n, n * 7))
+            .collect();
+        source.push_str("The storage layer must never lose a write.\n");
+
+        let anchor = relocate(
+            &source,
+            "The storage layer must not lose writes.",
+            20,
+            0,
+            20,
+            0,
+        );
+
+        assert_eq!(anchor.status, AnchorStatus::Fuzzy);
+        assert_eq!((anchor.line_start, anchor.line_end), (11_001, 11_001));
+    }
+
+    #[test]
+    fn test_outdated_when_text_removed() {
+        let source = "# Spec\n\nCompletely different content here.\n";
+        let anchor = relocate(source, "It stores data in JSON.", 5, 0, 5, 0);
+
+        assert!(anchor.is_outdated());
+        // Original range clamped to the current document
+        assert_eq!((anchor.line_start, anchor.line_end), (3, 3));
+    }
+
+    #[test]
+    fn test_empty_snippet_is_unchanged() {
+        let anchor = relocate(SOURCE, "", 2, 0, 2, 0);
+        assert_eq!(anchor.status, AnchorStatus::Unchanged);
+    }
+}
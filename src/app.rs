@@ -1,29 +1,64 @@
-use crate::models::{Document, ReviewMode};
+use crate::keyboard::{KeyAction, Keymap};
+use crate::links::{classify_link, resolve_anchor, resolve_footnote, LinkTarget};
+use crate::models::{build_toc, current_section, Document, ReviewMode};
 use crate::parsing::parse_markdown;
-use crate::rendering::{render_comment_section, render_content};
+use crate::rendering::comments::focus_comment_input;
+use crate::rendering::search::{
+    focus_global_search_input, focus_search_input, render_global_search,
+};
+use crate::rendering::ui::ContentView;
+use crate::rendering::{
+    render_comment_section, render_content, render_diff, render_front_matter, render_history_bar,
+    render_search_bar, render_toc_panel,
+};
+use crate::search::GlobalSearch;
 use crate::syntax::SyntaxHighlighter;
 use crate::theme::Theme;
+use crate::watcher::POLL_INTERVAL;
 use eframe::egui;
+use std::path::{Path, PathBuf};
 
-/// Extract text snippet from source for the given line range
-fn extract_text_snippet(source: &str, start_line: usize, end_line: usize) -> String {
-    let lines: Vec<&str> = source.lines().collect();
-    let start_idx = start_line.saturating_sub(1); // Lines are 1-indexed
-    let end_idx = end_line.min(lines.len());
+/// Canonical path for comparing files (as given when it can't be resolved)
+fn absolute(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
 
-    lines[start_idx..end_idx].join("\n")
+/// Print human-readable status (to stderr when stdout is reserved for --json output)
+fn status(json_output: bool, message: impl std::fmt::Display) {
+    if json_output {
+        eprintln!("{}", message);
+    } else {
+        println!("{}", message);
+    }
 }
 
-pub struct MarkdownReviewApp {
-    documents: Vec<Document>,
+pub struct MarkdownReviewApp<'a> {
+    documents: &'a mut Vec<Document>,
     active_document_index: usize,
     highlighter: SyntaxHighlighter,
     theme: Theme,
     review_mode: ReviewMode,
+    json_output: bool,
+    /// Rejection reason being typed (Some while the Nope dialog is open)
+    rejection_reason: Option<String>,
+    keymap: Keymap,
+    /// Keyboard shortcut help overlay is open
+    show_help: bool,
+    /// Table of contents side panel is expanded
+    show_toc: bool,
+    /// Cross-document search panel (Ctrl+Shift+F)
+    global_search: GlobalSearch,
+    /// --out-dir for documents opened by following links
+    out_dir: Option<PathBuf>,
 }
 
-impl MarkdownReviewApp {
-    pub fn new(documents: Vec<Document>) -> Self {
+impl<'a> MarkdownReviewApp<'a> {
+    pub fn new(
+        documents: &'a mut Vec<Document>,
+        json_output: bool,
+        keymap: Keymap,
+        out_dir: Option<PathBuf>,
+    ) -> Self {
         let highlighter = SyntaxHighlighter::new();
 
         Self {
@@ -32,15 +67,350 @@ impl MarkdownReviewApp {
             highlighter,
             theme: Theme::default_theme(),
             review_mode: ReviewMode::default(),
+            json_output,
+            rejection_reason: None,
+            keymap,
+            show_help: false,
+            show_toc: true,
+            global_search: GlobalSearch::default(),
+            out_dir,
         }
     }
 
     fn active_document(&mut self) -> &mut Document {
         &mut self.documents[self.active_document_index]
     }
+
+    /// Follow a clicked link from the active document
+    ///
+    /// `#anchor` scrolls to the heading, relative markdown links open (or switch to) a
+    /// review tab, and anything else goes to the system opener.
+    fn follow_link(&mut self, ctx: &egui::Context, dest: &str) {
+        let base_path = self.active_document().base_path.clone();
+        match classify_link(dest, &base_path) {
+            LinkTarget::Anchor(anchor) => self.scroll_to_anchor(&anchor),
+            LinkTarget::Document { path, anchor } => {
+                if let Err(e) = self.open_linked_document(&path) {
+                    eprintln!("Failed to open {}: {:#}", dest, e);
+                    return;
+                }
+                if let Some(anchor) = anchor {
+                    self.scroll_to_anchor(&anchor);
+                }
+            }
+            LinkTarget::File(path) => {
+                let url = format!("file://{}", absolute(&path).display());
+                ctx.open_url(egui::OpenUrl::new_tab(url));
+            }
+            LinkTarget::External(url) => ctx.open_url(egui::OpenUrl::new_tab(url)),
+        }
+    }
+
+    /// Scroll the active document to the footnote or heading an anchor refers to
+    fn scroll_to_anchor(&mut self, anchor: &str) {
+        let doc = self.active_document();
+        if doc.chunks.is_none() {
+            doc.chunks = Some(parse_markdown(
+                &doc.source,
+                &doc.base_path,
+                &mut doc.image_manager,
+            ));
+        }
+        let chunks = doc.chunks.as_deref().unwrap_or_default();
+        let toc = build_toc(chunks, doc.source.lines().count());
+        match resolve_footnote(chunks, anchor).or_else(|| resolve_anchor(&toc, anchor)) {
+            Some(line) => doc.scroll_to_heading = Some(line),
+            None => eprintln!("No heading or footnote #{} in {}", anchor, doc.filename),
+        }
+    }
+
+    /// Switch to the tab reviewing `path`, opening it as a new review tab if needed
+    fn open_linked_document(&mut self, path: &Path) -> anyhow::Result<()> {
+        let target = absolute(path);
+        if let Some(index) = self
+            .documents
+            .iter()
+            .position(|doc| absolute(&doc.file_path) == target)
+        {
+            if self.documents[index].approved {
+                anyhow::bail!("{} was already reviewed", self.documents[index].filename);
+            }
+            self.active_document_index = index;
+            return Ok(());
+        }
+
+        let active = &self.documents[self.active_document_index];
+        let mut document = Document::open(
+            path,
+            self.out_dir.as_deref(),
+            active.storage.session_id.clone(),
+            active.project_type.clone(),
+        )?;
+        // Prior review rounds are informational - unreadable history shouldn't block a review
+        if let Err(e) = document.load_history() {
+            eprintln!(
+                "Warning: failed to load review history for {}: {:#}",
+                path.display(),
+                e
+            );
+        }
+
+        self.documents.push(document);
+        self.active_document_index = self.documents.len() - 1;
+        self.global_search.invalidate();
+        Ok(())
+    }
+
+    /// After a verdict: close if all documents are done, otherwise switch to the next one
+    fn advance_after_verdict(&mut self, ctx: &egui::Context, unapproved_docs: &[usize]) {
+        // Decided documents drop out of cross-document search results
+        self.global_search.invalidate();
+
+        // Check if all documents are done (all approved)
+        let all_done = self.documents.iter().all(|d| d.approved);
+        if all_done {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        } else {
+            // Switch to next unapproved document
+            if let Some(&next_idx) = unapproved_docs
+                .iter()
+                .find(|&&i| i != self.active_document_index)
+            {
+                self.active_document_index = next_idx;
+            }
+        }
+    }
+
+    /// Write an approval for the active document (LGTM button / shortcut)
+    fn approve_active(&mut self, ctx: &egui::Context, unapproved_docs: &[usize]) {
+        // Write approval and mark current document as approved
+        match self.documents[self.active_document_index].approve() {
+            Ok(path) => {
+                status(
+                    self.json_output,
+                    format!(
+                        "Approval saved to {:?} for records, no need to review. User says: LGTM",
+                        path
+                    ),
+                );
+
+                self.advance_after_verdict(ctx, unapproved_docs);
+            }
+            Err(e) => {
+                eprintln!("Failed to write approval: {}", e);
+            }
+        }
+    }
+
+    /// Write the active document's queued comments (Submit Review button / shortcut)
+    fn submit_active_review(&mut self, ctx: &egui::Context, unapproved_docs: &[usize]) {
+        // Build comment tuples for current document (for printing)
+        let doc = &mut self.documents[self.active_document_index];
+        let comment_data = doc.comment_data();
+
+        // Write review and mark document as done (review submitted)
+        match doc.submit_review() {
+            Ok(path) => {
+                let json_output = self.json_output;
+                status(json_output, format!("Review written to: {:?}", path));
+                status(json_output, "");
+                // Print the full review content
+                for (text, comment, line_start, _col_start, line_end, _col_end) in &comment_data {
+                    status(json_output, format!("Lines {}-{}:", line_start, line_end));
+                    status(
+                        json_output,
+                        format!("  Selected: {}", text.lines().next().unwrap_or("")),
+                    );
+                    if text.lines().count() > 1 {
+                        status(json_output, "  ...");
+                    }
+                    status(json_output, format!("  Comment: {}", comment));
+                    status(json_output, "");
+                }
+
+                self.advance_after_verdict(ctx, unapproved_docs);
+            }
+            Err(e) => {
+                eprintln!("Failed to write review: {}", e);
+            }
+        }
+    }
+
+    /// Run a keyboard shortcut action (see keyboard.rs)
+    fn handle_key_action(
+        &mut self,
+        ctx: &egui::Context,
+        action: KeyAction,
+        unapproved_docs: &[usize],
+    ) {
+        // Dialogs own the keyboard while open
+        if self.rejection_reason.is_some() {
+            return;
+        }
+
+        match action {
+            KeyAction::NextDocument | KeyAction::PreviousDocument => {
+                let Some(pos) = unapproved_docs
+                    .iter()
+                    .position(|&i| i == self.active_document_index)
+                else {
+                    return;
+                };
+                let count = unapproved_docs.len();
+                let next = if action == KeyAction::NextDocument {
+                    (pos + 1) % count
+                } else {
+                    (pos + count - 1) % count
+                };
+                self.active_document_index = unapproved_docs[next];
+            }
+            KeyAction::Submit => {
+                let doc = &mut self.documents[self.active_document_index];
+                if doc.selection.is_active() && !doc.comment_text.is_empty() {
+                    // Same as "Start Review" / "Add to Review": queue the typed comment
+                    let text = std::mem::take(&mut doc.comment_text);
+                    if doc.add_comment(text) {
+                        self.review_mode = ReviewMode::Batched;
+                    }
+                } else if doc.comment_count() > 0 {
+                    self.submit_active_review(ctx, unapproved_docs);
+                }
+            }
+            KeyAction::ClearSelection => {
+                if self.show_help {
+                    self.show_help = false;
+                } else if self.global_search.open {
+                    self.global_search.open = false;
+                } else if self.active_document().search.open {
+                    self.active_document().search.open = false;
+                } else {
+                    // A second Escape hides the keyboard cursor
+                    let doc = self.active_document();
+                    if doc.selection.is_active() {
+                        doc.selection.clear();
+                    } else {
+                        doc.cursor.line = None;
+                    }
+                }
+            }
+            KeyAction::Search => {
+                self.active_document().search.open = true;
+                focus_search_input(ctx);
+            }
+            KeyAction::GlobalSearch => {
+                self.global_search.open = true;
+                focus_global_search_input(ctx);
+            }
+            KeyAction::CursorDown
+            | KeyAction::CursorUp
+            | KeyAction::NextBlock
+            | KeyAction::PreviousBlock
+            | KeyAction::ExtendSelectionDown
+            | KeyAction::ExtendSelectionUp
+            | KeyAction::ExtendSelectionNextBlock
+            | KeyAction::ExtendSelectionPreviousBlock => {
+                if let Some((motion, extend)) = action.cursor_motion() {
+                    self.active_document().move_cursor(motion, extend);
+                }
+            }
+            KeyAction::CommentAtCursor => {
+                let doc = self.active_document();
+                if doc.cursor.select_line(&mut doc.selection) {
+                    focus_comment_input(ctx);
+                }
+            }
+            KeyAction::Approve => {
+                // Like the LGTM button: only without queued comments
+                if self.documents[self.active_document_index].comment_count() == 0 {
+                    self.approve_active(ctx, unapproved_docs);
+                }
+            }
+            KeyAction::ToggleHelp => self.show_help = !self.show_help,
+            KeyAction::ToggleContents => self.show_toc = !self.show_toc,
+        }
+    }
+
+    /// Help overlay listing the active key bindings
+    fn render_help_overlay(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_help;
+        egui::Window::new("Keyboard shortcuts")
+            .open(&mut open)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("key_bindings")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for action in KeyAction::ALL {
+                            ui.label(action.description());
+                            ui.monospace(self.keymap.shortcuts_for(action).join(", "));
+                            ui.end_row();
+                        }
+                    });
+            });
+        self.show_help &= open;
+    }
+
+    /// Rejection dialog: optional free-text reason typed before rejecting
+    fn render_rejection_dialog(&mut self, ctx: &egui::Context, unapproved_docs: &[usize]) {
+        let Some(reason) = &mut self.rejection_reason else {
+            return;
+        };
+
+        let mut confirmed = false;
+        let mut cancelled = false;
+
+        egui::Window::new(format!(
+            "Reject {}",
+            self.documents[self.active_document_index].filename
+        ))
+        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+        .resizable(false)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            ui.label("Reason (optional):");
+            ui.text_edit_multiline(reason);
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                confirmed = ui.button("Reject").clicked();
+                cancelled = ui.button("Cancel").clicked();
+            });
+        });
+
+        if cancelled {
+            self.rejection_reason = None;
+        } else if confirmed {
+            let reason = self
+                .rejection_reason
+                .take()
+                .filter(|r| !r.trim().is_empty());
+            let doc = &mut self.documents[self.active_document_index];
+
+            match doc.reject(reason.clone()) {
+                Ok(path) => {
+                    status(
+                        self.json_output,
+                        format!(
+                            "Rejection saved to {:?}. User says more discussion is needed for {}.",
+                            path, doc.filename
+                        ),
+                    );
+                    if let Some(reason) = reason {
+                        status(self.json_output, format!("  Reason: {}", reason));
+                    }
+                    self.advance_after_verdict(ctx, unapproved_docs);
+                }
+                Err(e) => {
+                    eprintln!("Failed to write rejection: {}", e);
+                }
+            }
+        }
+    }
 }
 
-impl eframe::App for MarkdownReviewApp {
+impl eframe::App for MarkdownReviewApp<'_> {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Parse markdown on first frame for active document (lazy initialization)
         {
@@ -54,6 +424,18 @@ impl eframe::App for MarkdownReviewApp {
             }
         }
 
+        // Live reload: re-parse documents whose file changed on disk
+        for doc in self.documents.iter_mut().filter(|d| !d.approved) {
+            if doc.watcher.poll() {
+                if let Err(e) = doc.reload() {
+                    eprintln!("Failed to reload {}: {}", doc.filename, e);
+                }
+                doc.image_manager.forget_evicted(ctx);
+                self.global_search.invalidate();
+            }
+        }
+        ctx.request_repaint_after(POLL_INTERVAL);
+
         // Tab bar (if multiple documents, showing only non-approved docs)
         let unapproved_docs: Vec<usize> = self
             .documents
@@ -85,6 +467,24 @@ impl eframe::App for MarkdownReviewApp {
             });
         }
 
+        // Keyboard shortcuts (central keymap, see keyboard.rs)
+        for action in self.keymap.pressed(ctx) {
+            self.handle_key_action(ctx, action, &unapproved_docs);
+        }
+
+        // Cross-document search: results for every open document, click to jump
+        if let Some((doc_index, line)) =
+            render_global_search(ctx, &mut self.global_search, self.active_document_index)
+        {
+            self.active_document_index = doc_index;
+            let doc = self.active_document();
+            doc.cursor.line = Some(line);
+            doc.scroll_to_line = Some(line);
+        }
+        if self.global_search.open {
+            self.global_search.update(self.documents);
+        }
+
         // Top menu bar for LGTM and Submit Review buttons (per-document)
         let active_has_comments = self.documents[self.active_document_index].comment_count() > 0;
         let active_approved = self.documents[self.active_document_index].approved;
@@ -107,134 +507,20 @@ impl eframe::App for MarkdownReviewApp {
                         // (added first so it appears on the right in right_to_left layout)
                         if !active_has_comments {
                             if ui.button("Nope").clicked() {
-                                // Print rejection message with filename (no file write for rejections)
-                                let filename = &self.documents[self.active_document_index].filename;
-                                println!("User says more discussion is needed for {}.", filename);
-
-                                // Mark document as approved (treated same as LGTM for navigation)
-                                let doc = &mut self.documents[self.active_document_index];
-                                doc.approved = true;
-
-                                // Check if all documents are done (all approved)
-                                let all_done = self.documents.iter().all(|d| d.approved);
-                                if all_done {
-                                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
-                                } else {
-                                    // Switch to next unapproved document
-                                    if let Some(&next_idx) = unapproved_docs
-                                        .iter()
-                                        .find(|&&i| i != self.active_document_index)
-                                    {
-                                        self.active_document_index = next_idx;
-                                    }
-                                }
+                                // Open rejection dialog (reason is optional)
+                                self.rejection_reason = Some(String::new());
                             }
 
                             // LGTM button - only show if current document has no comments
                             if ui.button("LGTM").clicked() {
-                                // Write approval for current document
-                                match self.documents[self.active_document_index]
-                                    .write_approval()
-                                {
-                                    Ok(path) => {
-                                        println!("Approval saved to {:?} for records, no need to review. User says: LGTM", path);
-                                        // Mark document as approved
-                                        let doc = &mut self.documents[self.active_document_index];
-                                        doc.approved = true;
-
-                                        // Check if all documents are done (all approved)
-                                        let all_done = self.documents.iter().all(|d| d.approved);
-                                        if all_done {
-                                            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
-                                        } else {
-                                            // Switch to next unapproved document
-                                            if let Some(&next_idx) = unapproved_docs
-                                                .iter()
-                                                .find(|&&i| i != self.active_document_index)
-                                            {
-                                                self.active_document_index = next_idx;
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        eprintln!("Failed to write approval: {}", e);
-                                    }
-                                }
+                                self.approve_active(ctx, &unapproved_docs);
                             }
                         }
 
                         // Submit Review button - only show in batched mode with comments
                         if self.review_mode == ReviewMode::Batched && active_has_comments {
                             if ui.button("Submit Review").clicked() {
-                                // Build comment tuples for current document
-                                let doc = &self.documents[self.active_document_index];
-                                let comment_data: Vec<_> = doc
-                                    .comments
-                                    .iter()
-                                    .map(|c| {
-                                        let text = extract_text_snippet(
-                                            &doc.source,
-                                            c.line_start,
-                                            c.line_end,
-                                        );
-                                        (
-                                            text,
-                                            c.text.clone(),
-                                            c.line_start,
-                                            c.col_start,
-                                            c.line_end,
-                                            c.col_end,
-                                        )
-                                    })
-                                    .collect();
-
-                                match doc.write_review(comment_data.clone()) {
-                                    Ok(path) => {
-                                        println!("Review written to: {:?}", path);
-                                        println!();
-                                        // Print the full review content
-                                        for (
-                                            text,
-                                            comment,
-                                            line_start,
-                                            _col_start,
-                                            line_end,
-                                            _col_end,
-                                        ) in &comment_data
-                                        {
-                                            println!("Lines {}-{}:", line_start, line_end);
-                                            println!(
-                                                "  Selected: {}",
-                                                text.lines().next().unwrap_or("")
-                                            );
-                                            if text.lines().count() > 1 {
-                                                println!("  ...");
-                                            }
-                                            println!("  Comment: {}", comment);
-                                            println!();
-                                        }
-                                        // Mark document as approved (review submitted)
-                                        let doc = &mut self.documents[self.active_document_index];
-                                        doc.approved = true;
-
-                                        // Check if all documents are done
-                                        let all_done = self.documents.iter().all(|d| d.approved);
-                                        if all_done {
-                                            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
-                                        } else {
-                                            // Switch to next unapproved document
-                                            if let Some(&next_idx) = unapproved_docs
-                                                .iter()
-                                                .find(|&&i| i != self.active_document_index)
-                                            {
-                                                self.active_document_index = next_idx;
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        eprintln!("Failed to write review: {}", e);
-                                    }
-                                }
+                                self.submit_active_review(ctx, &unapproved_docs);
                             }
                         }
                     });
@@ -242,6 +528,71 @@ impl eframe::App for MarkdownReviewApp {
             });
         }
 
+        self.render_rejection_dialog(ctx, &unapproved_docs);
+        self.render_help_overlay(ctx);
+
+        // "File changed" banner after a live reload (until dismissed)
+        let doc = &mut self.documents[self.active_document_index];
+        if let Some(summary) = doc.reload_summary {
+            egui::TopBottomPanel::top("reload_banner").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let mut message = format!("{} changed on disk — reloaded", doc.filename);
+                    if summary.moved > 0 || summary.outdated > 0 {
+                        message.push_str(&format!(
+                            " ({} comment(s) re-anchored, {} outdated)",
+                            summary.moved, summary.outdated
+                        ));
+                    }
+                    ui.colored_label(ui.visuals().warn_fg_color, message);
+                    if ui.button("Dismiss").clicked() {
+                        doc.reload_summary = None;
+                    }
+                });
+            });
+        }
+
+        // Prior review rounds for the active document (toggles for margin markers)
+        render_history_bar(
+            ctx,
+            &mut self.documents[self.active_document_index].history,
+            &self.theme,
+        );
+
+        // Search bar (Ctrl+F): re-run the query when it changed, scroll to the focused match
+        let doc = &mut self.documents[self.active_document_index];
+        let comment_on_match = render_search_bar(ctx, &mut doc.search);
+        if doc.search.open {
+            doc.search
+                .update(&doc.source, doc.chunks.as_deref().unwrap_or_default());
+        }
+        if comment_on_match {
+            if let Some(hit) = doc.search.current_match() {
+                let range = hit.byte_range.clone();
+                doc.selection
+                    .select_bytes(&doc.source, range.start, range.end);
+                focus_comment_input(ctx);
+            }
+        }
+        if let Some(line) = doc.search.take_reveal() {
+            doc.scroll_to_line = Some(line);
+        }
+
+        // Table of contents (rendered documents only; diff mode shows hunks)
+        let toc = match (&doc.diff, &doc.chunks) {
+            (None, Some(chunks)) => build_toc(chunks, doc.source.lines().count()),
+            _ => Vec::new(),
+        };
+        render_toc_panel(
+            ctx,
+            &toc,
+            &doc.comments,
+            doc.current_heading,
+            &mut self.show_toc,
+            &mut doc.scroll_to_heading,
+            &self.theme,
+        );
+
+        let mut clicked_link = None;
         egui::CentralPanel::default().show(ctx, |ui| {
             // Apply page-level scroll area
             egui::ScrollArea::vertical().show(ui, |ui| {
@@ -294,42 +645,99 @@ impl eframe::App for MarkdownReviewApp {
                         };
                         ui.heading(title);
 
+                        if let Some(front_matter) = &doc.front_matter {
+                            render_front_matter(ui, front_matter, theme);
+                        }
+
                         // Clear layout map at start of frame
                         doc.layout_map.clear();
 
-                        // Only render if chunks are loaded
-                        if let Some(chunks) = &mut doc.chunks {
-                            render_content(
+                        // Diff mode replaces the rendered document with its hunks
+                        if let Some(diff) = &mut doc.diff {
+                            render_diff(
                                 ui,
-                                ctx,
-                                chunks,
+                                diff,
                                 &mut doc.selection,
-                                &mut doc.image_manager,
+                                &mut doc.layout_map,
+                                doc.cursor.line,
+                                &doc.search,
+                                theme,
+                            );
+                        } else if let Some(chunks) = &mut doc.chunks {
+                            clicked_link = render_content(
+                                ui,
+                                ctx,
+                                ContentView {
+                                    chunks,
+                                    source: &doc.source,
+                                    selection: &mut doc.selection,
+                                    image_manager: &mut doc.image_manager,
+                                    layout_map: &mut doc.layout_map,
+                                    history: &doc.history,
+                                    changes: doc
+                                        .revision
+                                        .as_ref()
+                                        .map_or(&[], |revision| &revision.markers[..]),
+                                    cursor: doc.cursor.line,
+                                    search: &doc.search,
+                                },
                                 highlighter,
                                 theme,
-                                &mut doc.layout_map,
                             );
                         } else {
                             ui.label("Loading...");
                         }
+
+                        // Bring a requested line into view (layout map was just recorded)
+                        if let Some(line) = doc.scroll_to_line.take() {
+                            if let Some(y) = doc.layout_map.get_line_y(line) {
+                                let target = egui::Rect::from_min_size(
+                                    egui::pos2(ui.min_rect().left(), y),
+                                    egui::vec2(1.0, theme.spacing.min_line_height),
+                                );
+                                ui.scroll_to_rect(target, Some(egui::Align::Center));
+                            }
+                        }
+
+                        // Contents or anchor jump: heading or footnote to the top of the view
+                        if let Some(line) = doc.scroll_to_heading.take() {
+                            if let Some(y) = doc.layout_map.get_line_y(line) {
+                                let target = egui::Rect::from_min_size(
+                                    egui::pos2(ui.min_rect().left(), y),
+                                    egui::vec2(1.0, theme.spacing.min_line_height),
+                                );
+                                ui.scroll_to_rect(target, Some(egui::Align::Min));
+                            }
+                        }
+
+                        // Section at the top of the view (highlighted in the contents)
+                        doc.current_heading =
+                            current_section(&toc, &doc.layout_map, ui.clip_rect().top(), 4.0)
+                                .map(|i| toc[i].line);
+
+                        // Keep the keyboard cursor visible (minimal scroll, no re-centering)
+                        if let Some(line) = doc.cursor.take_reveal() {
+                            if let Some((start_y, end_y)) = doc.layout_map.get_y_range(line, line) {
+                                let target = egui::Rect::from_x_y_ranges(
+                                    ui.min_rect().x_range(),
+                                    start_y..=end_y,
+                                );
+                                ui.scroll_to_rect(target, None);
+                            }
+                        }
                     });
                 });
 
                 // Render comment UI as floating window (outside scroll area)
-                render_comment_section(
-                    ctx,
-                    &doc.layout_map,
-                    &mut doc.selection,
-                    &mut doc.comment_text,
-                    &mut doc.comments,
-                    theme,
-                    &mut self.review_mode,
-                    &doc.storage,
-                    &doc.source,
-                );
+                render_comment_section(ctx, doc, theme, &mut self.review_mode);
 
                 ui.add_space(self.theme.layout.page_margin_bottom);
             });
         });
+
+        // Links are followed only on an explicit click
+        if let Some(dest) = clicked_link {
+            self.follow_link(ctx, &dest);
+        }
     }
 }
@@ -0,0 +1,21 @@
+/// Graphviz DOT graphs, parsed and laid out by layout-rs
+use super::svg::SvgCanvas;
+use super::Palette;
+use anyhow::{anyhow, bail, Result};
+use layout::gv::{DotParser, GraphBuilder};
+
+pub fn render(source: &str, palette: Palette) -> Result<String> {
+    let graph = DotParser::new(source)
+        .process()
+        .map_err(|e| anyhow!("invalid DOT: {}", e))?;
+    let mut builder = GraphBuilder::new();
+    builder.visit_graph(&graph);
+    let mut graph = builder.get();
+    if graph.num_nodes() == 0 {
+        bail!("empty graph");
+    }
+
+    let mut canvas = SvgCanvas::new(palette);
+    graph.do_it(false, false, false, &mut canvas);
+    Ok(canvas.finish())
+}
@@ -0,0 +1,391 @@
+/// Mermaid flowcharts (`flowchart`/`graph`), laid out by layout-rs
+///
+/// Supports node declarations with labels in any of mermaid's bracket shapes (circles
+/// drawn as circles, rounded shapes with rounded corners, the rest as boxes), link
+/// chains with `&` groups, link labels (`-->|text|` and `-- text -->`), dotted and thick
+/// links, and arrow, open and bidirectional ends. Subgraph, class and style statements
+/// are skipped; their nodes and links are still drawn. `BT`/`RL` draw as `TB`/`LR`.
+use super::svg::{measure_text, SvgCanvas};
+use super::{label_text, Palette, FONT_SIZE};
+use anyhow::{anyhow, bail, Result};
+use layout::core::base::Orientation;
+use layout::core::color::Color;
+use layout::core::geometry::Point;
+use layout::core::style::{LineStyleKind, StyleAttr};
+use layout::std_shapes::shapes::{Arrow, Element, LineEndKind, ShapeKind};
+use layout::topo::layout::VisualGraph;
+use std::collections::HashMap;
+
+/// Space between a node's label and its border
+const NODE_PADDING: (f64, f64) = (12.0, 8.0);
+
+/// Corner radius of rounded and stadium nodes
+const ROUNDED: usize = 10;
+
+/// Bracket pairs of node shapes, longest first so `((` wins over `(`
+const SHAPES: &[(&str, &str, Shape)] = &[
+    ("(((", ")))", Shape::Circle),
+    ("((", "))", Shape::Circle),
+    ("([", "])", Shape::Rounded),
+    ("[[", "]]", Shape::Box),
+    ("[(", ")]", Shape::Rounded),
+    ("[/", "/]", Shape::Box),
+    ("[\\", "\\]", Shape::Box),
+    ("{{", "}}", Shape::Box),
+    ("(", ")", Shape::Rounded),
+    ("[", "]", Shape::Box),
+    ("{", "}", Shape::Box),
+    (">", "]", Shape::Box),
+];
+
+/// Statements that don't declare nodes or links
+const SKIPPED: &[&str] = &[
+    "subgraph",
+    "end",
+    "direction",
+    "classDef",
+    "class",
+    "style",
+    "linkStyle",
+    "click",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Shape {
+    Box,
+    Rounded,
+    Circle,
+}
+
+#[derive(Debug, PartialEq)]
+struct Node {
+    label: String,
+    shape: Shape,
+}
+
+#[derive(Debug, PartialEq)]
+struct Link {
+    label: String,
+    dashed: bool,
+    thick: bool,
+    start_arrow: bool,
+    end_arrow: bool,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Flowchart {
+    nodes: Vec<Node>,
+    /// (from, to) node indices
+    links: Vec<(usize, usize, Link)>,
+    /// Mermaid node id → index into `nodes`
+    ids: HashMap<String, usize>,
+}
+
+pub fn render(header: &str, body: &[&str], palette: Palette) -> Result<String> {
+    let orientation = match header.split_whitespace().nth(1) {
+        Some("LR" | "RL") => Orientation::LeftToRight,
+        _ => Orientation::TopToBottom,
+    };
+    let chart = parse(body)?;
+    if chart.nodes.is_empty() {
+        bail!("flowchart has no nodes");
+    }
+
+    let look = |rounded: usize, width: usize| {
+        StyleAttr::new(
+            Color::fast("black"),
+            width,
+            Some(Color::fast("white")),
+            rounded,
+            FONT_SIZE,
+        )
+    };
+    let mut graph = VisualGraph::new(orientation);
+    let handles: Vec<_> = chart
+        .nodes
+        .iter()
+        .map(|node| {
+            let (w, h) = measure_text(&node.label, FONT_SIZE as f32);
+            let (w, h) = (w + 2.0 * NODE_PADDING.0, h + 2.0 * NODE_PADDING.1);
+            let (shape, rounded, size) = match node.shape {
+                Shape::Circle => {
+                    let diameter = w.max(h);
+                    let shape = ShapeKind::new_circle(&node.label);
+                    (shape, 0, Point::new(diameter, diameter))
+                }
+                Shape::Rounded => (ShapeKind::new_box(&node.label), ROUNDED, Point::new(w, h)),
+                Shape::Box => (ShapeKind::new_box(&node.label), 0, Point::new(w, h)),
+            };
+            // layout-rs creates shapes in the flipped orientation (see its DOT builder)
+            let element = Element::create(shape, look(rounded, 1), orientation.flip(), size);
+            graph.add_node(element)
+        })
+        .collect();
+
+    for (from, to, link) in &chart.links {
+        let end = |arrow: bool| {
+            if arrow {
+                LineEndKind::Arrow
+            } else {
+                LineEndKind::None
+            }
+        };
+        let style = if link.dashed {
+            LineStyleKind::Dashed
+        } else {
+            LineStyleKind::Normal
+        };
+        let arrow = Arrow::new(
+            end(link.start_arrow),
+            end(link.end_arrow),
+            style,
+            &link.label,
+            &look(0, if link.thick { 3 } else { 1 }),
+            &None,
+            &None,
+        );
+        graph.add_edge(arrow, handles[*from], handles[*to]);
+    }
+
+    let mut canvas = SvgCanvas::new(palette);
+    graph.do_it(false, false, false, &mut canvas);
+    Ok(canvas.finish())
+}
+
+fn parse(body: &[&str]) -> Result<Flowchart> {
+    let mut chart = Flowchart::default();
+
+    for statement in body.iter().flat_map(|line| line.split(';')) {
+        let statement = statement.trim();
+        let keyword = statement.split_whitespace().next().unwrap_or_default();
+        if statement.is_empty() || SKIPPED.contains(&keyword) {
+            continue;
+        }
+
+        let mut rest = statement;
+        let mut group = parse_group(&mut rest, &mut chart)?;
+        while !rest.trim().is_empty() {
+            let link = parse_link(&mut rest)?;
+            let next = parse_group(&mut rest, &mut chart)?;
+            for &from in &group {
+                for &to in &next {
+                    let link = Link {
+                        label: link.label.clone(),
+                        ..link
+                    };
+                    chart.links.push((from, to, link));
+                }
+            }
+            group = next;
+        }
+    }
+    Ok(chart)
+}
+
+/// `A & B[label] & ...` → node indices
+fn parse_group(rest: &mut &str, chart: &mut Flowchart) -> Result<Vec<usize>> {
+    let mut group = vec![parse_node(rest, chart)?];
+    while let Some(after) = rest.trim_start().strip_prefix('&') {
+        *rest = after;
+        group.push(parse_node(rest, chart)?);
+    }
+    Ok(group)
+}
+
+/// A node reference, declaring it (or updating its label) when it has a shape
+fn parse_node(rest: &mut &str, chart: &mut Flowchart) -> Result<usize> {
+    let s = rest.trim_start();
+    let id_len = s
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .unwrap_or(s.len());
+    if id_len == 0 {
+        bail!("expected a node at '{}'", s);
+    }
+    let id = &s[..id_len];
+    let mut s = &s[id_len..];
+
+    let mut declared = None;
+    if let Some((open, close, shape)) = SHAPES.iter().find(|(open, ..)| s.starts_with(open)) {
+        let inner = &s[open.len()..];
+        // Trapezoids close with either slash
+        let end = match *open {
+            "[/" | "[\\" => inner.find("/]").or_else(|| inner.find("\\]")),
+            _ => inner.find(close),
+        }
+        .ok_or_else(|| anyhow!("unclosed '{}' in node {}", open, id))?;
+        declared = Some((label_text(&inner[..end]), *shape));
+        s = &inner[end + close.len()..];
+    }
+    // Class shorthand (`A:::name`)
+    if let Some(after) = s.strip_prefix(":::") {
+        s = after.trim_start_matches(|c: char| c.is_alphanumeric() || c == '_' || c == '-');
+    }
+    *rest = s;
+
+    let index = *chart.ids.entry(id.to_string()).or_insert_with(|| {
+        chart.nodes.push(Node {
+            label: id.to_string(),
+            shape: Shape::Box,
+        });
+        chart.nodes.len() - 1
+    });
+    if let Some((label, shape)) = declared {
+        chart.nodes[index].label = label;
+        chart.nodes[index].shape = shape;
+    }
+    Ok(index)
+}
+
+/// A link: `-->`, `---`, `-.->`, `==>`, `<-->`, `--x`, with `|label|` or `-- label -->`
+fn parse_link(rest: &mut &str) -> Result<Link> {
+    let s = rest.trim_start();
+    let (start_arrow, s) = match s.strip_prefix('<') {
+        Some(after) => (true, after),
+        None => (false, s),
+    };
+    let stroke_len = s
+        .find(|c: char| !matches!(c, '-' | '=' | '.'))
+        .unwrap_or(s.len());
+    if stroke_len < 2 {
+        bail!("expected a link at '{}'", rest.trim_start());
+    }
+    let mut stroke = s[..stroke_len].to_string();
+    let mut s = &s[stroke_len..];
+    let mut end_arrow = false;
+    let mut label = String::new();
+
+    let head = |s: &str| -> Option<usize> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some('>'), _) => Some(1),
+            // Circle and cross ends, drawn as plain ends
+            (Some('o' | 'x'), next) if next.is_none_or(char::is_whitespace) => Some(1),
+            _ => None,
+        }
+    };
+
+    if let Some(len) = head(s) {
+        end_arrow = s.starts_with('>');
+        s = &s[len..];
+    } else if matches!(stroke.as_str(), "--" | "==" | "-.") {
+        // Label between the link's halves: `-- text -->`, `== text ==>`, `-. text .->`
+        let closings: &[&str] = match stroke.as_str() {
+            "--" => &["-->", "---", "--x", "--o"],
+            "==" => &["==>", "===", "==x", "==o"],
+            _ => &[".->", ".-", ".-x", ".-o"],
+        };
+        let (position, closing) = closings
+            .iter()
+            .filter_map(|closing| s.find(closing).map(|position| (position, *closing)))
+            .min_by_key(|(position, closing)| (*position, std::cmp::Reverse(closing.len())))
+            .ok_or_else(|| anyhow!("unclosed link label at '{}'", s.trim()))?;
+        label = label_text(&s[..position]);
+        stroke.push_str(closing);
+        end_arrow = closing.ends_with('>');
+        s = &s[position + closing.len()..];
+    }
+
+    if let Some(after) = s.trim_start().strip_prefix('|') {
+        let end = after
+            .find('|')
+            .ok_or_else(|| anyhow!("unclosed link label '|{}'", after))?;
+        label = label_text(&after[..end]);
+        s = &after[end + 1..];
+    }
+    *rest = s;
+
+    Ok(Link {
+        label,
+        dashed: stroke.contains('.'),
+        thick: stroke.contains('='),
+        start_arrow,
+        end_arrow,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(chart: &Flowchart, index: usize) -> &str {
+        chart.ids.iter().find(|(_, &i)| i == index).unwrap().0
+    }
+
+    fn links(chart: &Flowchart) -> Vec<(&str, &str, &str)> {
+        chart
+            .links
+            .iter()
+            .map(|(from, to, link)| (id(chart, *from), id(chart, *to), link.label.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_nodes_and_chains() {
+        let chart = parse(&[
+            "A[Start] --> B(Step one) --> C{Done?}",
+            "C -->|yes| D((End)); C -- no --> B",
+            "subgraph group",
+            "A & D --- E[\"Two<br>lines\"]:::note",
+            "end",
+            "classDef note fill:#fff",
+        ])
+        .unwrap();
+
+        let nodes: Vec<_> = chart
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (id(&chart, i), n.label.as_str(), n.shape))
+            .collect();
+        assert_eq!(
+            nodes,
+            vec![
+                ("A", "Start", Shape::Box),
+                ("B", "Step one", Shape::Rounded),
+                ("C", "Done?", Shape::Box),
+                ("D", "End", Shape::Circle),
+                ("E", "Two\nlines", Shape::Box),
+            ]
+        );
+        assert_eq!(
+            links(&chart),
+            vec![
+                ("A", "B", ""),
+                ("B", "C", ""),
+                ("C", "D", "yes"),
+                ("C", "B", "no"),
+                ("A", "E", ""),
+                ("D", "E", ""),
+            ]
+        );
+        // `---` has no arrowhead
+        assert!(chart.links[0].2.end_arrow && !chart.links[4].2.end_arrow);
+    }
+
+    #[test]
+    fn test_parse_link_styles() {
+        let chart = parse(&["A -.-> B", "B ==> C", "C <--> D", "D -. maybe .-> A"]).unwrap();
+        let styles: Vec<_> = chart
+            .links
+            .iter()
+            .map(|(_, _, l)| (l.dashed, l.thick, l.start_arrow, l.end_arrow))
+            .collect();
+        assert_eq!(
+            styles,
+            vec![
+                (true, false, false, true),
+                (false, true, false, true),
+                (false, false, true, true),
+                (true, false, false, true),
+            ]
+        );
+        assert_eq!(chart.links[3].2.label, "maybe");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse(&["A[unclosed --> B"]).is_err());
+        assert!(parse(&["A --> "]).is_err());
+        assert!(parse(&["A ~~ B"]).is_err());
+    }
+}
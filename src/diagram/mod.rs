@@ -0,0 +1,147 @@
+/// Diagrams: ```` ```mermaid ```` and ```` ```dot ```` fences laid out offline into SVG
+///
+/// Mermaid flowcharts and DOT graphs get a layered graph layout from layout-rs;
+/// mermaid sequence diagrams are laid out in lanes here. Everything is drawn through
+/// `SvgCanvas`, which writes labels as glyph outlines so the SVG rasterizes without
+/// font support. Other mermaid diagram types are errors; the renderer shows the source.
+mod dot;
+mod flowchart;
+mod sequence;
+mod svg;
+
+use anyhow::{anyhow, bail, Result};
+use eframe::egui::Color32;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Label size in pixels per em (layout-rs' default font size)
+const FONT_SIZE: usize = 14;
+
+/// Diagram languages recognized in fenced code blocks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiagramKind {
+    Mermaid,
+    Dot,
+}
+
+/// Colors diagrams are drawn in (from the theme)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Palette {
+    pub text: Color32,
+    pub stroke: Color32,
+    /// Node and participant background
+    pub fill: Color32,
+    /// Sequence diagram note background
+    pub note: Color32,
+}
+
+/// The diagram language of a code block's info string, if it is one
+pub fn diagram_kind(lang: &str) -> Option<DiagramKind> {
+    match lang.to_ascii_lowercase().as_str() {
+        "mermaid" => Some(DiagramKind::Mermaid),
+        "dot" | "graphviz" | "gv" => Some(DiagramKind::Dot),
+        _ => None,
+    }
+}
+
+/// Lay out a diagram and draw it as an SVG document
+pub fn render_svg(kind: DiagramKind, source: &str, palette: Palette) -> Result<String> {
+    // layout-rs asserts on graphs it can't handle; report those like parse errors
+    catch_unwind(AssertUnwindSafe(|| match kind {
+        DiagramKind::Mermaid => render_mermaid(source, palette),
+        DiagramKind::Dot => dot::render(source, palette),
+    }))
+    .map_err(|_| anyhow!("diagram layout failed"))?
+}
+
+fn render_mermaid(source: &str, palette: Palette) -> Result<String> {
+    let lines = mermaid_lines(source);
+    let Some((header, body)) = lines.split_first() else {
+        bail!("empty diagram");
+    };
+    let keyword = header.split_whitespace().next().unwrap_or_default();
+    match keyword {
+        "flowchart" | "graph" => flowchart::render(header, body, palette),
+        "sequenceDiagram" => sequence::render(body, palette),
+        _ => bail!("unsupported mermaid diagram type '{}'", keyword),
+    }
+}
+
+/// Trimmed, non-empty statement lines, without `%%` comments or `---` front matter
+fn mermaid_lines(source: &str) -> Vec<&str> {
+    let mut lines = source.lines().map(str::trim).peekable();
+    if lines.peek() == Some(&"---") {
+        lines.next();
+        lines.by_ref().find(|line| *line == "---");
+    }
+    lines
+        .filter(|line| !line.is_empty() && !line.starts_with("%%"))
+        .collect()
+}
+
+/// Label text: quotes stripped, `<br>` as a line break
+fn label_text(raw: &str) -> String {
+    let raw = raw.trim();
+    let raw = raw
+        .strip_prefix('"')
+        .and_then(|r| r.strip_suffix('"'))
+        .unwrap_or(raw);
+    raw.replace("<br/>", "\n")
+        .replace("<br />", "\n")
+        .replace("<br>", "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn palette() -> Palette {
+        Palette {
+            text: Color32::BLACK,
+            stroke: Color32::GRAY,
+            fill: Color32::WHITE,
+            note: Color32::LIGHT_YELLOW,
+        }
+    }
+
+    #[test]
+    fn test_diagram_kind() {
+        assert_eq!(diagram_kind("mermaid"), Some(DiagramKind::Mermaid));
+        assert_eq!(diagram_kind("dot"), Some(DiagramKind::Dot));
+        assert_eq!(diagram_kind("Graphviz"), Some(DiagramKind::Dot));
+        assert_eq!(diagram_kind("rust"), None);
+        assert_eq!(diagram_kind(""), None);
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let flowchart = "flowchart LR\n  A[Parse] -->|chunks| B(Render)\n  B --> C{Done?}\n";
+        let svg = render_svg(DiagramKind::Mermaid, flowchart, palette()).unwrap();
+        assert!(svg.starts_with("<svg"));
+        // Labels are outlines, not text elements
+        assert!(svg.contains("<path") && !svg.contains("<text"));
+
+        let sequence = "sequenceDiagram\n  Alice->>Bob: Hello\n  Bob-->>Alice: Hi\n";
+        assert!(render_svg(DiagramKind::Mermaid, sequence, palette()).is_ok());
+
+        let dot = "digraph { a -> b; b -> c [label=\"next\"]; }";
+        assert!(render_svg(DiagramKind::Dot, dot, palette()).is_ok());
+    }
+
+    #[test]
+    fn test_render_svg_errors() {
+        let pie = "pie title Pets\n  \"Dogs\" : 386\n";
+        let error = render_svg(DiagramKind::Mermaid, pie, palette()).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("unsupported mermaid diagram type 'pie'"));
+
+        assert!(render_svg(DiagramKind::Mermaid, "%% nothing\n", palette()).is_err());
+        assert!(render_svg(DiagramKind::Dot, "digraph { a -> }", palette()).is_err());
+    }
+
+    #[test]
+    fn test_mermaid_lines() {
+        let source = "---\ntitle: Flow\n---\nflowchart TD\n  %% comment\n\n  A --> B\n";
+        assert_eq!(mermaid_lines(source), vec!["flowchart TD", "A --> B"]);
+    }
+}
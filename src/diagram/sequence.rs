@@ -0,0 +1,579 @@
+/// Mermaid sequence diagrams, laid out in lanes
+///
+/// Participants (declared or implied by messages) get a lane each, spaced so message
+/// and note labels fit; messages and notes stack down the lifelines in order. Supports
+/// `participant`/`actor` with `as` aliases, the solid/dotted message arrows (`->>`,
+/// `-->>`, `->`, `-x`, `-)`, `<<->>`, ...), `autonumber`, notes (`left of`, `right of`,
+/// `over A,B`) and `loop`/`alt`/`opt`/`par`/`critical`/`break` frames with their
+/// `else`/`and`/`option` sections. Activations are ignored; `box`/`rect` group nothing.
+use super::svg::{measure_text, Head, SvgCanvas};
+use super::{label_text, Palette, FONT_SIZE};
+use anyhow::{anyhow, bail, Result};
+
+/// Space between a participant's label and its box border
+const BOX_PADDING: (f64, f64) = (12.0, 8.0);
+
+/// Minimum space between participant boxes
+const LANE_GAP: f64 = 30.0;
+
+/// Space between stacked rows (messages, notes, frame headers)
+const ROW_GAP: f64 = 14.0;
+
+/// Space around labels next to lines and inside notes
+const LABEL_MARGIN: f64 = 8.0;
+
+/// Width and height of a self-message loop
+const SELF_LOOP: (f64, f64) = (30.0, 18.0);
+
+/// Frame border distance outside the outermost lanes, per nesting level
+const FRAME_INSET: f64 = 8.0;
+
+/// Message arrows, longest first so `-->>` wins over `-->`: (token, dashed, head, both ends)
+const ARROWS: &[(&str, bool, Head, bool)] = &[
+    ("<<-->>", true, Head::Arrow, true),
+    ("<<->>", false, Head::Arrow, true),
+    ("-->>", true, Head::Arrow, false),
+    ("->>", false, Head::Arrow, false),
+    ("--x", true, Head::Cross, false),
+    ("-x", false, Head::Cross, false),
+    ("--)", true, Head::Open, false),
+    ("-)", false, Head::Open, false),
+    ("-->", true, Head::None, false),
+    ("->", false, Head::None, false),
+];
+
+/// Frames that draw a border and title
+const FRAMES: &[&str] = &["loop", "alt", "opt", "par", "critical", "break"];
+
+/// Blocks closed by `end` that draw nothing
+const GROUPS: &[&str] = &["box", "rect"];
+
+/// Sections within a frame
+const SECTIONS: &[&str] = &["else", "and", "option"];
+
+/// Statements that don't affect the drawing
+const SKIPPED: &[&str] = &[
+    "activate",
+    "deactivate",
+    "destroy",
+    "title",
+    "links",
+    "link",
+];
+
+#[derive(Debug, PartialEq)]
+struct Participant {
+    id: String,
+    label: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Placement {
+    LeftOf,
+    RightOf,
+    Over,
+}
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Message {
+        from: usize,
+        to: usize,
+        text: String,
+        dashed: bool,
+        head: Head,
+        both_ends: bool,
+    },
+    Note {
+        placement: Placement,
+        first: usize,
+        last: usize,
+        text: String,
+    },
+    /// Frame (or, without a title, a group) start
+    Start {
+        title: Option<String>,
+    },
+    Section {
+        title: String,
+    },
+    End,
+}
+
+/// A frame or group being drawn
+struct Block {
+    title: Option<String>,
+    top: f64,
+    /// Section dividers: (y, title)
+    sections: Vec<(f64, String)>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Sequence {
+    participants: Vec<Participant>,
+    events: Vec<Event>,
+    autonumber: bool,
+}
+
+impl Sequence {
+    /// Index of a participant, adding it on first mention
+    fn participant(&mut self, id: &str) -> usize {
+        let id = id.trim();
+        match self.participants.iter().position(|p| p.id == id) {
+            Some(index) => index,
+            None => {
+                self.participants.push(Participant {
+                    id: id.to_string(),
+                    label: id.to_string(),
+                });
+                self.participants.len() - 1
+            }
+        }
+    }
+}
+
+fn parse(body: &[&str]) -> Result<Sequence> {
+    let mut sequence = Sequence::default();
+
+    for line in body {
+        let line = line.strip_prefix("create ").unwrap_or(line).trim();
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+
+        if keyword == "participant" || keyword == "actor" {
+            let (id, label) = match rest.split_once(" as ") {
+                Some((id, label)) => (id, label_text(label)),
+                None => (rest, rest.to_string()),
+            };
+            let index = sequence.participant(id);
+            sequence.participants[index].label = label;
+        } else if keyword == "autonumber" {
+            sequence.autonumber = true;
+        } else if keyword.eq_ignore_ascii_case("note") {
+            let event = parse_note(rest, &mut sequence)?;
+            sequence.events.push(event);
+        } else if FRAMES.contains(&keyword) {
+            let title = match rest {
+                "" => keyword.to_string(),
+                _ => format!("{} [{}]", keyword, rest),
+            };
+            sequence.events.push(Event::Start { title: Some(title) });
+        } else if GROUPS.contains(&keyword) {
+            sequence.events.push(Event::Start { title: None });
+        } else if SECTIONS.contains(&keyword) {
+            let title = match rest {
+                "" => String::new(),
+                _ => format!("[{}]", rest),
+            };
+            sequence.events.push(Event::Section { title });
+        } else if keyword == "end" {
+            sequence.events.push(Event::End);
+        } else if !SKIPPED.contains(&keyword) {
+            let event = parse_message(line, &mut sequence)?;
+            sequence.events.push(event);
+        }
+    }
+    Ok(sequence)
+}
+
+/// `A->>B: text` (the text is optional)
+fn parse_message(line: &str, sequence: &mut Sequence) -> Result<Event> {
+    let (head, text) = line.split_once(':').unwrap_or((line, ""));
+    let Some((position, &(token, dashed, arrow_head, both_ends))) = ARROWS
+        .iter()
+        .filter_map(|arrow| head.find(arrow.0).map(|position| (position, arrow)))
+        .min_by_key(|(position, _)| *position)
+    else {
+        bail!("unrecognized statement '{}'", line);
+    };
+    let from = head[..position].trim();
+    // `+`/`-` activate or deactivate the target
+    let to = head[position + token.len()..]
+        .trim_start_matches(['+', '-'])
+        .trim();
+    if from.is_empty() || to.is_empty() {
+        bail!("message needs two participants: '{}'", line);
+    }
+    Ok(Event::Message {
+        from: sequence.participant(from),
+        to: sequence.participant(to),
+        text: label_text(text),
+        dashed,
+        head: arrow_head,
+        both_ends,
+    })
+}
+
+/// `left of A: text`, `right of A: text`, `over A: text`, `over A,B: text`
+fn parse_note(rest: &str, sequence: &mut Sequence) -> Result<Event> {
+    let (target, text) = rest
+        .split_once(':')
+        .ok_or_else(|| anyhow!("note without text: 'Note {}'", rest))?;
+    let (placement, ids) = if let Some(ids) = target.strip_prefix("left of") {
+        (Placement::LeftOf, ids)
+    } else if let Some(ids) = target.strip_prefix("right of") {
+        (Placement::RightOf, ids)
+    } else if let Some(ids) = target.strip_prefix("over") {
+        (Placement::Over, ids)
+    } else {
+        bail!(
+            "note placement must be 'left of', 'right of' or 'over': 'Note {}'",
+            rest
+        );
+    };
+    let indices: Vec<usize> = ids.split(',').map(|id| sequence.participant(id)).collect();
+    Ok(Event::Note {
+        placement,
+        first: *indices.iter().min().unwrap_or(&0),
+        last: *indices.iter().max().unwrap_or(&0),
+        text: label_text(text),
+    })
+}
+
+pub fn render(body: &[&str], palette: Palette) -> Result<String> {
+    let sequence = parse(body)?;
+    if sequence.participants.is_empty() {
+        bail!("sequence diagram has no participants");
+    }
+    let size = FONT_SIZE as f32;
+    let lanes = lane_centers(&sequence);
+    let box_sizes: Vec<(f64, f64)> = sequence
+        .participants
+        .iter()
+        .map(|p| {
+            let (w, h) = measure_text(&p.label, size);
+            (w + 2.0 * BOX_PADDING.0, h + 2.0 * BOX_PADDING.1)
+        })
+        .collect();
+    let box_height = box_sizes.iter().map(|(_, h)| *h).fold(0.0, f64::max);
+    let left = lanes[0] - box_sizes[0].0 / 2.0;
+    let right = lanes[lanes.len() - 1] + box_sizes[box_sizes.len() - 1].0 / 2.0;
+
+    let title_height = measure_text("", size).1;
+
+    let mut canvas = SvgCanvas::new(palette);
+    let mut y = box_height + ROW_GAP;
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut number = 0;
+
+    for event in &sequence.events {
+        match event {
+            Event::Message {
+                from,
+                to,
+                text,
+                dashed,
+                head,
+                both_ends,
+            } => {
+                number += 1;
+                let text = match sequence.autonumber {
+                    true => format!("{}. {}", number, text),
+                    false => text.clone(),
+                };
+                let (text_width, text_height) = measure_text(&text, size);
+                let (x1, x2) = (lanes[*from], lanes[*to]);
+                if from == to {
+                    let points = [
+                        (x1, y),
+                        (x1 + SELF_LOOP.0, y),
+                        (x1 + SELF_LOOP.0, y + SELF_LOOP.1),
+                        (x1, y + SELF_LOOP.1),
+                    ];
+                    canvas.polyline(&points, palette.text, *dashed);
+                    canvas.head(points[3], points[2], *head, palette.text);
+                    let label_x = x1 + SELF_LOOP.0 + LABEL_MARGIN + text_width / 2.0;
+                    canvas.text((label_x, y + SELF_LOOP.1 / 2.0), &text, size, palette.text);
+                    y += SELF_LOOP.1 + ROW_GAP;
+                } else {
+                    if !text.is_empty() {
+                        let center = ((x1 + x2) / 2.0, y + text_height / 2.0);
+                        canvas.text(center, &text, size, palette.text);
+                        y += text_height + LABEL_MARGIN / 2.0;
+                    }
+                    canvas.polyline(&[(x1, y), (x2, y)], palette.text, *dashed);
+                    canvas.head((x2, y), (x1, y), *head, palette.text);
+                    if *both_ends {
+                        canvas.head((x1, y), (x2, y), *head, palette.text);
+                    }
+                    y += ROW_GAP;
+                }
+            }
+            Event::Note {
+                placement,
+                first,
+                last,
+                text,
+            } => {
+                let (text_width, text_height) = measure_text(text, size);
+                let width = text_width + 2.0 * LABEL_MARGIN;
+                let height = text_height + LABEL_MARGIN;
+                let (x, width) = match placement {
+                    Placement::LeftOf => (lanes[*first] - LABEL_MARGIN - width, width),
+                    Placement::RightOf => (lanes[*last] + LABEL_MARGIN, width),
+                    Placement::Over => {
+                        // Wide enough to reach past the outer lanes
+                        let width = width.max(lanes[*last] - lanes[*first] + 2.0 * LANE_GAP);
+                        ((lanes[*first] + lanes[*last]) / 2.0 - width / 2.0, width)
+                    }
+                };
+                canvas.rect(
+                    (x, y),
+                    (width, height),
+                    0.0,
+                    Some(palette.note),
+                    palette.stroke,
+                );
+                canvas.text(
+                    (x + width / 2.0, y + height / 2.0),
+                    text,
+                    size,
+                    palette.text,
+                );
+                y += height + ROW_GAP;
+            }
+            Event::Start { title } => {
+                blocks.push(Block {
+                    title: title.clone(),
+                    top: y,
+                    sections: Vec::new(),
+                });
+                if title.is_some() {
+                    y += title_height + ROW_GAP;
+                }
+            }
+            Event::Section { title } => {
+                if let Some(block) = blocks.last_mut() {
+                    block.sections.push((y, title.clone()));
+                    y += title_height + ROW_GAP;
+                }
+            }
+            Event::End => {
+                if let Some(block) = blocks.pop() {
+                    draw_frame(&mut canvas, block, (left, right), blocks.len(), y, size);
+                    y += ROW_GAP;
+                }
+            }
+        }
+    }
+    // Unclosed blocks end with the diagram
+    while let Some(block) = blocks.pop() {
+        draw_frame(&mut canvas, block, (left, right), blocks.len(), y, size);
+        y += ROW_GAP;
+    }
+
+    for (lane, (participant, (width, _))) in lanes
+        .iter()
+        .zip(sequence.participants.iter().zip(&box_sizes))
+    {
+        canvas.polyline(&[(*lane, box_height), (*lane, y)], palette.stroke, true);
+        for top in [0.0, y] {
+            let corner = (lane - width / 2.0, top);
+            canvas.rect(
+                corner,
+                (*width, box_height),
+                3.0,
+                Some(palette.fill),
+                palette.stroke,
+            );
+            let center = (*lane, top + box_height / 2.0);
+            canvas.text(center, &participant.label, size, palette.text);
+        }
+    }
+    Ok(canvas.finish())
+}
+
+/// Lifeline x positions: boxes side by side, widened where labels need the room
+fn lane_centers(sequence: &Sequence) -> Vec<f64> {
+    let size = FONT_SIZE as f32;
+    let widths: Vec<f64> = sequence
+        .participants
+        .iter()
+        .map(|p| measure_text(&p.label, size).0 + 2.0 * BOX_PADDING.0)
+        .collect();
+
+    // (left lane, right lane, minimum distance)
+    let mut constraints: Vec<(usize, usize, f64)> = (1..widths.len())
+        .map(|i| (i - 1, i, (widths[i - 1] + widths[i]) / 2.0 + LANE_GAP))
+        .collect();
+    let mut number = 0;
+    for event in &sequence.events {
+        match event {
+            Event::Message { from, to, text, .. } => {
+                number += 1;
+                let text = match sequence.autonumber {
+                    true => format!("{}. {}", number, text),
+                    false => text.clone(),
+                };
+                let width = measure_text(&text, size).0 + 2.0 * LABEL_MARGIN;
+                if from == to {
+                    if from + 1 < widths.len() {
+                        constraints.push((*from, from + 1, SELF_LOOP.0 + width));
+                    }
+                } else {
+                    constraints.push((*from.min(to), *from.max(to), width));
+                }
+            }
+            Event::Note {
+                placement,
+                first,
+                last,
+                text,
+            } => {
+                let width = measure_text(text, size).0 + 3.0 * LABEL_MARGIN;
+                match placement {
+                    Placement::LeftOf if *first > 0 => {
+                        constraints.push((first - 1, *first, width + widths[first - 1] / 2.0))
+                    }
+                    Placement::RightOf if last + 1 < widths.len() => {
+                        constraints.push((*last, last + 1, width + widths[last + 1] / 2.0))
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Widening a gap moves every later lane, so settle constraints left to right
+    constraints.sort_by_key(|(_, right, _)| *right);
+    let mut lanes: Vec<f64> = vec![widths.first().copied().unwrap_or_default() / 2.0];
+    lanes.resize(widths.len(), lanes[0]);
+    for (left, right, distance) in constraints {
+        let deficit = distance - (lanes[right] - lanes[left]);
+        if deficit > 0.0 {
+            for lane in &mut lanes[right..] {
+                *lane += deficit;
+            }
+        }
+    }
+    lanes
+}
+
+/// Border around a closed frame, its title and section dividers
+fn draw_frame(
+    canvas: &mut SvgCanvas,
+    Block {
+        title,
+        top,
+        sections,
+    }: Block,
+    (left, right): (f64, f64),
+    depth: usize,
+    bottom: f64,
+    size: f32,
+) {
+    let Some(title) = title else {
+        return;
+    };
+    let palette = canvas.palette();
+    let inset = FRAME_INSET * (3.0 - depth.min(2) as f64);
+    let (left, right) = (left - inset, right + inset);
+    canvas.rect(
+        (left, top),
+        (right - left, bottom - top),
+        0.0,
+        None,
+        palette.stroke,
+    );
+
+    let label = |canvas: &mut SvgCanvas, y: f64, text: &str| {
+        let (width, height) = measure_text(text, size);
+        let center = (
+            left + LABEL_MARGIN + width / 2.0,
+            y + ROW_GAP / 2.0 + height / 2.0,
+        );
+        canvas.text(center, text, size, palette.text);
+    };
+    label(canvas, top, &title);
+    for (y, title) in sections {
+        canvas.polyline(&[(left, y), (right, y)], palette.stroke, true);
+        label(canvas, y, &title);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sequence() {
+        let sequence = parse(&[
+            "participant A as Alice",
+            "actor B",
+            "autonumber",
+            "A->>+B: Hello",
+            "loop Every minute",
+            "B-->>-A: Hi",
+            "Note over A,B: shared",
+            "end",
+            "A-xC: gone",
+        ])
+        .unwrap();
+
+        let participants: Vec<_> = sequence
+            .participants
+            .iter()
+            .map(|p| (p.id.as_str(), p.label.as_str()))
+            .collect();
+        assert_eq!(participants, vec![("A", "Alice"), ("B", "B"), ("C", "C")]);
+        assert!(sequence.autonumber);
+        assert_eq!(
+            sequence.events,
+            vec![
+                Event::Message {
+                    from: 0,
+                    to: 1,
+                    text: "Hello".to_string(),
+                    dashed: false,
+                    head: Head::Arrow,
+                    both_ends: false,
+                },
+                Event::Start {
+                    title: Some("loop [Every minute]".to_string())
+                },
+                Event::Message {
+                    from: 1,
+                    to: 0,
+                    text: "Hi".to_string(),
+                    dashed: true,
+                    head: Head::Arrow,
+                    both_ends: false,
+                },
+                Event::Note {
+                    placement: Placement::Over,
+                    first: 0,
+                    last: 1,
+                    text: "shared".to_string(),
+                },
+                Event::End,
+                Event::Message {
+                    from: 0,
+                    to: 2,
+                    text: "gone".to_string(),
+                    dashed: false,
+                    head: Head::Cross,
+                    both_ends: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lanes_fit_labels() {
+        let short = parse(&["A->>B: x"]).unwrap();
+        let long = parse(&["A->>B: a much longer message label"]).unwrap();
+        let gap = |sequence: &Sequence| {
+            let lanes = lane_centers(sequence);
+            lanes[1] - lanes[0]
+        };
+        assert!(gap(&long) > gap(&short));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse(&["A says hi"]).is_err());
+        assert!(parse(&["->>B: nobody"]).is_err());
+        assert!(parse(&["Note beside A: x"]).is_err());
+    }
+}
@@ -0,0 +1,445 @@
+/// SVG output for laid-out diagrams
+///
+/// Labels are written as glyph outline paths (Inter, with the math fallback font),
+/// since the SVG rasterizer has no font support. Shapes in layout-rs' default black
+/// on white are drawn in the palette's colors so diagrams follow the theme.
+use super::Palette;
+use crate::math::{px_scale, MathFonts};
+use ab_glyph::{Font, OutlineCurve, ScaleFont};
+use eframe::egui::Color32;
+use layout::core::color::Color;
+use layout::core::format::{ClipHandle, RenderBackend};
+use layout::core::geometry::Point;
+use layout::core::style::StyleAttr;
+use std::fmt::Write;
+
+/// Blank border around the drawing
+const MARGIN: f64 = 8.0;
+
+/// Line height of multi-line labels, in em
+const LINE_HEIGHT: f32 = 1.25;
+
+/// Arrowhead length and half width
+const HEAD_LENGTH: f64 = 9.0;
+const HEAD_HALF_WIDTH: f64 = 4.0;
+
+/// How an arrow ends
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Head {
+    None,
+    /// Filled triangle
+    Arrow,
+    /// Open chevron (asynchronous messages)
+    Open,
+    Cross,
+}
+
+/// Width and height of a (possibly multi-line) label at `size` pixels per em
+pub fn measure_text(text: &str, size: f32) -> (f64, f64) {
+    let fonts = MathFonts::get();
+    let width = text
+        .lines()
+        .map(|line| line_width(fonts, line, size))
+        .fold(0.0, f32::max);
+    let lines = text.lines().count().max(1);
+    (width as f64, (lines as f32 * size * LINE_HEIGHT) as f64)
+}
+
+fn line_width(fonts: &MathFonts, line: &str, size: f32) -> f32 {
+    line.chars()
+        .filter_map(|ch| fonts.text_glyph(ch))
+        .map(|(index, id)| {
+            let font = fonts.font(index);
+            font.as_scaled(px_scale(font, size)).h_advance(id)
+        })
+        .sum()
+}
+
+/// An SVG document being drawn; the view grows to fit everything drawn
+pub struct SvgCanvas {
+    palette: Palette,
+    content: String,
+    min: Point,
+    max: Point,
+}
+
+impl SvgCanvas {
+    pub fn new(palette: Palette) -> Self {
+        Self {
+            palette,
+            content: String::new(),
+            min: Point::splat(f64::INFINITY),
+            max: Point::splat(f64::NEG_INFINITY),
+        }
+    }
+
+    pub fn palette(&self) -> Palette {
+        self.palette
+    }
+
+    /// The complete document
+    pub fn finish(self) -> String {
+        let (min, max) = if self.min.x <= self.max.x {
+            (self.min, self.max)
+        } else {
+            (Point::zero(), Point::zero())
+        };
+        let (width, height) = (max.x - min.x + 2.0 * MARGIN, max.y - min.y + 2.0 * MARGIN);
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.0}\" height=\"{h:.0}\" \
+             viewBox=\"{:.1} {:.1} {w:.0} {h:.0}\">\n{}</svg>\n",
+            min.x - MARGIN,
+            min.y - MARGIN,
+            self.content,
+            w = width.ceil(),
+            h = height.ceil(),
+        )
+    }
+
+    fn grow(&mut self, x: f64, y: f64) {
+        self.min.x = self.min.x.min(x);
+        self.min.y = self.min.y.min(y);
+        self.max.x = self.max.x.max(x);
+        self.max.y = self.max.y.max(y);
+    }
+
+    pub fn rect(
+        &mut self,
+        (x, y): (f64, f64),
+        (w, h): (f64, f64),
+        rounded: f64,
+        fill: Option<Color32>,
+        stroke: Color32,
+    ) {
+        self.grow(x, y);
+        self.grow(x + w, y + h);
+        let _ = writeln!(
+            self.content,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" rx=\"{:.1}\" {} {} stroke-width=\"1.5\"/>",
+            x,
+            y,
+            w,
+            h,
+            rounded,
+            paint("fill", fill),
+            paint("stroke", Some(stroke)),
+        );
+    }
+
+    pub fn ellipse(
+        &mut self,
+        (cx, cy): (f64, f64),
+        (w, h): (f64, f64),
+        fill: Option<Color32>,
+        stroke: Color32,
+    ) {
+        self.grow(cx - w / 2.0, cy - h / 2.0);
+        self.grow(cx + w / 2.0, cy + h / 2.0);
+        let _ = writeln!(
+            self.content,
+            "<ellipse cx=\"{:.1}\" cy=\"{:.1}\" rx=\"{:.1}\" ry=\"{:.1}\" {} {} stroke-width=\"1.5\"/>",
+            cx,
+            cy,
+            w / 2.0,
+            h / 2.0,
+            paint("fill", fill),
+            paint("stroke", Some(stroke)),
+        );
+    }
+
+    /// Stroke an SVG path (`d` attribute), optionally dashed
+    pub fn stroke_path(&mut self, d: &str, color: Color32, width: f64, dashed: bool) {
+        let _ = writeln!(
+            self.content,
+            "<path d=\"{}\" fill=\"none\" {} stroke-width=\"{:.1}\"{}/>",
+            d,
+            paint("stroke", Some(color)),
+            width,
+            if dashed {
+                " stroke-dasharray=\"5,4\""
+            } else {
+                ""
+            },
+        );
+    }
+
+    /// Straight line through `points`
+    pub fn polyline(&mut self, points: &[(f64, f64)], color: Color32, dashed: bool) {
+        let mut d = String::new();
+        for (i, (x, y)) in points.iter().enumerate() {
+            self.grow(*x, *y);
+            let _ = write!(d, "{}{:.1} {:.1} ", if i == 0 { "M" } else { "L" }, x, y);
+        }
+        self.stroke_path(d.trim_end(), color, 1.5, dashed);
+    }
+
+    /// Arrow end at `tip`, pointing away from `from`
+    pub fn head(&mut self, tip: (f64, f64), from: (f64, f64), head: Head, color: Color32) {
+        let (dx, dy) = (tip.0 - from.0, tip.1 - from.1);
+        let length = (dx * dx + dy * dy).sqrt();
+        if length == 0.0 {
+            return;
+        }
+        let (ux, uy) = (dx / length, dy / length);
+        let back = (tip.0 - ux * HEAD_LENGTH, tip.1 - uy * HEAD_LENGTH);
+        let left = (back.0 - uy * HEAD_HALF_WIDTH, back.1 + ux * HEAD_HALF_WIDTH);
+        let right = (back.0 + uy * HEAD_HALF_WIDTH, back.1 - ux * HEAD_HALF_WIDTH);
+        match head {
+            Head::None => {}
+            Head::Arrow => {
+                let _ = writeln!(
+                    self.content,
+                    "<path d=\"M{:.1} {:.1} L{:.1} {:.1} L{:.1} {:.1} Z\" {}/>",
+                    tip.0,
+                    tip.1,
+                    left.0,
+                    left.1,
+                    right.0,
+                    right.1,
+                    paint("fill", Some(color)),
+                );
+            }
+            Head::Open => self.polyline(&[left, tip, right], color, false),
+            Head::Cross => {
+                let center = (tip.0 - ux * HEAD_HALF_WIDTH, tip.1 - uy * HEAD_HALF_WIDTH);
+                let (ax, ay) = (HEAD_HALF_WIDTH * (ux - uy), HEAD_HALF_WIDTH * (uy + ux));
+                let (bx, by) = (HEAD_HALF_WIDTH * (ux + uy), HEAD_HALF_WIDTH * (uy - ux));
+                self.polyline(
+                    &[
+                        (center.0 - ax, center.1 - ay),
+                        (center.0 + ax, center.1 + ay),
+                    ],
+                    color,
+                    false,
+                );
+                self.polyline(
+                    &[
+                        (center.0 - bx, center.1 - by),
+                        (center.0 + bx, center.1 + by),
+                    ],
+                    color,
+                    false,
+                );
+            }
+        }
+    }
+
+    /// Label centered on `center`, one row per line
+    pub fn text(&mut self, center: (f64, f64), text: &str, size: f32, color: Color32) {
+        let fonts = MathFonts::get();
+        let line_height = size * LINE_HEIGHT;
+        let lines: Vec<&str> = text.lines().collect();
+        let top = center.1 as f32 - line_height * lines.len() as f32 / 2.0;
+
+        let mut d = String::new();
+        for (row, line) in lines.iter().enumerate() {
+            let width = line_width(fonts, line, size);
+            let mut x = center.0 as f32 - width / 2.0;
+            let row_center = top + line_height * (row as f32 + 0.5);
+            let mut previous = None;
+            for (index, id) in line.chars().filter_map(|ch| fonts.text_glyph(ch)) {
+                let font = fonts.font(index);
+                let scaled = font.as_scaled(px_scale(font, size));
+                let baseline = row_center + (scaled.ascent() + scaled.descent()) / 2.0;
+                if let Some((previous_index, previous_id)) = previous {
+                    if previous_index == index {
+                        x += scaled.kern(previous_id, id);
+                    }
+                }
+                if let Some(outline) = font.outline(id) {
+                    let (h, v) = (scaled.h_scale_factor(), scaled.v_scale_factor());
+                    let map = |p: ab_glyph::Point| (x + p.x * h, baseline - p.y * v);
+                    outline_path(&mut d, &outline.curves, map);
+                }
+                x += scaled.h_advance(id);
+                previous = Some((index, id));
+            }
+            self.grow(center.0 - width as f64 / 2.0, row_center as f64);
+            self.grow(center.0 + width as f64 / 2.0, row_center as f64);
+        }
+        self.grow(center.0, top as f64);
+        self.grow(center.0, (top + line_height * lines.len() as f32) as f64);
+
+        if !d.is_empty() {
+            let _ = writeln!(
+                self.content,
+                "<path d=\"{}\" {}/>",
+                d.trim_end(),
+                paint("fill", Some(color))
+            );
+        }
+    }
+
+    /// layout-rs black strokes and text take the palette's stroke and text colors
+    fn line_color(&self, look: &StyleAttr, default: Color32) -> Color32 {
+        match to_color32(look.line_color) {
+            Some(Color32::BLACK) | None => default,
+            Some(color) => color,
+        }
+    }
+
+    /// layout-rs white fills take the palette's fill
+    fn fill_color(&self, look: &StyleAttr) -> Option<Color32> {
+        match look.fill_color.and_then(to_color32) {
+            Some(Color32::WHITE) => Some(self.palette.fill),
+            color => color,
+        }
+    }
+}
+
+impl RenderBackend for SvgCanvas {
+    fn draw_rect(&mut self, xy: Point, size: Point, look: &StyleAttr, _clip: Option<ClipHandle>) {
+        let stroke = self.line_color(look, self.palette.stroke);
+        let fill = self.fill_color(look);
+        self.rect(
+            (xy.x, xy.y),
+            (size.x, size.y),
+            look.rounded as f64,
+            fill,
+            stroke,
+        );
+    }
+
+    fn draw_line(&mut self, start: Point, stop: Point, look: &StyleAttr) {
+        let color = self.line_color(look, self.palette.stroke);
+        self.polyline(&[(start.x, start.y), (stop.x, stop.y)], color, false);
+    }
+
+    fn draw_circle(&mut self, xy: Point, size: Point, look: &StyleAttr) {
+        let stroke = self.line_color(look, self.palette.stroke);
+        let fill = self.fill_color(look);
+        self.ellipse((xy.x, xy.y), (size.x, size.y), fill, stroke);
+    }
+
+    fn draw_text(&mut self, xy: Point, text: &str, look: &StyleAttr) {
+        let color = self.line_color(look, self.palette.text);
+        self.text((xy.x, xy.y), text, look.font_size as f32, color);
+    }
+
+    fn draw_arrow(
+        &mut self,
+        path: &[(Point, Point)],
+        dashed: bool,
+        head: (bool, bool),
+        look: &StyleAttr,
+        text: &str,
+    ) {
+        // Cubic Bézier: path[0] is (start, exit control), later entries are
+        // (entry control, point)
+        if path.len() < 2 {
+            return;
+        }
+        let color = self.line_color(look, self.palette.stroke);
+        let mut d = format!(
+            "M{:.1} {:.1} C{:.1} {:.1}, {:.1} {:.1}, {:.1} {:.1}",
+            path[0].0.x,
+            path[0].0.y,
+            path[0].1.x,
+            path[0].1.y,
+            path[1].0.x,
+            path[1].0.y,
+            path[1].1.x,
+            path[1].1.y
+        );
+        for (control, point) in &path[2..] {
+            let _ = write!(
+                d,
+                " S{:.1} {:.1}, {:.1} {:.1}",
+                control.x, control.y, point.x, point.y
+            );
+        }
+        for (control, point) in path {
+            self.grow(control.x, control.y);
+            self.grow(point.x, point.y);
+        }
+        self.stroke_path(&d, color, look.line_width.max(1) as f64, dashed);
+
+        let point = |p: Point| (p.x, p.y);
+        if head.0 {
+            self.head(point(path[0].0), point(path[0].1), Head::Arrow, color);
+        }
+        if head.1 {
+            let (control, end) = path[path.len() - 1];
+            self.head(point(end), point(control), Head::Arrow, color);
+        }
+        if !text.is_empty() {
+            let middle = path[path.len() / 2].1;
+            let color = self.palette.text;
+            self.text((middle.x, middle.y), text, look.font_size as f32, color);
+        }
+    }
+
+    fn create_clip(&mut self, _xy: Point, _size: Point, _rounded_px: usize) -> ClipHandle {
+        // Clips are only used for record shapes' rounded corners; draw unclipped
+        0
+    }
+}
+
+/// Append glyph outline curves to a path, starting a new contour where they break
+fn outline_path(
+    d: &mut String,
+    curves: &[OutlineCurve],
+    map: impl Fn(ab_glyph::Point) -> (f32, f32),
+) {
+    let mut last: Option<ab_glyph::Point> = None;
+    for curve in curves {
+        let start = match curve {
+            OutlineCurve::Line(p, _) | OutlineCurve::Quad(p, _, _) | OutlineCurve::Cubic(p, ..) => {
+                *p
+            }
+        };
+        if last != Some(start) {
+            if last.is_some() {
+                d.push_str("Z ");
+            }
+            let (x, y) = map(start);
+            let _ = write!(d, "M{:.2} {:.2} ", x, y);
+        }
+        last = Some(match curve {
+            OutlineCurve::Line(_, p) => {
+                let (x, y) = map(*p);
+                let _ = write!(d, "L{:.2} {:.2} ", x, y);
+                *p
+            }
+            OutlineCurve::Quad(_, c, p) => {
+                let ((cx, cy), (x, y)) = (map(*c), map(*p));
+                let _ = write!(d, "Q{:.2} {:.2} {:.2} {:.2} ", cx, cy, x, y);
+                *p
+            }
+            OutlineCurve::Cubic(_, c1, c2, p) => {
+                let ((ax, ay), (bx, by), (x, y)) = (map(*c1), map(*c2), map(*p));
+                let _ = write!(
+                    d,
+                    "C{:.2} {:.2} {:.2} {:.2} {:.2} {:.2} ",
+                    ax, ay, bx, by, x, y
+                );
+                *p
+            }
+        });
+    }
+    if last.is_some() {
+        d.push_str("Z ");
+    }
+}
+
+/// A fill or stroke attribute (`none` when absent or fully transparent)
+fn paint(attribute: &str, color: Option<Color32>) -> String {
+    match color.filter(|c| c.a() > 0) {
+        Some(color) => {
+            let [r, g, b, a] = color.to_srgba_unmultiplied();
+            let mut paint = format!("{}=\"#{:02x}{:02x}{:02x}\"", attribute, r, g, b);
+            if a < 255 {
+                let _ = write!(paint, " {}-opacity=\"{:.3}\"", attribute, a as f32 / 255.0);
+            }
+            paint
+        }
+        None => format!("{}=\"none\"", attribute),
+    }
+}
+
+/// layout-rs colors are RGBA, only exposed in web notation
+fn to_color32(color: Color) -> Option<Color32> {
+    let rgba = u32::from_str_radix(color.to_web_color().trim_start_matches('#'), 16).ok()?;
+    let [r, g, b, a] = rgba.to_be_bytes();
+    Some(Color32::from_rgba_unmultiplied(r, g, b, a))
+}
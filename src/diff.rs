@@ -0,0 +1,588 @@
+/// Line diff between two Markdown revisions (`mirror --diff OLD NEW`)
+///
+/// The NEW file is the reviewed document; hunks map its lines back to OLD so
+/// comments can record which side of a change they target.
+use crate::git::{read_at_revision, resolve_revision, BaseRevision};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use std::path::{Path, PathBuf};
+
+/// Unchanged lines shown around each change
+pub const CONTEXT_LINES: usize = 3;
+
+/// How a diff line changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineChange {
+    Context,
+    Added,
+    Removed,
+}
+
+/// A single line of a hunk
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffLine {
+    pub change: LineChange,
+    /// Line number in OLD (1-indexed, None for added lines)
+    pub old_line: Option<usize>,
+    /// Line number in NEW (1-indexed, None for removed lines)
+    pub new_line: Option<usize>,
+    pub text: String,
+}
+
+impl DiffLine {
+    /// Unified diff prefix: ' ', '+' or '-'
+    pub fn prefix(&self) -> char {
+        match self.change {
+            LineChange::Context => ' ',
+            LineChange::Added => '+',
+            LineChange::Removed => '-',
+        }
+    }
+}
+
+/// A group of changes with surrounding context (ranges are 1-indexed, unified-diff style)
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_len: usize,
+    pub new_start: usize,
+    pub new_len: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+/// What a comment on a hunk targets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HunkChange {
+    /// Only added lines
+    Added,
+    /// Removed lines (and no added ones)
+    Removed,
+    /// Both removed and added lines
+    Modified,
+    /// Unchanged lines around a change
+    Context,
+}
+
+/// Diff-hunk metadata stored with a review comment
+///
+/// The start/len fields are the whole hunk; `old_lines`/`new_lines` are the lines the
+/// comment selected on each side (1-indexed, inclusive; None if it selected none).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HunkContext {
+    pub change: HunkChange,
+    pub old_start: usize,
+    pub old_len: usize,
+    pub new_start: usize,
+    pub new_len: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_lines: Option<(usize, usize)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_lines: Option<(usize, usize)>,
+}
+
+impl HunkContext {
+    /// The selection includes removed lines (its text is only in OLD)
+    pub fn has_removed(&self) -> bool {
+        matches!(self.change, HunkChange::Removed | HunkChange::Modified)
+    }
+}
+
+/// Rows of one hunk selected in the diff view (indices into `DiffHunk::lines`, inclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RowSelection {
+    pub hunk: usize,
+    pub first: usize,
+    pub last: usize,
+}
+
+/// First and last line number among `numbers` (None if there are none)
+fn span(numbers: impl Iterator<Item = usize>) -> Option<(usize, usize)> {
+    numbers.fold(None, |span, n| match span {
+        None => Some((n, n)),
+        Some((start, end)) => Some((start.min(n), end.max(n))),
+    })
+}
+
+impl DiffHunk {
+    /// Unified diff header, e.g. "@@ -3,4 +3,6 @@"
+    pub fn header(&self) -> String {
+        format!(
+            "@@ -{},{} +{},{} @@",
+            self.old_start, self.old_len, self.new_start, self.new_len
+        )
+    }
+
+    /// NEW line range covered by the hunk (None if it has no NEW lines)
+    pub fn new_range(&self) -> Option<(usize, usize)> {
+        (self.new_len > 0).then(|| (self.new_start, self.new_start + self.new_len - 1))
+    }
+
+    /// The selection [line_start..=line_end] covers the entire hunk
+    pub fn is_whole(&self, line_start: usize, line_end: usize) -> bool {
+        self.new_range() == Some((line_start, line_end))
+    }
+
+    /// Classify a selection of NEW lines within this hunk
+    ///
+    /// Selecting the whole hunk targets its removed lines too; other NEW line
+    /// selections only contain added and context lines (see `rows_context`).
+    pub fn context_for(&self, line_start: usize, line_end: usize) -> HunkContext {
+        let whole = self.is_whole(line_start, line_end);
+        let selected: Vec<&DiffLine> = self
+            .lines
+            .iter()
+            .filter(|line| match line.new_line {
+                Some(n) => n >= line_start && n <= line_end,
+                None => whole,
+            })
+            .collect();
+        self.context_of(&selected)
+    }
+
+    /// Classify the selected rows `first..=last` (removed lines included)
+    pub fn rows_context(&self, first: usize, last: usize) -> HunkContext {
+        let selected: Vec<&DiffLine> = self.rows(first, last).iter().collect();
+        self.context_of(&selected)
+    }
+
+    /// Rows `first..=last`, clamped to the hunk
+    fn rows(&self, first: usize, last: usize) -> &[DiffLine] {
+        let last = last.min(self.lines.len().saturating_sub(1));
+        self.lines.get(first..=last).unwrap_or_default()
+    }
+
+    /// NEW lines a selection of rows is anchored to: the NEW lines it covers, or for
+    /// removed lines only, the NEW line next to the deletion
+    pub fn anchor_lines(&self, first: usize, last: usize) -> (usize, usize) {
+        if let Some(lines) = span(self.rows(first, last).iter().filter_map(|l| l.new_line)) {
+            return lines;
+        }
+        let after = self.lines.iter().skip(last + 1).find_map(|l| l.new_line);
+        let before = self.lines[..first.min(self.lines.len())]
+            .iter()
+            .rev()
+            .find_map(|l| l.new_line);
+        let line = after.or(before).unwrap_or(self.new_start).max(1);
+        (line, line)
+    }
+
+    /// Selected rows as unified diff text
+    pub fn rows_text(&self, first: usize, last: usize) -> String {
+        self.rows(first, last)
+            .iter()
+            .map(|line| format!("{}{}", line.prefix(), line.text))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn context_of(&self, selected: &[&DiffLine]) -> HunkContext {
+        let (mut added, mut removed) = (false, false);
+        for line in selected {
+            match line.change {
+                LineChange::Added => added = true,
+                LineChange::Removed => removed = true,
+                LineChange::Context => {}
+            }
+        }
+
+        let change = match (added, removed) {
+            (true, true) => HunkChange::Modified,
+            (true, false) => HunkChange::Added,
+            (false, true) => HunkChange::Removed,
+            (false, false) => HunkChange::Context,
+        };
+
+        HunkContext {
+            change,
+            old_start: self.old_start,
+            old_len: self.old_len,
+            new_start: self.new_start,
+            new_len: self.new_len,
+            old_lines: span(selected.iter().filter_map(|line| line.old_line)),
+            new_lines: span(selected.iter().filter_map(|line| line.new_line)),
+        }
+    }
+}
+
+/// Compute hunks turning `old` into `new`
+pub fn compute_hunks(old: &str, new: &str) -> Vec<DiffHunk> {
+    let diff = TextDiff::from_lines(old, new);
+
+    diff.grouped_ops(CONTEXT_LINES)
+        .iter()
+        .filter_map(|group| {
+            let (first, last) = (group.first()?, group.last()?);
+            let old_range = first.old_range().start..last.old_range().end;
+            let new_range = first.new_range().start..last.new_range().end;
+
+            let lines = group
+                .iter()
+                .flat_map(|op| diff.iter_changes(op))
+                .map(|change| DiffLine {
+                    change: match change.tag() {
+                        ChangeTag::Equal => LineChange::Context,
+                        ChangeTag::Insert => LineChange::Added,
+                        ChangeTag::Delete => LineChange::Removed,
+                    },
+                    old_line: change.old_index().map(|i| i + 1),
+                    new_line: change.new_index().map(|i| i + 1),
+                    text: change.value().trim_end_matches(['\n', '\r']).to_string(),
+                })
+                .collect();
+
+            Some(DiffHunk {
+                old_start: old_range.start + 1,
+                old_len: old_range.len(),
+                new_start: new_range.start + 1,
+                new_len: new_range.len(),
+                lines,
+            })
+        })
+        .collect()
+}
+
+/// Diff presentation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiffLayout {
+    #[default]
+    Unified,
+    SideBySide,
+}
+
+/// Diff state for a document reviewed against an older revision
+pub struct DiffView {
+    pub old_path: PathBuf,
+    pub old_source: String,
+    pub hunks: Vec<DiffHunk>,
+    pub layout: DiffLayout,
+    /// Rows picked in the diff view (they can include removed lines)
+    pub selected_rows: Option<RowSelection>,
+}
+
+impl DiffView {
+    pub fn new(old_path: PathBuf, old_source: String, new_source: &str) -> Self {
+        let hunks = compute_hunks(&old_source, new_source);
+        Self {
+            old_path,
+            old_source,
+            hunks,
+            layout: DiffLayout::default(),
+            selected_rows: None,
+        }
+    }
+
+    /// Recompute hunks after NEW changed (live reload)
+    pub fn refresh(&mut self, new_source: &str) {
+        self.hunks = compute_hunks(&self.old_source, new_source);
+        self.selected_rows = None;
+    }
+
+    /// Picked rows, if the line selection is still the one they anchor to
+    /// (keyboard or other line selections replace them)
+    pub fn row_selection(&self, line_range: Option<(usize, usize)>) -> Option<RowSelection> {
+        let rows = self.selected_rows?;
+        let hunk = self.hunks.get(rows.hunk)?;
+        (line_range == Some(hunk.anchor_lines(rows.first, rows.last))).then_some(rows)
+    }
+
+    /// Hunk context of the picked rows (see `row_selection`)
+    pub fn rows_context(&self, line_range: Option<(usize, usize)>) -> Option<HunkContext> {
+        let rows = self.row_selection(line_range)?;
+        Some(self.hunks[rows.hunk].rows_context(rows.first, rows.last))
+    }
+
+    /// Unified diff text of the lines a context selected, for selections with removed
+    /// lines (None otherwise, or if its hunk no longer exists)
+    pub fn removed_text(&self, context: &HunkContext) -> Option<String> {
+        if !context.has_removed() {
+            return None;
+        }
+        let hunk = self
+            .hunks
+            .iter()
+            .find(|h| h.old_start == context.old_start && h.new_start == context.new_start)?;
+        let within = |n: Option<usize>, lines: Option<(usize, usize)>| match (n, lines) {
+            (Some(n), Some((start, end))) => n >= start && n <= end,
+            _ => false,
+        };
+        let rows: Vec<usize> = (0..hunk.lines.len())
+            .filter(|&i| {
+                let line = &hunk.lines[i];
+                within(line.old_line, context.old_lines) || within(line.new_line, context.new_lines)
+            })
+            .collect();
+        Some(hunk.rows_text(*rows.first()?, *rows.last()?))
+    }
+
+    /// Hunk containing NEW lines [line_start..=line_end] (None outside any hunk)
+    pub fn hunk_at(&self, line_start: usize, line_end: usize) -> Option<&DiffHunk> {
+        self.hunks.iter().find(|hunk| {
+            hunk.new_range()
+                .is_some_and(|(start, end)| line_start >= start && line_end <= end)
+        })
+    }
+
+    /// Hunk context for a comment on NEW lines [line_start..=line_end]
+    pub fn context_for(&self, line_start: usize, line_end: usize) -> Option<HunkContext> {
+        self.hunk_at(line_start, line_end)
+            .map(|hunk| hunk.context_for(line_start, line_end))
+    }
+}
+
+/// Kind of change shown by a gutter marker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    /// Lines were deleted just before `line_start`
+    Removed,
+}
+
+/// A changed range of NEW lines (1-indexed, inclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangeMarker {
+    pub kind: ChangeKind,
+    pub line_start: usize,
+    pub line_end: usize,
+}
+
+impl ChangeMarker {
+    /// Marker touches lines [line_start..=line_end]
+    pub fn overlaps(&self, line_start: usize, line_end: usize) -> bool {
+        self.line_start <= line_end && line_start <= self.line_end
+    }
+}
+
+/// Collapse hunks into changed NEW line ranges (one marker per run of changed lines)
+pub fn change_markers(hunks: &[DiffHunk]) -> Vec<ChangeMarker> {
+    let mut markers = Vec::new();
+
+    for hunk in hunks {
+        let mut lines = hunk.lines.iter().peekable();
+        while let Some(line) = lines.next() {
+            if line.change == LineChange::Context {
+                continue;
+            }
+
+            // Gather the run of consecutive changed lines
+            let mut removed = line.change == LineChange::Removed;
+            let mut added_lines: Vec<usize> = line.new_line.into_iter().collect();
+            while let Some(next) = lines.next_if(|l| l.change != LineChange::Context) {
+                removed |= next.change == LineChange::Removed;
+                added_lines.extend(next.new_line);
+            }
+
+            let marker = match (added_lines.first(), added_lines.last()) {
+                (Some(&line_start), Some(&line_end)) => ChangeMarker {
+                    kind: if removed {
+                        ChangeKind::Modified
+                    } else {
+                        ChangeKind::Added
+                    },
+                    line_start,
+                    line_end,
+                },
+                // Pure deletion: mark the line that now follows it
+                _ => {
+                    let line = lines
+                        .peek()
+                        .and_then(|l| l.new_line)
+                        .unwrap_or(hunk.new_start + hunk.new_len)
+                        .max(1);
+                    ChangeMarker {
+                        kind: ChangeKind::Removed,
+                        line_start: line,
+                        line_end: line,
+                    }
+                }
+            };
+            markers.push(marker);
+        }
+    }
+
+    markers
+}
+
+/// Working-copy changes relative to a git revision (`--since REV`)
+pub struct RevisionDiff {
+    pub base: BaseRevision,
+    pub base_source: String,
+    pub markers: Vec<ChangeMarker>,
+}
+
+impl RevisionDiff {
+    pub fn new(base: BaseRevision, base_source: String, source: &str) -> Self {
+        let markers = change_markers(&compute_hunks(&base_source, source));
+        Self {
+            base,
+            base_source,
+            markers,
+        }
+    }
+
+    /// Read the file at `rev` from git and diff it against `source`
+    /// (a file that didn't exist at `rev` shows as entirely added)
+    pub fn load(file_path: &Path, rev: &str, source: &str) -> Result<Self> {
+        let base = resolve_revision(file_path, rev)?;
+        let base_source = read_at_revision(file_path, &base.commit)?.unwrap_or_default();
+        Ok(Self::new(base, base_source, source))
+    }
+
+    /// Recompute markers after the working copy changed (live reload)
+    pub fn refresh(&mut self, source: &str) {
+        self.markers = change_markers(&compute_hunks(&self.base_source, source));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = "# Title\n\nKeep\n\nRemove me\n\nTail\n";
+    const NEW: &str = "# Title\n\nKeep\n\nAdded line\n\nTail\n";
+
+    #[test]
+    fn test_identical_sources_have_no_hunks() {
+        assert!(compute_hunks(OLD, OLD).is_empty());
+    }
+
+    #[test]
+    fn test_hunk_ranges_and_lines() {
+        let hunks = compute_hunks(OLD, NEW);
+        assert_eq!(hunks.len(), 1);
+
+        let hunk = &hunks[0];
+        assert_eq!(hunk.header(), "@@ -2,6 +2,6 @@");
+
+        let removed: Vec<_> = hunk
+            .lines
+            .iter()
+            .filter(|l| l.change == LineChange::Removed)
+            .collect();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].text, "Remove me");
+        assert_eq!(removed[0].old_line, Some(5));
+        assert_eq!(removed[0].new_line, None);
+    }
+
+    #[test]
+    fn test_context_classification() {
+        let view = DiffView::new(PathBuf::from("OLD.md"), OLD.to_string(), NEW);
+
+        // Added line only
+        assert_eq!(
+            view.context_for(5, 5).map(|c| c.change),
+            Some(HunkChange::Added)
+        );
+        // Unchanged line inside the hunk
+        assert_eq!(
+            view.context_for(3, 3).map(|c| c.change),
+            Some(HunkChange::Context)
+        );
+        // Whole hunk covers the removal too
+        assert_eq!(
+            view.context_for(2, 7).map(|c| c.change),
+            Some(HunkChange::Modified)
+        );
+        // Outside any hunk
+        assert_eq!(view.context_for(1, 1), None);
+    }
+
+    #[test]
+    fn test_pure_removal_whole_hunk() {
+        let old = "one\ntwo\nthree\n";
+        let new = "one\nthree\n";
+        let view = DiffView::new(PathBuf::from("OLD.md"), old.to_string(), new);
+
+        let (start, end) = view.hunks[0].new_range().unwrap();
+        let context = view.context_for(start, end).unwrap();
+        assert_eq!(context.change, HunkChange::Removed);
+        assert_eq!((context.old_start, context.old_len), (1, 3));
+        assert_eq!(
+            (context.old_lines, context.new_lines),
+            (Some((1, 3)), Some((1, 2)))
+        );
+        assert_eq!(view.hunks[0].rows_text(0, 2), " one\n-two\n three");
+    }
+
+    #[test]
+    fn test_removed_rows_selection() {
+        let old = "one\ntwo\nthree\nfour\n";
+        let new = "one\nfour\n";
+        let mut view = DiffView::new(PathBuf::from("OLD.md"), old.to_string(), new);
+        let hunk = &view.hunks[0];
+
+        // Row 2 is the second removed line ("three")
+        assert_eq!(hunk.lines[2].text, "three");
+        let context = hunk.rows_context(2, 2);
+        assert_eq!(context.change, HunkChange::Removed);
+        assert_eq!((context.old_lines, context.new_lines), (Some((3, 3)), None));
+        // Anchored to the NEW line after the deletion
+        assert_eq!(hunk.anchor_lines(2, 2), (2, 2));
+        assert_eq!(view.removed_text(&context).as_deref(), Some("-three"));
+
+        // The rows only count while the line selection is still theirs
+        view.selected_rows = Some(RowSelection {
+            hunk: 0,
+            first: 1,
+            last: 2,
+        });
+        assert_eq!(
+            view.rows_context(Some((2, 2))).map(|c| c.old_lines),
+            Some(Some((2, 3)))
+        );
+        assert_eq!(view.rows_context(Some((1, 1))), None);
+    }
+
+    #[test]
+    fn test_rows_context_records_selected_spans() {
+        let old = "a\nb\nc\n";
+        let new = "a\nB\nc\n";
+        let hunk = &compute_hunks(old, new)[0];
+        // Rows: " a", "-b", "+B", " c"
+        let context = hunk.rows_context(1, 2);
+        assert_eq!(context.change, HunkChange::Modified);
+        assert_eq!(
+            (context.old_lines, context.new_lines),
+            (Some((2, 2)), Some((2, 2)))
+        );
+        assert_eq!((context.old_start, context.old_len), (1, 3));
+
+        let context = hunk.rows_context(2, 3);
+        assert_eq!(context.change, HunkChange::Added);
+        assert_eq!(
+            (context.old_lines, context.new_lines),
+            (Some((3, 3)), Some((2, 3)))
+        );
+    }
+
+    #[test]
+    fn test_change_markers() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nB\nc\nd\ne\nf\ng\ni\nj\nk\n";
+        let markers = change_markers(&compute_hunks(old, new));
+
+        assert_eq!(
+            markers,
+            vec![
+                ChangeMarker {
+                    kind: ChangeKind::Modified,
+                    line_start: 2,
+                    line_end: 2
+                },
+                ChangeMarker {
+                    kind: ChangeKind::Removed,
+                    line_start: 8,
+                    line_end: 8
+                },
+                ChangeMarker {
+                    kind: ChangeKind::Added,
+                    line_start: 10,
+                    line_end: 10
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,158 @@
+/// Read base revisions of reviewed files from the local git repository (`--since REV`)
+///
+/// Shells out to the `git` CLI in the file's directory; no network access.
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+/// Git revision a document is reviewed against
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaseRevision {
+    /// Revision as given on the command line (e.g. "HEAD~1")
+    pub rev: String,
+    /// Resolved commit SHA
+    pub commit: String,
+}
+
+/// Run git in `dir`, returning stdout (None if git exits unsuccessfully)
+fn git(dir: &Path, args: &[&str]) -> Result<Option<String>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git (is it installed?)")?;
+
+    if !output.status.success() {
+        return Ok(None);
+    }
+    String::from_utf8(output.stdout)
+        .map(Some)
+        .context("git output is not valid UTF-8")
+}
+
+/// Directory and `./name` path git should use for a file
+fn locate(file_path: &Path) -> Result<(&Path, String)> {
+    let name = file_path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .with_context(|| format!("Invalid file path: {:?}", file_path))?;
+    let dir = match file_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    Ok((dir, format!("./{}", name)))
+}
+
+/// Resolve `rev` to a commit in the repository containing `file_path`
+///
+/// Revisions starting with `-` are rejected so they can't be read as git options.
+pub fn resolve_revision(file_path: &Path, rev: &str) -> Result<BaseRevision> {
+    if rev.starts_with('-') {
+        bail!(
+            "Invalid git revision {:?}: revisions can't start with '-'",
+            rev
+        );
+    }
+    let (dir, _) = locate(file_path)?;
+    let spec = format!("{}^{{commit}}", rev);
+
+    let args = [
+        "rev-parse",
+        "--verify",
+        "--quiet",
+        "--end-of-options",
+        &spec,
+    ];
+    match git(dir, &args)? {
+        Some(commit) => Ok(BaseRevision {
+            rev: rev.to_string(),
+            commit: commit.trim().to_string(),
+        }),
+        None => bail!("Unknown git revision {:?} for {:?}", rev, file_path),
+    }
+}
+
+/// File contents at `commit` (None if the file didn't exist at that revision)
+pub fn read_at_revision(file_path: &Path, commit: &str) -> Result<Option<String>> {
+    let (dir, path) = locate(file_path)?;
+    let object = format!("{}:{}", commit, path);
+
+    if git(dir, &["cat-file", "-e", &object])?.is_none() {
+        return Ok(None);
+    }
+    git(dir, &["show", &object])?
+        .with_context(|| format!("Failed to read {} from git", object))
+        .map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn run(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    /// Repo with SPEC.md committed twice ("v1", then "v2")
+    fn repo_with_history() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        run(dir, &["init", "--quiet"]);
+        fs::write(dir.join("SPEC.md"), "v1\n").unwrap();
+        run(dir, &["add", "SPEC.md"]);
+        run(dir, &["commit", "--quiet", "-m", "v1"]);
+        fs::write(dir.join("SPEC.md"), "v2\n").unwrap();
+        run(dir, &["commit", "--quiet", "-am", "v2"]);
+        temp_dir
+    }
+
+    #[test]
+    fn test_read_previous_revision() {
+        let temp_dir = repo_with_history();
+        let file_path = temp_dir.path().join("SPEC.md");
+
+        let base = resolve_revision(&file_path, "HEAD~1").unwrap();
+        assert_eq!(base.rev, "HEAD~1");
+        assert_eq!(base.commit.len(), 40);
+
+        let content = read_at_revision(&file_path, &base.commit).unwrap();
+        assert_eq!(content.as_deref(), Some("v1\n"));
+    }
+
+    #[test]
+    fn test_file_missing_at_revision() {
+        let temp_dir = repo_with_history();
+        let file_path = temp_dir.path().join("NEW.md");
+        fs::write(&file_path, "new\n").unwrap();
+
+        let base = resolve_revision(&file_path, "HEAD").unwrap();
+        assert_eq!(read_at_revision(&file_path, &base.commit).unwrap(), None);
+    }
+
+    #[test]
+    fn test_unknown_revision() {
+        let temp_dir = repo_with_history();
+        let file_path = temp_dir.path().join("SPEC.md");
+        assert!(resolve_revision(&file_path, "no-such-rev").is_err());
+    }
+
+    #[test]
+    fn test_option_like_revision_rejected() {
+        let temp_dir = repo_with_history();
+        let file_path = temp_dir.path().join("SPEC.md");
+        let err = resolve_revision(&file_path, "--output=/tmp/x").unwrap_err();
+        assert!(err.to_string().contains("can't start with '-'"));
+    }
+}
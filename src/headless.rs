@@ -0,0 +1,385 @@
+/// Scripted headless review mode (no display required)
+///
+/// Drives the same `Document` review actions as the GUI, so scripts exercise the
+/// real storage routing (Hegel `.hegel/reviews.json` vs standalone `.review.N`).
+///
+/// Script format (JSON array, actions apply to the current document):
+/// ```json
+/// [
+///   {"action": "switch", "file": "SPEC.md"},
+///   {"action": "select", "start_line": 3, "end_line": 5},
+///   {"action": "comment", "text": "Clarify this constraint"},
+///   {"action": "submit"},
+///   {"action": "switch", "file": "PLAN.md"},
+///   {"action": "lgtm"}
+/// ]
+/// ```
+///
+/// Rejections may carry a reason:
+/// ```json
+/// {"action": "nope", "reason": "Scope is unclear"}
+/// ```
+///
+/// Selections may be column-precise (1-indexed, end exclusive):
+/// ```json
+/// {"action": "select", "start_line": 3, "start_col": 7, "end_line": 3, "end_col": 16}
+/// ```
+///
+/// Each document takes one verdict (`submit`, `lgtm` or `nope`); a second one fails the
+/// script. Verdicts written before a failing step stay on disk.
+use crate::models::Document;
+use crate::parsing::LineOffsets;
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::io::Read;
+
+/// A single scripted review action
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ScriptAction {
+    /// Make the document with this filename the current document
+    Switch { file: String },
+    /// Select a line range (1-indexed, inclusive), optionally narrowed to columns
+    Select {
+        start_line: usize,
+        end_line: usize,
+        start_col: Option<usize>,
+        end_col: Option<usize>,
+    },
+    /// Queue a comment on the current selection
+    Comment { text: String },
+    /// Write queued comments as a review
+    Submit,
+    /// Approve the current document
+    Lgtm,
+    /// Reject the current document (more discussion needed), with optional reason
+    Nope { reason: Option<String> },
+}
+
+/// Load a script from a file path, or from stdin if path is None or "-"
+pub fn load_script(path: Option<&str>) -> Result<Vec<ScriptAction>> {
+    let content = match path {
+        Some(path) if path != "-" => std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read script: {}", path))?,
+        _ => {
+            let mut content = String::new();
+            std::io::stdin()
+                .read_to_string(&mut content)
+                .context("Failed to read script from stdin")?;
+            content
+        }
+    };
+
+    parse_script(&content)
+}
+
+/// Parse a JSON action script
+pub fn parse_script(content: &str) -> Result<Vec<ScriptAction>> {
+    serde_json::from_str(content).context("Failed to parse review script")
+}
+
+/// Run script actions against the loaded documents
+pub fn run_script(documents: &mut [Document], actions: &[ScriptAction]) -> Result<()> {
+    if documents.is_empty() {
+        bail!("No documents to review");
+    }
+
+    let mut current = 0;
+
+    for (step, action) in actions.iter().enumerate() {
+        run_action(documents, &mut current, action)
+            .with_context(|| format!("Script step {} failed: {:?}", step + 1, action))?;
+    }
+
+    Ok(())
+}
+
+fn run_action(
+    documents: &mut [Document],
+    current: &mut usize,
+    action: &ScriptAction,
+) -> Result<()> {
+    match action {
+        ScriptAction::Switch { file } => {
+            *current = documents
+                .iter()
+                .position(|d| &d.filename == file)
+                .with_context(|| format!("No document named {}", file))?;
+        }
+        ScriptAction::Select {
+            start_line,
+            end_line,
+            start_col,
+            end_col,
+        } => {
+            let doc = &mut documents[*current];
+            let line_count = doc.source.lines().count();
+            for &line in &[*start_line, *end_line] {
+                if line == 0 || line > line_count {
+                    bail!(
+                        "Line {} out of range for {} ({} lines)",
+                        line,
+                        doc.filename,
+                        line_count
+                    );
+                }
+            }
+            match (start_col, end_col) {
+                (Some(start_col), Some(end_col)) => {
+                    let offsets = LineOffsets::new(&doc.source);
+                    let start = offsets.line_col_to_byte(&doc.source, *start_line, *start_col);
+                    let end = offsets.line_col_to_byte(&doc.source, *end_line, *end_col);
+                    doc.selection.select_bytes(&doc.source, start, end);
+                }
+                (None, None) => doc.selection.select_lines(*start_line, *end_line),
+                _ => bail!("select needs both start_col and end_col, or neither"),
+            }
+        }
+        ScriptAction::Comment { text } => {
+            if !documents[*current].add_comment(text.clone()) {
+                bail!("Comment requires a selection and non-empty text");
+            }
+        }
+        ScriptAction::Submit => {
+            ensure_undecided(&documents[*current])?;
+            documents[*current].submit_review()?;
+        }
+        ScriptAction::Lgtm => {
+            ensure_undecided(&documents[*current])?;
+            documents[*current].approve()?;
+        }
+        ScriptAction::Nope { reason } => {
+            ensure_undecided(&documents[*current])?;
+            documents[*current].reject(reason.clone())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A document takes one verdict, as in the GUI (decided documents leave the review)
+fn ensure_undecided(doc: &Document) -> Result<()> {
+    if doc.approved {
+        bail!("{} already decided ({:?})", doc.filename, doc.verdict);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Verdict;
+    use crate::storage::{read_hegel_reviews, ProjectType};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_document(temp_dir: &TempDir, name: &str, project_type: ProjectType) -> Document {
+        let source = "# Title\n\nFirst paragraph\n\nSecond paragraph\n";
+        let file_path = temp_dir.path().join(name);
+        fs::write(&file_path, source).unwrap();
+
+        Document::new(
+            name.to_string(),
+            source.to_string(),
+            temp_dir.path().to_path_buf(),
+            file_path,
+            temp_dir.path().to_path_buf(),
+            Some("session123".to_string()),
+            project_type,
+        )
+    }
+
+    #[test]
+    fn test_parse_script() {
+        let script = r#"[
+            {"action": "switch", "file": "SPEC.md"},
+            {"action": "select", "start_line": 3, "end_line": 5},
+            {"action": "comment", "text": "Clarify"},
+            {"action": "submit"},
+            {"action": "lgtm"},
+            {"action": "nope"}
+        ]"#;
+
+        let actions = parse_script(script).unwrap();
+        assert_eq!(actions.len(), 6);
+        assert_eq!(
+            actions[1],
+            ScriptAction::Select {
+                start_line: 3,
+                end_line: 5,
+                start_col: None,
+                end_col: None,
+            }
+        );
+        assert_eq!(actions[3], ScriptAction::Submit);
+    }
+
+    #[test]
+    fn test_parse_script_unknown_action() {
+        assert!(parse_script(r#"[{"action": "explode"}]"#).is_err());
+    }
+
+    #[test]
+    fn test_standalone_review_script() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut docs = vec![create_document(
+            &temp_dir,
+            "SPEC.md",
+            ProjectType::Standalone,
+        )];
+
+        let actions = parse_script(
+            r#"[
+                {"action": "select", "start_line": 3, "end_line": 3},
+                {"action": "comment", "text": "Too vague"},
+                {"action": "submit"}
+            ]"#,
+        )
+        .unwrap();
+
+        run_script(&mut docs, &actions).unwrap();
+
+        let review_file = temp_dir.path().join("SPEC.review.1");
+        assert!(review_file.exists());
+        assert_eq!(docs[0].verdict, Verdict::Reviewed);
+        assert_eq!(docs[0].review_path.as_ref(), Some(&review_file));
+
+        let content = fs::read_to_string(&review_file).unwrap();
+        assert!(content.contains("Too vague"));
+        assert!(content.contains("First paragraph"));
+    }
+
+    #[test]
+    fn test_column_precise_comment() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut docs = vec![create_document(
+            &temp_dir,
+            "SPEC.md",
+            ProjectType::Standalone,
+        )];
+
+        // "First paragraph" on line 3 - select just "paragraph"
+        let actions = parse_script(
+            r#"[
+                {"action": "select", "start_line": 3, "start_col": 7, "end_line": 3, "end_col": 16},
+                {"action": "comment", "text": "Which one?"}
+            ]"#,
+        )
+        .unwrap();
+
+        run_script(&mut docs, &actions).unwrap();
+
+        let data = docs[0].comment_data();
+        assert_eq!(
+            data[0],
+            (
+                "paragraph".to_string(),
+                "Which one?".to_string(),
+                3,
+                7,
+                3,
+                16
+            )
+        );
+    }
+
+    #[test]
+    fn test_hegel_multi_document_script() {
+        let temp_dir = TempDir::new().unwrap();
+        let hegel_dir = temp_dir.path().join(".hegel");
+        fs::create_dir(&hegel_dir).unwrap();
+        let project_type = ProjectType::Hegel {
+            root: hegel_dir.clone(),
+        };
+
+        let mut docs = vec![
+            create_document(&temp_dir, "SPEC.md", project_type.clone()),
+            create_document(&temp_dir, "PLAN.md", project_type),
+        ];
+
+        let actions = parse_script(
+            r#"[
+                {"action": "select", "start_line": 1, "end_line": 3},
+                {"action": "comment", "text": "Needs scope"},
+                {"action": "submit"},
+                {"action": "switch", "file": "PLAN.md"},
+                {"action": "lgtm"}
+            ]"#,
+        )
+        .unwrap();
+
+        run_script(&mut docs, &actions).unwrap();
+
+        assert_eq!(docs[0].verdict, Verdict::Reviewed);
+        assert_eq!(docs[1].verdict, Verdict::Lgtm);
+
+        let reviews = read_hegel_reviews(&hegel_dir).unwrap();
+        assert_eq!(reviews.get("SPEC.md").unwrap()[0].comments.len(), 1);
+        assert_eq!(reviews.get("PLAN.md").unwrap()[0].comments.len(), 0);
+    }
+
+    #[test]
+    fn test_comment_without_selection_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut docs = vec![create_document(
+            &temp_dir,
+            "SPEC.md",
+            ProjectType::Standalone,
+        )];
+
+        let actions = parse_script(r#"[{"action": "comment", "text": "Orphan"}]"#).unwrap();
+        assert!(run_script(&mut docs, &actions).is_err());
+    }
+
+    #[test]
+    fn test_select_out_of_range_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut docs = vec![create_document(
+            &temp_dir,
+            "SPEC.md",
+            ProjectType::Standalone,
+        )];
+
+        let actions =
+            parse_script(r#"[{"action": "select", "start_line": 1, "end_line": 99}]"#).unwrap();
+        assert!(run_script(&mut docs, &actions).is_err());
+    }
+
+    #[test]
+    fn test_second_verdict_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut docs = vec![create_document(
+            &temp_dir,
+            "SPEC.md",
+            ProjectType::Standalone,
+        )];
+
+        let actions = parse_script(r#"[{"action": "lgtm"}, {"action": "nope"}]"#).unwrap();
+        let error = run_script(&mut docs, &actions).unwrap_err();
+
+        assert!(format!("{:#}", error).contains("SPEC.md already decided (Lgtm)"));
+        // The first verdict stands and no second review file is written
+        assert_eq!(docs[0].verdict, Verdict::Lgtm);
+        assert!(!temp_dir.path().join("SPEC.review.2").exists());
+    }
+
+    #[test]
+    fn test_nope_marks_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut docs = vec![create_document(
+            &temp_dir,
+            "SPEC.md",
+            ProjectType::Standalone,
+        )];
+
+        let actions = parse_script(r#"[{"action": "nope", "reason": "Scope unclear"}]"#).unwrap();
+        run_script(&mut docs, &actions).unwrap();
+
+        assert_eq!(docs[0].verdict, Verdict::Nope);
+        let review_file = temp_dir.path().join("SPEC.review.1");
+        assert_eq!(docs[0].review_path.as_ref(), Some(&review_file));
+        assert!(fs::read_to_string(&review_file)
+            .unwrap()
+            .contains("Scope unclear"));
+    }
+}
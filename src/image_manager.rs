@@ -1,6 +1,8 @@
+use crate::storage::content_hash;
 use eframe::egui;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Metadata for an image (dimensions without full texture)
 #[derive(Debug, Clone)]
@@ -10,12 +12,39 @@ struct ImageMetadata {
     full_path: PathBuf,
 }
 
+/// Cache entry rendered from document content
+struct Rendered<T> {
+    /// Content hash of the text it was rendered from
+    source: String,
+    /// Rendered value, or why rendering failed
+    result: Result<T, String>,
+}
+
+impl<T> Rendered<T> {
+    fn new(source: &str, result: anyhow::Result<T>) -> Self {
+        Self {
+            source: content_hash(source),
+            result: result.map_err(|e| format!("{:#}", e)),
+        }
+    }
+}
+
 /// Centralized image manager - loads metadata during parsing, textures on-demand
+///
+/// Also caches textures and SVG documents rendered from document content (math
+/// formulas, diagrams), keyed by the caller. Each document has its own manager;
+/// `retain_rendered` drops entries for content the document no longer contains.
 pub struct ImageManager {
     /// Image metadata cache (dimensions loaded during parsing)
     metadata: HashMap<String, ImageMetadata>,
     /// Lazy-loaded texture cache (created during rendering)
     textures: HashMap<String, egui::TextureHandle>,
+    /// Rendered texture cache; failures are kept too so they aren't retried every frame
+    rendered: HashMap<String, Rendered<egui::TextureHandle>>,
+    /// Rendered SVG cache by image URI (rasterized by egui's SVG loader), failures included
+    svgs: HashMap<String, Rendered<Arc<[u8]>>>,
+    /// URIs of dropped SVGs whose rasterizations egui's loaders still hold
+    evicted_svgs: Vec<String>,
     /// Base path for resolving relative image paths
     base_path: PathBuf,
 }
@@ -26,6 +55,9 @@ impl ImageManager {
         Self {
             metadata: HashMap::new(),
             textures: HashMap::new(),
+            rendered: HashMap::new(),
+            svgs: HashMap::new(),
+            evicted_svgs: Vec::new(),
             base_path: base_path.as_ref().to_path_buf(),
         }
     }
@@ -102,6 +134,76 @@ impl ImageManager {
 
         None
     }
+
+    /// Get or render a texture from content (lazy, like get_or_load_texture)
+    /// `key` must identify everything `render` depends on and `source` is the document
+    /// text it renders (see `retain_rendered`); the error is the render failure
+    pub fn get_or_render_texture(
+        &mut self,
+        ctx: &egui::Context,
+        key: &str,
+        source: &str,
+        render: impl FnOnce() -> anyhow::Result<egui::ColorImage>,
+    ) -> Result<&egui::TextureHandle, &str> {
+        self.rendered
+            .entry(key.to_string())
+            .or_insert_with(|| {
+                let texture = render()
+                    .map(|image| ctx.load_texture(key, image, egui::TextureOptions::LINEAR));
+                Rendered::new(source, texture)
+            })
+            .result
+            .as_ref()
+            .map_err(String::as_str)
+    }
+
+    /// Drop rendered entries whose source text isn't in `sources` (content hashes)
+    ///
+    /// Called after re-parsing, so edited formulas and diagrams don't keep their old
+    /// renders alive. Dropped SVGs stay loaded in egui until `forget_evicted`.
+    pub fn retain_rendered(&mut self, sources: &HashSet<String>) {
+        self.rendered
+            .retain(|_, rendered| sources.contains(&rendered.source));
+
+        let evicted = &mut self.evicted_svgs;
+        self.svgs.retain(|uri, rendered| {
+            let keep = sources.contains(&rendered.source);
+            if !keep {
+                evicted.push(uri.clone());
+            }
+            keep
+        });
+    }
+
+    /// Release egui's loaded copies of SVGs dropped by `retain_rendered`
+    pub fn forget_evicted(&mut self, ctx: &egui::Context) {
+        for uri in self.evicted_svgs.drain(..) {
+            ctx.forget_image(&uri);
+        }
+    }
+
+    /// Get or render an SVG document from content, as an image source for egui's loaders
+    /// `uri` (a `bytes://…svg` name) must identify everything `render` depends on and
+    /// `source` is the document text it renders (see `retain_rendered`)
+    pub fn get_or_render_svg(
+        &mut self,
+        uri: &str,
+        source: &str,
+        render: impl FnOnce() -> anyhow::Result<String>,
+    ) -> Result<egui::ImageSource<'static>, &str> {
+        self.svgs
+            .entry(uri.to_string())
+            .or_insert_with(|| {
+                Rendered::new(source, render().map(|svg| Arc::from(svg.into_bytes())))
+            })
+            .result
+            .as_ref()
+            .map(|bytes| egui::ImageSource::Bytes {
+                uri: uri.to_string().into(),
+                bytes: egui::load::Bytes::Shared(bytes.clone()),
+            })
+            .map_err(String::as_str)
+    }
 }
 
 #[cfg(test)]
@@ -205,4 +307,95 @@ mod tests {
 
         fs::remove_dir_all(&temp_dir).ok();
     }
+
+    #[test]
+    fn test_get_or_render_texture_caches_results() {
+        let ctx = egui::Context::default();
+        let mut manager = ImageManager::new(std::env::temp_dir());
+        let mut renders = 0;
+
+        for _ in 0..2 {
+            let texture = manager.get_or_render_texture(&ctx, "formula", "x^2", || {
+                renders += 1;
+                Ok(egui::ColorImage::filled([4, 2], egui::Color32::BLACK))
+            });
+            assert_eq!(texture.unwrap().size(), [4, 2]);
+        }
+        assert_eq!(renders, 1);
+
+        // Failures are cached as well
+        let failed =
+            manager.get_or_render_texture(&ctx, "broken", "\\", || anyhow::bail!("bad input"));
+        assert_eq!(failed.err(), Some("bad input"));
+        let retried = manager.get_or_render_texture(&ctx, "broken", "\\", || unreachable!());
+        assert!(retried.is_err());
+    }
+
+    #[test]
+    fn test_retain_rendered_drops_unreferenced_sources() {
+        let ctx = egui::Context::default();
+        let mut manager = ImageManager::new(std::env::temp_dir());
+        let image = || Ok(egui::ColorImage::filled([1, 1], egui::Color32::BLACK));
+        manager
+            .get_or_render_texture(&ctx, "old", "x^2", image)
+            .unwrap();
+        manager
+            .get_or_render_texture(&ctx, "new", "y^2", image)
+            .unwrap();
+
+        manager.retain_rendered(&HashSet::from([content_hash("y^2")]));
+
+        let mut renders = 0;
+        for key in ["old", "new"] {
+            manager
+                .get_or_render_texture(&ctx, key, "", || {
+                    renders += 1;
+                    image()
+                })
+                .unwrap();
+        }
+        // Only the formula no longer in the document is rendered again
+        assert_eq!(renders, 1);
+    }
+
+    #[test]
+    fn test_get_or_render_svg_caches_results() {
+        let mut manager = ImageManager::new(std::env::temp_dir());
+        let mut renders = 0;
+        let uri = "bytes://diagram.svg";
+
+        for _ in 0..2 {
+            let source = manager.get_or_render_svg(uri, "graph { a }", || {
+                renders += 1;
+                Ok("<svg xmlns=\"http://www.w3.org/2000/svg\"/>".to_string())
+            });
+            assert_eq!(source.unwrap().uri(), Some(uri));
+        }
+        assert_eq!(renders, 1);
+
+        let broken = "bytes://broken.svg";
+        let failed = manager.get_or_render_svg(broken, "graph {", || anyhow::bail!("bad"));
+        assert_eq!(failed.err(), Some("bad"));
+        let retried = manager.get_or_render_svg(broken, "graph {", || unreachable!());
+        assert!(retried.is_err());
+    }
+
+    #[test]
+    fn test_retain_rendered_evicts_svgs() {
+        let mut manager = ImageManager::new(std::env::temp_dir());
+        let svg = || Ok("<svg xmlns=\"http://www.w3.org/2000/svg\"/>".to_string());
+        manager
+            .get_or_render_svg("bytes://old.svg", "a -> b", svg)
+            .unwrap();
+        manager
+            .get_or_render_svg("bytes://new.svg", "a -> c", svg)
+            .unwrap();
+
+        manager.retain_rendered(&HashSet::from([content_hash("a -> c")]));
+        assert_eq!(manager.evicted_svgs, vec!["bytes://old.svg".to_string()]);
+        assert!(manager.svgs.contains_key("bytes://new.svg"));
+
+        manager.forget_evicted(&egui::Context::default());
+        assert!(manager.evicted_svgs.is_empty());
+    }
 }
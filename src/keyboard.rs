@@ -0,0 +1,402 @@
+/// Central keymap: key combinations → review actions (ROADMAP M6)
+///
+/// Bindings can be overridden from a JSON config file mapping action names to one
+/// or more shortcuts (`--keymap FILE`, or `$XDG_CONFIG_HOME/mirror/keymap.json`,
+/// falling back to `~/.config/mirror/keymap.json`):
+/// ```json
+/// {"approve": "Ctrl+Shift+L", "extend_selection_down": ["J", "Shift+Down"]}
+/// ```
+use crate::models::CursorMotion;
+use anyhow::{bail, Context, Result};
+use eframe::egui::{self, Key, KeyboardShortcut, Modifiers};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Actions that can be bound to keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAction {
+    NextDocument,
+    PreviousDocument,
+    /// Add the typed comment, or submit the queued review if nothing is being typed
+    Submit,
+    ClearSelection,
+    /// Open the search bar
+    Search,
+    /// Open the cross-document search panel
+    GlobalSearch,
+    /// Move the line cursor (collapses the selection)
+    CursorDown,
+    CursorUp,
+    /// Move the line cursor to the next/previous block (chunk, or hunk in diff mode)
+    NextBlock,
+    PreviousBlock,
+    /// Move the line cursor, extending the selection with it
+    ExtendSelectionDown,
+    ExtendSelectionUp,
+    ExtendSelectionNextBlock,
+    ExtendSelectionPreviousBlock,
+    /// Anchor a comment at the cursor line (or the selection) and focus the comment box
+    CommentAtCursor,
+    Approve,
+    /// Expand/collapse the table of contents (unbound by default)
+    ToggleContents,
+    ToggleHelp,
+}
+
+impl KeyAction {
+    /// All actions, in help-overlay order
+    pub const ALL: [KeyAction; 18] = [
+        KeyAction::NextDocument,
+        KeyAction::PreviousDocument,
+        KeyAction::Submit,
+        KeyAction::ClearSelection,
+        KeyAction::Search,
+        KeyAction::GlobalSearch,
+        KeyAction::CursorDown,
+        KeyAction::CursorUp,
+        KeyAction::NextBlock,
+        KeyAction::PreviousBlock,
+        KeyAction::ExtendSelectionDown,
+        KeyAction::ExtendSelectionUp,
+        KeyAction::ExtendSelectionNextBlock,
+        KeyAction::ExtendSelectionPreviousBlock,
+        KeyAction::CommentAtCursor,
+        KeyAction::Approve,
+        KeyAction::ToggleContents,
+        KeyAction::ToggleHelp,
+    ];
+
+    /// Cursor motion for cursor/extend actions (`true` = extend the selection)
+    pub fn cursor_motion(self) -> Option<(CursorMotion, bool)> {
+        let motion = match self {
+            KeyAction::CursorDown => (CursorMotion::LineDown, false),
+            KeyAction::CursorUp => (CursorMotion::LineUp, false),
+            KeyAction::NextBlock => (CursorMotion::BlockDown, false),
+            KeyAction::PreviousBlock => (CursorMotion::BlockUp, false),
+            KeyAction::ExtendSelectionDown => (CursorMotion::LineDown, true),
+            KeyAction::ExtendSelectionUp => (CursorMotion::LineUp, true),
+            KeyAction::ExtendSelectionNextBlock => (CursorMotion::BlockDown, true),
+            KeyAction::ExtendSelectionPreviousBlock => (CursorMotion::BlockUp, true),
+            _ => return None,
+        };
+        Some(motion)
+    }
+
+    /// Help-overlay description
+    pub fn description(self) -> &'static str {
+        match self {
+            KeyAction::NextDocument => "Next document",
+            KeyAction::PreviousDocument => "Previous document",
+            KeyAction::Submit => "Add comment / submit review",
+            KeyAction::ClearSelection => "Close search / clear selection (again: hide cursor)",
+            KeyAction::Search => "Search in document",
+            KeyAction::GlobalSearch => "Search all open documents",
+            KeyAction::CursorDown => "Cursor down one line",
+            KeyAction::CursorUp => "Cursor up one line",
+            KeyAction::NextBlock => "Cursor to next block",
+            KeyAction::PreviousBlock => "Cursor to previous block",
+            KeyAction::ExtendSelectionDown => "Extend selection down",
+            KeyAction::ExtendSelectionUp => "Extend selection up",
+            KeyAction::ExtendSelectionNextBlock => "Extend selection to next block",
+            KeyAction::ExtendSelectionPreviousBlock => "Extend selection to previous block",
+            KeyAction::CommentAtCursor => "Comment on cursor line / selection",
+            KeyAction::Approve => "LGTM",
+            KeyAction::ToggleContents => "Show/hide table of contents",
+            KeyAction::ToggleHelp => "Show/hide this help",
+        }
+    }
+}
+
+/// Parse a shortcut like "Ctrl+Shift+Tab", "J" or "?"
+///
+/// "Ctrl" means Cmd on macOS (egui's command modifier).
+pub fn parse_shortcut(text: &str) -> Result<KeyboardShortcut> {
+    let parts: Vec<&str> = text.split('+').map(str::trim).collect();
+    let Some((key_name, modifier_names)) = parts.split_last() else {
+        bail!("Empty shortcut");
+    };
+
+    let mut modifiers = Modifiers::NONE;
+    for name in modifier_names {
+        modifiers |= match name.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" | "cmd" | "command" => Modifiers::COMMAND,
+            "shift" => Modifiers::SHIFT,
+            "alt" | "option" => Modifiers::ALT,
+            _ => bail!("Unknown modifier {:?} in shortcut {:?}", name, text),
+        };
+    }
+
+    let key = Key::from_name(key_name)
+        .with_context(|| format!("Unknown key {:?} in shortcut {:?}", key_name, text))?;
+    Ok(KeyboardShortcut::new(modifiers, key))
+}
+
+/// Human-readable shortcut, e.g. "Ctrl+Shift+Tab"
+pub fn format_shortcut(shortcut: &KeyboardShortcut) -> String {
+    let modifiers = shortcut.modifiers;
+    let mut parts = Vec::new();
+    if modifiers.command || modifiers.ctrl {
+        parts.push("Ctrl");
+    }
+    if modifiers.alt {
+        parts.push("Alt");
+    }
+    if modifiers.shift {
+        parts.push("Shift");
+    }
+    parts.push(shortcut.logical_key.symbol_or_name());
+    parts.join("+")
+}
+
+/// One shortcut or a list of alternatives (config file values)
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ShortcutList {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Key bindings, most specific shortcuts first
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(KeyboardShortcut, KeyAction)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let defaults = [
+            ("Ctrl+Tab", KeyAction::NextDocument),
+            ("Ctrl+Shift+Tab", KeyAction::PreviousDocument),
+            ("Ctrl+Enter", KeyAction::Submit),
+            ("Escape", KeyAction::ClearSelection),
+            ("Ctrl+F", KeyAction::Search),
+            ("Ctrl+Shift+F", KeyAction::GlobalSearch),
+            ("Down", KeyAction::CursorDown),
+            ("Up", KeyAction::CursorUp),
+            ("Ctrl+Down", KeyAction::NextBlock),
+            ("Ctrl+Up", KeyAction::PreviousBlock),
+            ("J", KeyAction::ExtendSelectionDown),
+            ("Shift+Down", KeyAction::ExtendSelectionDown),
+            ("K", KeyAction::ExtendSelectionUp),
+            ("Shift+Up", KeyAction::ExtendSelectionUp),
+            ("Ctrl+Shift+Down", KeyAction::ExtendSelectionNextBlock),
+            ("Ctrl+Shift+Up", KeyAction::ExtendSelectionPreviousBlock),
+            ("Enter", KeyAction::CommentAtCursor),
+            ("Ctrl+L", KeyAction::Approve),
+            ("?", KeyAction::ToggleHelp),
+        ];
+
+        Self::from_bindings(defaults.iter().map(|&(text, action)| {
+            (
+                parse_shortcut(text).expect("default shortcut is valid"),
+                action,
+            )
+        }))
+    }
+}
+
+impl Keymap {
+    fn from_bindings(bindings: impl IntoIterator<Item = (KeyboardShortcut, KeyAction)>) -> Self {
+        let mut bindings: Vec<_> = bindings.into_iter().collect();
+        // egui matches modifiers loosely (extra Shift is ignored), so Ctrl+Shift+Tab
+        // must be checked before Ctrl+Tab
+        bindings.sort_by_key(|(shortcut, _)| {
+            let m = shortcut.modifiers;
+            std::cmp::Reverse(m.command as u8 + m.shift as u8 + m.alt as u8)
+        });
+        Self { bindings }
+    }
+
+    /// Replace the bindings of each action named in a JSON config
+    pub fn with_overrides(self, config: &str) -> Result<Self> {
+        let overrides: HashMap<KeyAction, ShortcutList> =
+            serde_json::from_str(config).context("Failed to parse keymap config")?;
+
+        let mut bindings: Vec<_> = self
+            .bindings
+            .into_iter()
+            .filter(|(_, action)| !overrides.contains_key(action))
+            .collect();
+
+        for (action, shortcuts) in overrides {
+            let shortcuts = match shortcuts {
+                ShortcutList::One(text) => vec![text],
+                ShortcutList::Many(texts) => texts,
+            };
+            for text in shortcuts {
+                bindings.push((parse_shortcut(&text)?, action));
+            }
+        }
+
+        Ok(Self::from_bindings(bindings))
+    }
+
+    /// Default config location (`$XDG_CONFIG_HOME/mirror/keymap.json`, then `~/.config/...`)
+    pub fn default_config_path() -> Option<PathBuf> {
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config_dir.join("mirror").join("keymap.json"))
+    }
+
+    /// Defaults with overrides from `path` (must exist), or from the default config
+    /// location if it exists
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match Self::default_config_path().filter(|p| p.exists()) {
+                Some(path) => path,
+                None => return Ok(Self::default()),
+            },
+        };
+
+        let config = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read keymap: {:?}", path))?;
+        Self::default()
+            .with_overrides(&config)
+            .with_context(|| format!("In keymap: {:?}", path))
+    }
+
+    /// Shortcuts bound to an action (for the help overlay)
+    pub fn shortcuts_for(&self, action: KeyAction) -> Vec<String> {
+        self.bindings
+            .iter()
+            .filter(|(_, bound)| *bound == action)
+            .map(|(shortcut, _)| format_shortcut(shortcut))
+            .collect()
+    }
+
+    /// Consume this frame's key presses and return the triggered actions
+    ///
+    /// While a text field has focus only Ctrl/Alt shortcuts and Escape fire, so plain
+    /// keys like `j` still type into the comment box.
+    pub fn pressed(&self, ctx: &egui::Context) -> Vec<KeyAction> {
+        let typing = ctx.wants_keyboard_input();
+
+        ctx.input_mut(|input| {
+            self.bindings
+                .iter()
+                .filter(|(shortcut, _)| {
+                    let m = shortcut.modifiers;
+                    !typing || m.command || m.alt || shortcut.logical_key == Key::Escape
+                })
+                .filter(|(shortcut, _)| input.consume_shortcut(shortcut))
+                .map(|&(_, action)| action)
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(keymap: &Keymap, modifiers: Modifiers, key: Key) -> Vec<KeyAction> {
+        let ctx = egui::Context::default();
+        let raw_input = egui::RawInput {
+            modifiers,
+            events: vec![egui::Event::Key {
+                key,
+                physical_key: None,
+                pressed: true,
+                repeat: false,
+                modifiers,
+            }],
+            ..Default::default()
+        };
+
+        let mut actions = Vec::new();
+        let _ = ctx.run(raw_input, |ctx| actions = keymap.pressed(ctx));
+        actions
+    }
+
+    #[test]
+    fn test_parse_and_format_shortcut() {
+        let shortcut = parse_shortcut("Ctrl+Shift+Tab").unwrap();
+        assert_eq!(shortcut.modifiers, Modifiers::COMMAND | Modifiers::SHIFT);
+        assert_eq!(shortcut.logical_key, Key::Tab);
+        assert_eq!(format_shortcut(&shortcut), "Ctrl+Shift+Tab");
+
+        assert_eq!(parse_shortcut("?").unwrap().logical_key, Key::Questionmark);
+        assert!(parse_shortcut("Hyper+J").is_err());
+        assert!(parse_shortcut("Ctrl+Nope").is_err());
+    }
+
+    #[test]
+    fn test_default_bindings() {
+        let keymap = Keymap::default();
+        assert_eq!(
+            press(&keymap, Modifiers::COMMAND, Key::Enter),
+            vec![KeyAction::Submit]
+        );
+        assert_eq!(
+            press(&keymap, Modifiers::NONE, Key::J),
+            vec![KeyAction::ExtendSelectionDown]
+        );
+        assert_eq!(
+            press(&keymap, Modifiers::SHIFT, Key::ArrowUp),
+            vec![KeyAction::ExtendSelectionUp]
+        );
+        assert!(keymap.shortcuts_for(KeyAction::ToggleContents).is_empty());
+    }
+
+    #[test]
+    fn test_most_specific_shortcut_wins() {
+        let keymap = Keymap::default();
+        assert_eq!(
+            press(&keymap, Modifiers::COMMAND | Modifiers::SHIFT, Key::Tab),
+            vec![KeyAction::PreviousDocument]
+        );
+        assert_eq!(
+            press(&keymap, Modifiers::COMMAND, Key::Tab),
+            vec![KeyAction::NextDocument]
+        );
+        assert_eq!(
+            press(
+                &keymap,
+                Modifiers::COMMAND | Modifiers::SHIFT,
+                Key::ArrowDown
+            ),
+            vec![KeyAction::ExtendSelectionNextBlock]
+        );
+        assert_eq!(
+            press(&keymap, Modifiers::NONE, Key::ArrowDown),
+            vec![KeyAction::CursorDown]
+        );
+        assert_eq!(
+            press(&keymap, Modifiers::COMMAND, Key::Enter),
+            vec![KeyAction::Submit]
+        );
+    }
+
+    #[test]
+    fn test_config_overrides() {
+        let keymap = Keymap::default()
+            .with_overrides(r#"{"approve": "Ctrl+Shift+L", "clear_selection": ["Escape", "Q"]}"#)
+            .unwrap();
+
+        assert_eq!(
+            keymap.shortcuts_for(KeyAction::Approve),
+            vec!["Ctrl+Shift+L"]
+        );
+        assert_eq!(
+            keymap.shortcuts_for(KeyAction::ClearSelection),
+            vec!["Escape", "Q"]
+        );
+        // Untouched actions keep their defaults
+        assert_eq!(keymap.shortcuts_for(KeyAction::Submit), vec!["Ctrl+Enter"]);
+        assert!(press(&keymap, Modifiers::COMMAND, Key::L).is_empty());
+    }
+
+    #[test]
+    fn test_config_errors() {
+        assert!(Keymap::default()
+            .with_overrides(r#"{"explode": "X"}"#)
+            .is_err());
+        assert!(Keymap::default()
+            .with_overrides(r#"{"approve": "Ctrl+"}"#)
+            .is_err());
+    }
+}
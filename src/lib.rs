@@ -1,14 +1,25 @@
 // Library exports for testing
 
+pub mod anchoring;
+pub mod diagram;
+pub mod diff;
+pub mod git;
+pub mod headless;
 pub mod image_manager;
+pub mod keyboard;
+pub mod links;
+pub mod math;
 pub mod models;
 pub mod parsing;
 pub mod rendering;
+pub mod report;
+pub mod search;
 pub mod storage;
 pub mod syntax;
 pub mod theme;
+pub mod watcher;
 
 // Re-export commonly used items for convenience
-pub use models::{Comment, Document, LayoutMap, ReviewMode, Selection, TextChunk};
+pub use models::{Comment, Document, LayoutMap, ReviewMode, Selection, TextChunk, Verdict};
 pub use parsing::parse_markdown;
 pub use storage::ReviewStorage;
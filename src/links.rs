@@ -0,0 +1,209 @@
+/// Link navigation: classify link destinations and resolve `#anchor` fragments to headings
+/// and footnotes
+///
+/// In-document anchors scroll to a heading or footnote, relative links to other markdown
+/// files open them as review tabs, and everything else is handed to the system opener.
+use crate::models::{Footnote, TextChunk, TocEntry};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Where a link destination points
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkTarget {
+    /// Heading in the current document (`#anchor`, without the `#`)
+    Anchor(String),
+    /// Another markdown file, resolved against the linking document's directory
+    Document {
+        path: PathBuf,
+        anchor: Option<String>,
+    },
+    /// Any other local file, resolved against the linking document's directory
+    File(PathBuf),
+    /// URL with a scheme (https:, mailto:, ...)
+    External(String),
+}
+
+/// File extensions opened as review tabs rather than with the system opener
+const MARKDOWN_EXTENSIONS: [&str; 3] = ["md", "markdown", "mdown"];
+
+/// Classify a link destination relative to the directory of the linking document
+pub fn classify_link(dest: &str, base_path: &Path) -> LinkTarget {
+    let dest = dest.trim();
+    if let Some(anchor) = dest.strip_prefix('#') {
+        return LinkTarget::Anchor(anchor.to_string());
+    }
+    if has_scheme(dest) {
+        return LinkTarget::External(dest.to_string());
+    }
+
+    let (path, anchor) = match dest.split_once('#') {
+        Some((path, anchor)) => (path, Some(anchor.to_string()).filter(|a| !a.is_empty())),
+        None => (dest, None),
+    };
+    // Query strings mean nothing to a local file
+    let path = path.split('?').next().unwrap_or(path);
+    let path = base_path.join(path.replace("%20", " "));
+
+    let is_markdown = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            MARKDOWN_EXTENSIONS
+                .iter()
+                .any(|md| ext.eq_ignore_ascii_case(md))
+        });
+    if is_markdown {
+        LinkTarget::Document { path, anchor }
+    } else {
+        LinkTarget::File(path)
+    }
+}
+
+/// `scheme:` prefix per RFC 3986 (letter, then letters, digits, `+`, `-`, `.`)
+///
+/// Single letters are not schemes so Windows drive paths (`C:\...`) stay local.
+fn has_scheme(dest: &str) -> bool {
+    match dest.split_once(':') {
+        Some((scheme, _)) => {
+            scheme.len() > 1
+                && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
+
+/// GitHub-style heading anchor: lowercase, punctuation dropped, spaces to hyphens
+pub fn heading_slug(title: &str) -> String {
+    title
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Line of the heading an anchor refers to
+///
+/// Repeated headings get `-1`, `-2`, ... suffixes in document order, as on GitHub.
+/// Anchors are matched case-insensitively.
+pub fn resolve_anchor(entries: &[TocEntry], anchor: &str) -> Option<usize> {
+    let anchor = anchor.to_lowercase();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    for entry in entries {
+        let base = heading_slug(&entry.title);
+        let count = seen.entry(base.clone()).or_insert(0);
+        let slug = if *count == 0 {
+            base
+        } else {
+            format!("{}-{}", base, count)
+        };
+        *count += 1;
+
+        if slug == anchor {
+            return Some(entry.line);
+        }
+    }
+    None
+}
+
+/// Anchor of a footnote's definition (`#fn-label`, as on GitHub)
+pub fn footnote_anchor(label: &str) -> String {
+    format!("fn-{}", label)
+}
+
+/// Anchor of a footnote's first reference, the definition's back-link target
+pub fn footnote_ref_anchor(label: &str) -> String {
+    format!("fnref-{}", label)
+}
+
+/// Line a footnote anchor refers to: the definition for `fn-label`, the first
+/// reference for `fnref-label`
+pub fn resolve_footnote(chunks: &[TextChunk], anchor: &str) -> Option<usize> {
+    let target = if let Some(label) = anchor.strip_prefix("fnref-") {
+        Footnote::Reference(label.to_string())
+    } else {
+        Footnote::Definition(anchor.strip_prefix("fn-")?.to_string())
+    };
+    chunks
+        .iter()
+        .find(|chunk| chunk.footnote.as_ref() == Some(&target))
+        .map(|chunk| chunk.line_start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, line: usize) -> TocEntry {
+        TocEntry {
+            level: 2,
+            title: title.to_string(),
+            line,
+            section_end: line,
+        }
+    }
+
+    #[test]
+    fn test_classify_anchor_and_external() {
+        let base = Path::new("docs");
+        assert_eq!(
+            classify_link("#setup", base),
+            LinkTarget::Anchor("setup".to_string())
+        );
+        assert_eq!(
+            classify_link("https://example.com/a#b", base),
+            LinkTarget::External("https://example.com/a#b".to_string())
+        );
+        assert_eq!(
+            classify_link("mailto:someone@example.com", base),
+            LinkTarget::External("mailto:someone@example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn test_classify_relative_paths() {
+        let base = Path::new("docs");
+        assert_eq!(
+            classify_link("../ROADMAP.md#phase-2", base),
+            LinkTarget::Document {
+                path: Path::new("docs").join("../ROADMAP.md"),
+                anchor: Some("phase-2".to_string()),
+            }
+        );
+        assert_eq!(
+            classify_link("guide.MD", base),
+            LinkTarget::Document {
+                path: Path::new("docs").join("guide.MD"),
+                anchor: None,
+            }
+        );
+        assert_eq!(
+            classify_link("images/diagram.png", base),
+            LinkTarget::File(Path::new("docs").join("images/diagram.png"))
+        );
+    }
+
+    #[test]
+    fn test_heading_slug() {
+        assert_eq!(heading_slug("Getting Started"), "getting-started");
+        assert_eq!(heading_slug("What's new in v2.0?"), "whats-new-in-v20");
+        assert_eq!(heading_slug("snake_case & more"), "snake_case--more");
+    }
+
+    #[test]
+    fn test_resolve_anchor_with_duplicates() {
+        let entries = vec![entry("Usage", 3), entry("Examples", 10), entry("Usage", 20)];
+        assert_eq!(resolve_anchor(&entries, "usage"), Some(3));
+        assert_eq!(resolve_anchor(&entries, "usage-1"), Some(20));
+        assert_eq!(resolve_anchor(&entries, "Examples"), Some(10));
+        assert_eq!(resolve_anchor(&entries, "missing"), None);
+    }
+}
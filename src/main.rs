@@ -1,21 +1,36 @@
+mod anchoring;
 mod app;
+mod diagram;
+mod diff;
+mod git;
+mod headless;
 mod image_manager;
+mod keyboard;
+mod links;
+mod math;
 mod models;
 mod parsing;
 mod rendering;
+mod report;
+mod search;
 mod storage;
 mod syntax;
 mod theme;
+mod watcher;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use app::MarkdownReviewApp;
 use clap::Parser;
+use diff::{DiffView, RevisionDiff};
 use eframe::egui;
+use keyboard::Keymap;
 use models::Document;
+use report::{exit_code, ReviewReport};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use std::sync::Arc;
-use storage::detect_project_type;
+use storage::{detect_project_type, ProjectType};
 
 /// Ephemeral Markdown review UI for Dialectic-Driven Development
 #[derive(Parser, Debug)]
@@ -25,17 +40,38 @@ struct Args {
     /// Files to review
     files: Vec<String>,
 
+    /// Review NEW as a diff against OLD (comments record the diff hunk they target)
+    #[arg(long, num_args = 2, value_names = ["OLD", "NEW"], conflicts_with = "files")]
+    diff: Option<Vec<String>>,
+
+    /// Mark changes since a git revision (read from the local repository) in the margin
+    #[arg(
+        long,
+        visible_alias = "base",
+        value_name = "REV",
+        conflicts_with = "diff"
+    )]
+    since: Option<String>,
+
     /// Output directory for review files (defaults to same directory as the file being reviewed)
     #[arg(long)]
     out_dir: Option<String>,
 
-    /// Emit JSON with review file paths on exit
+    /// Emit a JSON review report (verdicts, review file paths, comments) on stdout at exit
     #[arg(long)]
     json: bool,
 
-    /// Headless mode (no-op, for testing)
+    /// Headless mode: run a review script instead of opening a window
     #[arg(long)]
     headless: bool,
+
+    /// Review script for headless mode (JSON array of actions; defaults to stdin)
+    #[arg(long, requires = "headless")]
+    script: Option<String>,
+
+    /// Key binding overrides (JSON; defaults to ~/.config/mirror/keymap.json if present)
+    #[arg(long, value_name = "FILE")]
+    keymap: Option<String>,
 }
 
 fn load_icon() -> egui::IconData {
@@ -51,66 +87,13 @@ fn load_icon() -> egui::IconData {
     }
 }
 
-fn main() -> Result<()> {
-    let args = Args::parse();
-
-    if args.files.is_empty() {
-        anyhow::bail!("No files specified. Usage: mirror FILE1.md [FILE2.md ...]");
-    }
-
-    if args.headless {
-        // Headless mode: just exit successfully
-        return Ok(());
-    }
-
-    // Get session ID from environment
-    let session_id = std::env::var("HEGEL_SESSION_ID").ok();
-
-    // Detect project type
-    let project_type = detect_project_type();
-
-    // Load all files into Document structs
-    let mut documents = Vec::new();
-    for file_path_str in &args.files {
-        let markdown_content = fs::read_to_string(file_path_str)
-            .unwrap_or_else(|e| panic!("Failed to read {}: {}", file_path_str, e));
-
-        let file_path = Path::new(file_path_str);
-        let base_path = file_path.parent().unwrap_or(Path::new(".")).to_path_buf();
-
-        let filename = file_path
-            .file_name()
-            .and_then(|s| s.to_str())
-            .unwrap_or("unknown.md")
-            .to_string();
-
-        // Convert to absolute path for Hegel mode
-        let abs_file_path = if file_path.is_absolute() {
-            file_path.to_path_buf()
-        } else {
-            std::env::current_dir()
-                .unwrap_or_else(|_| Path::new(".").to_path_buf())
-                .join(file_path)
-        };
-
-        // Use explicit out_dir if provided, otherwise use file's directory
-        let review_dir = if let Some(ref out_dir) = args.out_dir {
-            Path::new(out_dir).to_path_buf()
-        } else {
-            base_path.clone()
-        };
-
-        documents.push(Document::new(
-            filename,
-            markdown_content,
-            base_path,
-            abs_file_path,
-            review_dir,
-            session_id.clone(),
-            project_type.clone(),
-        ));
-    }
-
+/// Run the review window until the user closes it or all documents are done
+fn run_gui(
+    documents: &mut Vec<Document>,
+    json_output: bool,
+    keymap: Keymap,
+    out_dir: Option<&str>,
+) -> Result<()> {
     // Load app icon
     let icon_data = load_icon();
 
@@ -124,7 +107,7 @@ fn main() -> Result<()> {
     eframe::run_native(
         "Hegel Mirror",
         options,
-        Box::new(move |cc| {
+        Box::new(|cc| {
             // Set light mode visuals
             cc.egui_ctx.set_visuals(egui::Visuals::light());
 
@@ -181,8 +164,109 @@ fn main() -> Result<()> {
             // Apply font definitions
             cc.egui_ctx.set_fonts(fonts);
 
-            Ok(Box::new(MarkdownReviewApp::new(documents)))
+            Ok(Box::new(MarkdownReviewApp::new(
+                documents,
+                json_output,
+                keymap,
+                out_dir.map(PathBuf::from),
+            )))
         }),
     )
     .map_err(|e| anyhow::anyhow!("eframe error: {}", e))
 }
+
+/// Read a file into a Document (review files go to out_dir, or next to the file)
+fn load_document(
+    file_path_str: &str,
+    out_dir: Option<&str>,
+    session_id: Option<String>,
+    project_type: ProjectType,
+) -> Result<Document> {
+    let mut document = Document::open(
+        Path::new(file_path_str),
+        out_dir.map(Path::new),
+        session_id,
+        project_type,
+    )?;
+
+    // Prior review rounds are informational - unreadable history shouldn't block a review
+    if let Err(e) = document.load_history() {
+        eprintln!(
+            "Warning: failed to load review history for {}: {:#}",
+            file_path_str, e
+        );
+    }
+
+    Ok(document)
+}
+
+fn main() -> Result<ExitCode> {
+    let args = Args::parse();
+
+    if args.files.is_empty() && args.diff.is_none() {
+        anyhow::bail!("No files specified. Usage: mirror FILE1.md [FILE2.md ...]");
+    }
+
+    // Get session ID from environment
+    let session_id = std::env::var("HEGEL_SESSION_ID").ok();
+
+    // Detect project type
+    let project_type = detect_project_type();
+
+    // Load all files into Document structs
+    let mut documents = Vec::new();
+    if let Some([old_path, new_path]) = args.diff.as_deref() {
+        // Diff mode: review NEW, showing its changes relative to OLD
+        let old_source =
+            fs::read_to_string(old_path).with_context(|| format!("Failed to read {}", old_path))?;
+        let mut document = load_document(
+            new_path,
+            args.out_dir.as_deref(),
+            session_id.clone(),
+            project_type.clone(),
+        )?;
+        document.diff = Some(DiffView::new(
+            Path::new(old_path).to_path_buf(),
+            old_source,
+            &document.source,
+        ));
+        documents.push(document);
+    }
+    for file_path_str in &args.files {
+        let mut document = load_document(
+            file_path_str,
+            args.out_dir.as_deref(),
+            session_id.clone(),
+            project_type.clone(),
+        )?;
+        if let Some(rev) = &args.since {
+            document.revision = Some(
+                RevisionDiff::load(&document.file_path, rev, &document.source)
+                    .with_context(|| format!("Failed to diff {} against {}", file_path_str, rev))?,
+            );
+        }
+        documents.push(document);
+    }
+
+    if args.headless {
+        // Headless mode: drive the same review actions from a script
+        let actions = headless::load_script(args.script.as_deref())?;
+        if let Err(error) = headless::run_script(&mut documents, &actions) {
+            // Verdicts written before the failing step are already on disk; report them
+            if args.json {
+                println!("{}", ReviewReport::from_documents(&documents).to_json()?);
+            }
+            return Err(error);
+        }
+    } else {
+        let keymap = Keymap::load(args.keymap.as_deref().map(Path::new))?;
+        run_gui(&mut documents, args.json, keymap, args.out_dir.as_deref())?;
+    }
+
+    if args.json {
+        println!("{}", ReviewReport::from_documents(&documents).to_json()?);
+    }
+
+    // Aggregate outcome so workflows can branch on $? without parsing output
+    Ok(ExitCode::from(exit_code(&documents)))
+}
@@ -0,0 +1,684 @@
+/// Box layout for parsed math, after TeX's rules in simplified form
+///
+/// Every node becomes a `MathBox`: a width, the extent above and below the baseline,
+/// and positioned items (glyphs, filled rules, stroked lines) relative to the box's
+/// left end of the baseline, y pointing down. Sizes are in pixels.
+use super::parse::{Class, Node, Variant};
+use ab_glyph::{point, Font, FontRef, GlyphId, PxScale, ScaleFont};
+use anyhow::{anyhow, Result};
+use std::sync::OnceLock;
+
+/// Fonts used for math: Inter (as in the document text) with Hack as fallback for the
+/// math symbols Inter lacks
+pub struct MathFonts {
+    fonts: [FontRef<'static>; 4],
+}
+
+/// Index into `MathFonts::fonts`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FontIndex {
+    Regular = 0,
+    Italic = 1,
+    Bold = 2,
+    Fallback = 3,
+}
+
+impl MathFonts {
+    /// Fonts parsed once from the embedded font data
+    pub fn get() -> &'static MathFonts {
+        static FONTS: OnceLock<MathFonts> = OnceLock::new();
+        FONTS.get_or_init(|| {
+            let load = |data: &'static [u8]| {
+                FontRef::try_from_slice(data).expect("embedded font data is valid")
+            };
+            MathFonts {
+                fonts: [
+                    load(include_bytes!("../../fonts/Inter-Regular.ttf")),
+                    load(include_bytes!("../../fonts/Inter-Italic.ttf")),
+                    load(include_bytes!("../../fonts/Inter-Bold.ttf")),
+                    load(epaint_default_fonts::HACK_REGULAR),
+                ],
+            }
+        })
+    }
+
+    pub fn font(&self, index: FontIndex) -> &FontRef<'static> {
+        &self.fonts[index as usize]
+    }
+
+    /// Upright glyph for `ch` in plain text (diagram labels)
+    pub fn text_glyph(&self, ch: char) -> Option<(FontIndex, GlyphId)> {
+        self.find_glyph(ch, Variant::Upright)
+    }
+
+    /// First font of the variant's fallback chain that has a glyph for `ch`
+    fn find_glyph(&self, ch: char, variant: Variant) -> Option<(FontIndex, GlyphId)> {
+        let chain: &[FontIndex] = match variant {
+            Variant::Italic => &[FontIndex::Italic, FontIndex::Regular, FontIndex::Fallback],
+            Variant::Upright => &[FontIndex::Regular, FontIndex::Fallback],
+            Variant::Bold => &[FontIndex::Bold, FontIndex::Regular, FontIndex::Fallback],
+        };
+        chain.iter().find_map(|&index| {
+            let id = self.font(index).glyph_id(ch);
+            (id.0 != 0).then_some((index, id))
+        })
+    }
+}
+
+/// Pixel scale at which a font's em is `size` pixels (egui's font size convention)
+pub fn px_scale(font: &FontRef, size: f32) -> PxScale {
+    let units_per_em = font.units_per_em().unwrap_or(1000.0);
+    PxScale::from(size * font.height_unscaled() / units_per_em)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Item {
+    Glyph {
+        font: FontIndex,
+        id: GlyphId,
+        size: f32,
+        x: f32,
+        y: f32,
+    },
+    /// Filled rectangle (fraction bars, overlines, straight delimiters)
+    Rule { x: f32, y: f32, w: f32, h: f32 },
+    /// Stroked polyline (radical signs, parentheses)
+    Line { points: Vec<(f32, f32)>, width: f32 },
+}
+
+impl Item {
+    fn shifted(self, dx: f32, dy: f32) -> Item {
+        match self {
+            Item::Glyph {
+                font,
+                id,
+                size,
+                x,
+                y,
+            } => Item::Glyph {
+                font,
+                id,
+                size,
+                x: x + dx,
+                y: y + dy,
+            },
+            Item::Rule { x, y, w, h } => Item::Rule {
+                x: x + dx,
+                y: y + dy,
+                w,
+                h,
+            },
+            Item::Line { points, width } => Item::Line {
+                points: points.into_iter().map(|(x, y)| (x + dx, y + dy)).collect(),
+                width,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MathBox {
+    pub width: f32,
+    /// Extent above the baseline
+    pub ascent: f32,
+    /// Extent below the baseline
+    pub descent: f32,
+    pub items: Vec<Item>,
+}
+
+impl MathBox {
+    /// Empty box of the given width (spaces, kerns)
+    fn space(width: f32) -> MathBox {
+        MathBox {
+            width,
+            ..MathBox::default()
+        }
+    }
+
+    /// Add another box with its baseline origin at (dx, dy)
+    fn place(&mut self, other: MathBox, dx: f32, dy: f32) {
+        self.ascent = self.ascent.max(other.ascent - dy);
+        self.descent = self.descent.max(other.descent + dy);
+        self.width = self.width.max(dx + other.width);
+        self.items
+            .extend(other.items.into_iter().map(|item| item.shifted(dx, dy)));
+    }
+
+    /// Append a box after this one on the same baseline
+    fn append(&mut self, other: MathBox) {
+        let dx = self.width;
+        self.place(other, dx, 0.0);
+    }
+
+    fn add_rule(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        self.ascent = self.ascent.max(-y);
+        self.descent = self.descent.max(y + h);
+        self.width = self.width.max(x + w);
+        self.items.push(Item::Rule { x, y, w, h });
+    }
+
+    fn add_line(&mut self, points: Vec<(f32, f32)>, width: f32) {
+        for &(x, y) in &points {
+            self.ascent = self.ascent.max(-y + width / 2.0);
+            self.descent = self.descent.max(y + width / 2.0);
+            self.width = self.width.max(x + width / 2.0);
+        }
+        self.items.push(Item::Line { points, width });
+    }
+}
+
+/// Current math style: font size and whether it is display style
+#[derive(Clone, Copy, Debug)]
+struct Style {
+    size: f32,
+    display: bool,
+    /// Script nesting level (0 for the main formula)
+    level: u8,
+}
+
+/// Lays out nodes at a base font size
+pub struct Layouter<'a> {
+    fonts: &'a MathFonts,
+    base_size: f32,
+}
+
+impl<'a> Layouter<'a> {
+    pub fn new(fonts: &'a MathFonts, base_size: f32) -> Self {
+        Self { fonts, base_size }
+    }
+
+    /// Lay out a formula (display style for `$$...$$`)
+    pub fn layout(&self, nodes: &[Node], display: bool) -> Result<MathBox> {
+        self.list(
+            nodes,
+            Style {
+                size: self.base_size,
+                display,
+                level: 0,
+            },
+        )
+    }
+
+    /// Smaller style for scripts and inline fractions
+    fn script(&self, style: Style) -> Style {
+        let scale = if style.level == 0 { 0.7 } else { 0.55 };
+        Style {
+            size: self.base_size * scale,
+            display: false,
+            level: style.level + 1,
+        }
+    }
+
+    /// Height of the math axis (fraction bars, centered operators) above the baseline
+    fn axis(&self, style: Style) -> f32 {
+        0.26 * style.size
+    }
+
+    fn rule_thickness(&self, style: Style) -> f32 {
+        (0.055 * style.size).max(1.0)
+    }
+
+    /// Horizontal list with inter-atom spacing
+    fn list(&self, nodes: &[Node], style: Style) -> Result<MathBox> {
+        let classes = effective_classes(nodes);
+        let mut hbox = MathBox::default();
+        let mut previous: Option<Class> = None;
+
+        for (node, class) in nodes.iter().zip(classes) {
+            if let (Some(left), Some(right)) = (previous, class) {
+                hbox.append(MathBox::space(
+                    spacing(left, right, style.level) * style.size,
+                ));
+            }
+            hbox.append(self.node(node, style)?);
+            if class.is_some() {
+                previous = class;
+            }
+        }
+        Ok(hbox)
+    }
+
+    fn node(&self, node: &Node, style: Style) -> Result<MathBox> {
+        match node {
+            Node::Symbol { ch, variant, .. } => self.symbol(*ch, *variant, style.size),
+            Node::Text(text) => self.text(text, style.size),
+            Node::OpName { name, .. } => self.text(name, style.size),
+            Node::BigOp { ch, .. } => self.big_op(*ch, style),
+            Node::Group(nodes) => self.list(nodes, style),
+            Node::Scripts { base, sup, sub } => {
+                self.scripts(base, sup.as_deref(), sub.as_deref(), style)
+            }
+            Node::Frac { num, den } => self.frac(num, den, style),
+            Node::Sqrt(body) => self.sqrt(body, style),
+            Node::Space(em) => Ok(MathBox::space(em * style.size)),
+            Node::Delimited { left, right, body } => self.delimited(*left, *right, body, style),
+            Node::Accent { accent, base } => self.accent(*accent, base, style),
+        }
+    }
+
+    /// A glyph box with its ink extents (the descent may be negative for raised marks)
+    fn glyph(&self, ch: char, variant: Variant, size: f32) -> Result<MathBox> {
+        let (index, id) = self
+            .fonts
+            .find_glyph(ch, variant)
+            .ok_or_else(|| anyhow!("no glyph for '{}'", ch))?;
+        let font = self.fonts.font(index);
+        let scale = px_scale(font, size);
+        let advance = font.as_scaled(scale).h_advance(id);
+        let (ascent, descent) =
+            match font.outline_glyph(id.with_scale_and_position(scale, point(0.0, 0.0))) {
+                Some(outline) => {
+                    let bounds = outline.px_bounds();
+                    (-bounds.min.y, bounds.max.y)
+                }
+                None => (0.0, 0.0),
+            };
+        Ok(MathBox {
+            width: advance,
+            ascent,
+            descent,
+            items: vec![Item::Glyph {
+                font: index,
+                id,
+                size,
+                x: 0.0,
+                y: 0.0,
+            }],
+        })
+    }
+
+    fn symbol(&self, ch: char, variant: Variant, size: f32) -> Result<MathBox> {
+        let mut glyph = self.glyph(ch, variant, size)?;
+        glyph.ascent = glyph.ascent.max(0.0);
+        glyph.descent = glyph.descent.max(0.0);
+        Ok(glyph)
+    }
+
+    fn text(&self, text: &str, size: f32) -> Result<MathBox> {
+        let mut hbox = MathBox::default();
+        for ch in text.chars() {
+            hbox.append(self.symbol(ch, Variant::Upright, size)?);
+        }
+        Ok(hbox)
+    }
+
+    /// Large operator, enlarged and centered on the axis
+    fn big_op(&self, ch: char, style: Style) -> Result<MathBox> {
+        let scale = if style.display { 1.6 } else { 1.2 };
+        let glyph = self.glyph(ch, Variant::Upright, style.size * scale)?;
+        let center = (glyph.descent - glyph.ascent) / 2.0;
+        let mut op = MathBox::default();
+        op.place(glyph, 0.0, -self.axis(style) - center);
+        Ok(op)
+    }
+
+    fn scripts(
+        &self,
+        base: &Node,
+        sup: Option<&Node>,
+        sub: Option<&Node>,
+        style: Style,
+    ) -> Result<MathBox> {
+        let base_box = self.node(base, style)?;
+        let script_style = self.script(style);
+        let sup_box = sup.map(|n| self.node(n, script_style)).transpose()?;
+        let sub_box = sub.map(|n| self.node(n, script_style)).transpose()?;
+
+        let limits = style.display
+            && matches!(
+                base,
+                Node::BigOp { limits: true, .. } | Node::OpName { limits: true, .. }
+            );
+        if limits {
+            return Ok(self.limits(base_box, sup_box, sub_box, style));
+        }
+
+        let size = style.size;
+        let mut sup_shift = sup_box
+            .as_ref()
+            .map(|s| {
+                (0.4 * size)
+                    .max(base_box.ascent - 0.45 * s.ascent)
+                    .max(s.descent + 0.25 * size)
+            })
+            .unwrap_or(0.0);
+        let mut sub_shift = sub_box
+            .as_ref()
+            .map(|s| {
+                (0.2 * size)
+                    .max(base_box.descent + 0.1 * size)
+                    .max(s.ascent - 0.45 * size)
+            })
+            .unwrap_or(0.0);
+        if let (Some(sup), Some(sub)) = (&sup_box, &sub_box) {
+            // Keep a gap between the superscript's bottom and the subscript's top
+            let gap = (sub_shift - sub.ascent) + (sup_shift - sup.descent);
+            let min_gap = 0.15 * size;
+            if gap < min_gap {
+                sub_shift += (min_gap - gap) / 2.0;
+                sup_shift += (min_gap - gap) / 2.0;
+            }
+        }
+
+        let mut hbox = MathBox::default();
+        let x = base_box.width;
+        hbox.append(base_box);
+        if let Some(sup) = sup_box {
+            hbox.place(sup, x, -sup_shift);
+        }
+        if let Some(sub) = sub_box {
+            hbox.place(sub, x, sub_shift);
+        }
+        hbox.width += 0.05 * size;
+        Ok(hbox)
+    }
+
+    /// Scripts centered above and below the operator (display-style limits)
+    fn limits(
+        &self,
+        base: MathBox,
+        sup: Option<MathBox>,
+        sub: Option<MathBox>,
+        style: Style,
+    ) -> MathBox {
+        let gap = 0.15 * style.size;
+        let width = [Some(&base), sup.as_ref(), sub.as_ref()]
+            .into_iter()
+            .flatten()
+            .map(|b| b.width)
+            .fold(0.0, f32::max);
+
+        let mut vbox = MathBox::default();
+        let (base_ascent, base_descent) = (base.ascent, base.descent);
+        let base_x = (width - base.width) / 2.0;
+        vbox.place(base, base_x, 0.0);
+        if let Some(sup) = sup {
+            let (x, y) = (
+                (width - sup.width) / 2.0,
+                -(base_ascent + gap + sup.descent),
+            );
+            vbox.place(sup, x, y);
+        }
+        if let Some(sub) = sub {
+            let (x, y) = ((width - sub.width) / 2.0, base_descent + gap + sub.ascent);
+            vbox.place(sub, x, y);
+        }
+        vbox
+    }
+
+    fn frac(&self, num: &Node, den: &Node, style: Style) -> Result<MathBox> {
+        // Display fractions keep the size; inline ones use script size
+        let part_style = if style.display {
+            Style {
+                display: false,
+                ..style
+            }
+        } else {
+            self.script(style)
+        };
+        let num = self.node(num, part_style)?;
+        let den = self.node(den, part_style)?;
+
+        let thickness = self.rule_thickness(style);
+        let axis = self.axis(style);
+        let gap = if style.display { 0.18 } else { 0.1 } * style.size;
+        let padding = 0.1 * style.size;
+        let width = num.width.max(den.width) + 2.0 * padding;
+
+        let mut frac = MathBox::default();
+        let num_pos = (
+            (width - num.width) / 2.0,
+            -axis - thickness / 2.0 - gap - num.descent,
+        );
+        let den_pos = (
+            (width - den.width) / 2.0,
+            -axis + thickness / 2.0 + gap + den.ascent,
+        );
+        frac.place(num, num_pos.0, num_pos.1);
+        frac.place(den, den_pos.0, den_pos.1);
+        frac.add_rule(0.0, -axis - thickness / 2.0, width, thickness);
+        Ok(frac)
+    }
+
+    fn sqrt(&self, body: &Node, style: Style) -> Result<MathBox> {
+        let body = self.node(body, style)?;
+        let size = style.size;
+        let thickness = self.rule_thickness(style);
+        let gap = 0.12 * size;
+        let body_ascent = body.ascent.max(0.7 * size);
+        let body_descent = body.descent.max(0.1 * size);
+
+        let top = -(body_ascent + gap + thickness / 2.0);
+        let bottom = body_descent;
+        let height = bottom - top;
+        let sign_width = 0.5 * size + 0.1 * height;
+        let body_x = sign_width + 0.08 * size;
+        let right = body_x + body.width + 0.08 * size;
+
+        let mut root = MathBox::default();
+        // Short upstroke, heavy downstroke to the bottom, long stroke up to the vinculum
+        root.add_line(
+            vec![
+                (0.0, bottom - 0.42 * height),
+                (0.18 * sign_width, bottom - 0.5 * height),
+            ],
+            thickness,
+        );
+        root.add_line(
+            vec![
+                (0.18 * sign_width, bottom - 0.5 * height),
+                (0.5 * sign_width, bottom),
+            ],
+            thickness * 1.8,
+        );
+        root.add_line(
+            vec![(0.5 * sign_width, bottom), (sign_width, top), (right, top)],
+            thickness,
+        );
+        root.place(body, body_x, 0.0);
+        Ok(root)
+    }
+
+    fn delimited(
+        &self,
+        left: Option<char>,
+        right: Option<char>,
+        body: &[Node],
+        style: Style,
+    ) -> Result<MathBox> {
+        let body = self.list(body, style)?;
+        // Delimiters are symmetric around the axis and cover the body
+        let axis = self.axis(style);
+        let half = (body.ascent - axis)
+            .max(body.descent + axis)
+            .max(0.6 * style.size)
+            + 0.1 * style.size;
+
+        let mut hbox = MathBox::default();
+        if let Some(ch) = left {
+            hbox.append(self.delimiter(ch, -axis, half, style)?);
+        }
+        hbox.append(body);
+        if let Some(ch) = right {
+            hbox.append(self.delimiter(ch, -axis, half, style)?);
+        }
+        Ok(hbox)
+    }
+
+    /// Delimiter spanning `center ± half`: drawn for parentheses, brackets and bars,
+    /// scaled glyph otherwise
+    fn delimiter(&self, ch: char, center: f32, half: f32, style: Style) -> Result<MathBox> {
+        let size = style.size;
+        let thickness = self.rule_thickness(style);
+        let (top, bottom) = (center - half, center + half);
+        let width = (0.2 * size + 0.12 * half).min(0.6 * size);
+        let mut delim = MathBox::default();
+
+        match ch {
+            '(' | ')' => {
+                // Elliptic arc bulging away from the body
+                let segments = 16;
+                let (inner, outer) = (0.8 * width, 0.2 * width);
+                let points = (0..=segments)
+                    .map(|i| {
+                        let t = std::f32::consts::PI * i as f32 / segments as f32;
+                        let x = inner - (inner - outer) * t.sin();
+                        let x = if ch == '(' { x } else { width - x };
+                        (x, center - half * t.cos())
+                    })
+                    .collect();
+                delim.add_line(points, thickness * 1.2);
+            }
+            '[' | ']' | '⌊' | '⌋' | '⌈' | '⌉' => {
+                let (stem, arm_from) = if matches!(ch, '[' | '⌊' | '⌈') {
+                    (0.3 * width, 0.3 * width)
+                } else {
+                    (0.7 * width - thickness, 0.2 * width)
+                };
+                let arm = 0.5 * width;
+                delim.add_rule(stem, top, thickness, bottom - top);
+                if matches!(ch, '[' | ']' | '⌈' | '⌉') {
+                    delim.add_rule(arm_from, top, arm, thickness);
+                }
+                if matches!(ch, '[' | ']' | '⌊' | '⌋') {
+                    delim.add_rule(arm_from, bottom - thickness, arm, thickness);
+                }
+            }
+            '|' => delim.add_rule(0.5 * width - thickness / 2.0, top, thickness, bottom - top),
+            '‖' => {
+                delim.add_rule(0.3 * width, top, thickness, bottom - top);
+                delim.add_rule(0.7 * width - thickness, top, thickness, bottom - top);
+            }
+            _ => {
+                let natural = self.glyph(ch, Variant::Upright, size)?;
+                let natural_height = (natural.ascent + natural.descent).max(1.0);
+                let scaled_size = size * ((2.0 * half) / natural_height).max(1.0);
+                let glyph = self.glyph(ch, Variant::Upright, scaled_size)?;
+                let glyph_center = (glyph.descent - glyph.ascent) / 2.0;
+                delim.place(glyph, 0.0, center - glyph_center);
+                return Ok(delim);
+            }
+        }
+        delim.width = width;
+        Ok(delim)
+    }
+
+    fn accent(&self, accent: Option<char>, base: &Node, style: Style) -> Result<MathBox> {
+        let base = self.node(base, style)?;
+        let size = style.size;
+        let gap = 0.08 * size;
+        let mut accented = MathBox::default();
+        let (base_width, base_ascent) = (base.width, base.ascent.max(0.5 * size));
+        accented.append(base);
+
+        match accent {
+            None => {
+                let thickness = self.rule_thickness(style);
+                accented.add_rule(0.0, -(base_ascent + gap + thickness), base_width, thickness);
+            }
+            Some(ch) => {
+                let mark = self.glyph(ch, Variant::Upright, size * 0.8)?;
+                let dx = (base_width - mark.width) / 2.0;
+                // Sit the mark's ink just above the base
+                let dy = -(base_ascent + gap) - mark.descent;
+                accented.place(mark, dx, dy);
+            }
+        }
+        Ok(accented)
+    }
+}
+
+/// Classes after TeX's binary operator rules: a `Bin` with no left operand (or
+/// before a relation, closing delimiter or punctuation) acts as an ordinary atom
+fn effective_classes(nodes: &[Node]) -> Vec<Option<Class>> {
+    let mut classes: Vec<Option<Class>> = nodes.iter().map(Node::class).collect();
+    let mut previous: Option<usize> = None;
+    for i in 0..classes.len() {
+        let Some(class) = classes[i] else {
+            continue;
+        };
+        if class == Class::Bin {
+            let left = previous.and_then(|p| classes[p]);
+            let next = classes[i + 1..].iter().flatten().next().copied();
+            let no_left = matches!(
+                left,
+                None | Some(Class::Bin | Class::Op | Class::Rel | Class::Open | Class::Punct)
+            );
+            let no_right = matches!(next, None | Some(Class::Rel | Class::Close | Class::Punct));
+            if no_left || no_right {
+                classes[i] = Some(Class::Ord);
+            }
+        }
+        previous = Some(i);
+    }
+    classes
+}
+
+/// Space between two atoms in em (medium and thick spaces vanish in scripts)
+fn spacing(left: Class, right: Class, level: u8) -> f32 {
+    let (thin, medium, thick) = (3.0 / 18.0, 4.0 / 18.0, 5.0 / 18.0);
+    let in_script = level > 0;
+    match (left, right) {
+        (Class::Rel, Class::Rel) => 0.0,
+        (Class::Bin, _) | (_, Class::Bin) if !in_script => medium,
+        (Class::Rel, _) | (_, Class::Rel) if !in_script => thick,
+        (Class::Op, Class::Ord | Class::Op) | (Class::Ord | Class::Close, Class::Op) => thin,
+        (Class::Punct, _) if !in_script => thin,
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::parse::parse;
+
+    fn layout(tex: &str, display: bool) -> MathBox {
+        Layouter::new(MathFonts::get(), 20.0)
+            .layout(&parse(tex).unwrap(), display)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_scripts_raise_and_lower() {
+        let x = layout("x", false);
+        let sup = layout("x^2", false);
+        let sub = layout("x_2", false);
+        assert!(sup.width > x.width);
+        assert!(sup.ascent > x.ascent);
+        assert!(sub.descent > x.descent);
+    }
+
+    #[test]
+    fn test_fraction_and_limits_stack() {
+        let frac = layout("\\frac{a}{b}", true);
+        let a = layout("a", true);
+        assert!(frac.ascent > a.ascent && frac.descent > a.descent);
+        assert!(frac
+            .items
+            .iter()
+            .any(|item| matches!(item, Item::Rule { .. })));
+
+        // Display limits stack, inline ones sit beside the operator
+        let display = layout("\\sum_{i=1}^n i", true);
+        let inline = layout("\\sum_{i=1}^n i", false);
+        assert!(display.ascent + display.descent > inline.ascent + inline.descent);
+    }
+
+    #[test]
+    fn test_binary_operator_spacing() {
+        // A leading minus is unary: no space around it
+        let unary = layout("-x", false);
+        let binary = layout("y-x", false);
+        let y = layout("y", false);
+        assert!(binary.width - y.width > unary.width);
+    }
+
+    #[test]
+    fn test_missing_glyph_is_an_error() {
+        let nodes = parse("\\text{\u{1F600}}").unwrap();
+        assert!(Layouter::new(MathFonts::get(), 20.0)
+            .layout(&nodes, false)
+            .is_err());
+    }
+}
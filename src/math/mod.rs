@@ -0,0 +1,85 @@
+/// Math formulas: `$...$` and `$$...$$` rendered offline from a TeX subset
+///
+/// parse (TeX → nodes) → layout (nodes → positioned glyphs, rules and strokes, using
+/// the metrics of the bundled fonts) → raster (→ image for an egui texture). Formulas
+/// outside the supported subset are errors; the renderer shows their source instead.
+mod layout;
+mod parse;
+mod raster;
+
+use anyhow::{bail, Result};
+use eframe::egui;
+use layout::Layouter;
+pub use layout::{px_scale, MathFonts};
+
+/// Height of the center of a text row above its baseline, in em
+///
+/// Inline formulas are centered in their row like any widget, so their images are
+/// padded to put the formula's baseline on the surrounding text's.
+const TEXT_CENTER: f32 = 0.36;
+
+/// Blank border around the formula, in pixels
+const PADDING: f32 = 1.0;
+
+/// Render a formula at `size` pixels per em (physical pixels, for a sharp texture)
+pub fn render_formula(
+    tex: &str,
+    display: bool,
+    size: f32,
+    color: egui::Color32,
+) -> Result<egui::ColorImage> {
+    let nodes = parse::parse(tex)?;
+    if nodes.is_empty() {
+        bail!("empty formula");
+    }
+    let fonts = MathFonts::get();
+    let math = Layouter::new(fonts, size).layout(&nodes, display)?;
+
+    let center = TEXT_CENTER * size;
+    let half = (math.ascent - center).max(math.descent + center);
+    let (ascent, descent) = (center + half, half - center);
+    let width = (math.width + 2.0 * PADDING).ceil() as usize;
+    let height = (ascent + descent + 2.0 * PADDING).ceil() as usize;
+
+    Ok(raster::rasterize(
+        &math,
+        fonts,
+        color,
+        [width, height],
+        (PADDING, PADDING + ascent),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ink(image: &egui::ColorImage) -> usize {
+        image.pixels.iter().filter(|p| p.a() > 0).count()
+    }
+
+    #[test]
+    fn test_render_formula() {
+        let image = render_formula("E = mc^2", false, 20.0, egui::Color32::BLACK).unwrap();
+        assert!(image.size[0] > 40 && image.size[1] >= 20);
+        assert!(ink(&image) > 0);
+
+        // Display style enlarges operators and stacks limits
+        let inline =
+            render_formula("\\sum_{i=1}^n x_i", false, 20.0, egui::Color32::BLACK).unwrap();
+        let display =
+            render_formula("\\sum_{i=1}^n x_i", true, 20.0, egui::Color32::BLACK).unwrap();
+        assert!(display.size[1] > inline.size[1]);
+    }
+
+    #[test]
+    fn test_render_formula_errors() {
+        assert!(render_formula("\\frac{1}{", false, 20.0, egui::Color32::BLACK).is_err());
+        assert!(render_formula("  ", false, 20.0, egui::Color32::BLACK).is_err());
+
+        // Deep nesting is refused rather than overflowing the stack
+        let nested = |depth| format!("{}x{}", "\\sqrt{".repeat(depth), "}".repeat(depth));
+        assert!(render_formula(&nested(30), false, 20.0, egui::Color32::BLACK).is_ok());
+        assert!(render_formula(&nested(5000), false, 20.0, egui::Color32::BLACK).is_err());
+    }
+}
@@ -0,0 +1,603 @@
+/// TeX math subset → node tree
+///
+/// Covers what plans and research notes typically use: letters and digits, Greek,
+/// common operators and relations, `^`/`_` scripts, `\frac`, `\sqrt`, big operators,
+/// operator names (`\sin`, `\lim`), `\left..\right` delimiters, accents, font switches
+/// (`\mathbf`, `\text`, ...) and spacing. Anything else is an error, and the caller
+/// shows the source instead.
+use anyhow::{bail, Result};
+
+/// Font variant of a symbol
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    Italic,
+    Upright,
+    Bold,
+}
+
+/// Spacing class of an atom (TeX's ord/op/bin/rel/open/close/punct)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Class {
+    Ord,
+    Op,
+    Bin,
+    Rel,
+    Open,
+    Close,
+    Punct,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    /// Single character with its font variant and spacing class
+    Symbol {
+        ch: char,
+        variant: Variant,
+        class: Class,
+    },
+    /// Upright run of text (`\text{...}`, `\mathrm{...}`)
+    Text(String),
+    /// Operator name (`\sin`, `\lim`); `limits` puts scripts above/below in display style
+    OpName {
+        name: String,
+        limits: bool,
+    },
+    /// Large operator (∑, ∫)
+    BigOp {
+        ch: char,
+        limits: bool,
+    },
+    /// Braced group (one atom)
+    Group(Vec<Node>),
+    Scripts {
+        base: Box<Node>,
+        sup: Option<Box<Node>>,
+        sub: Option<Box<Node>>,
+    },
+    Frac {
+        num: Box<Node>,
+        den: Box<Node>,
+    },
+    Sqrt(Box<Node>),
+    /// Horizontal space in em (negative for `\!`)
+    Space(f32),
+    /// `\left( ... \right)`: delimiters sized to the body (None for `.`)
+    Delimited {
+        left: Option<char>,
+        right: Option<char>,
+        body: Vec<Node>,
+    },
+    /// Accent over a base (`\hat`, `\vec`, ...); None draws a bar (`\bar`, `\overline`)
+    Accent {
+        accent: Option<char>,
+        base: Box<Node>,
+    },
+}
+
+impl Node {
+    /// Spacing class of the node as an atom (None for spaces, which take no spacing)
+    pub fn class(&self) -> Option<Class> {
+        match self {
+            Node::Symbol { class, .. } => Some(*class),
+            Node::OpName { .. } | Node::BigOp { .. } => Some(Class::Op),
+            Node::Scripts { base, .. } => base.class(),
+            Node::Space(_) => None,
+            _ => Some(Class::Ord),
+        }
+    }
+}
+
+/// Deepest nesting of groups and commands a formula may use (the parser and layout
+/// recurse per level, so unbounded input would overflow the stack)
+const MAX_DEPTH: usize = 64;
+
+/// Parse TeX math source into a node list
+pub fn parse(tex: &str) -> Result<Vec<Node>> {
+    let mut parser = Parser {
+        chars: tex.chars().collect(),
+        pos: 0,
+        depth: 0,
+    };
+    parser.parse_list(Until::End)
+}
+
+/// What ends a node list
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Until {
+    End,
+    Brace,
+    Right,
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    /// Atoms currently being parsed (nesting level)
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn parse_list(&mut self, until: Until) -> Result<Vec<Node>> {
+        let mut nodes: Vec<Node> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let Some(c) = self.peek() else {
+                match until {
+                    Until::End => return Ok(nodes),
+                    Until::Brace => bail!("missing closing brace"),
+                    Until::Right => bail!("\\left without \\right"),
+                }
+            };
+
+            match c {
+                '}' => {
+                    if until != Until::Brace {
+                        bail!("unexpected closing brace");
+                    }
+                    self.pos += 1;
+                    return Ok(nodes);
+                }
+                '^' | '_' => {
+                    self.pos += 1;
+                    let script = self.parse_atom()?;
+                    attach_script(&mut nodes, script, c == '^')?;
+                }
+                '\'' => {
+                    self.pos += 1;
+                    attach_script(&mut nodes, symbol('′', Class::Ord), true)?;
+                }
+                '\\' if self.command_ahead("right") => {
+                    if until != Until::Right {
+                        bail!("\\right without \\left");
+                    }
+                    return Ok(nodes);
+                }
+                _ => nodes.push(self.parse_atom()?),
+            }
+        }
+    }
+
+    /// A single atom: braced group, command or character
+    fn parse_atom(&mut self) -> Result<Node> {
+        if self.depth == MAX_DEPTH {
+            bail!("formula nested more than {} levels deep", MAX_DEPTH);
+        }
+        self.depth += 1;
+        let atom = self.atom();
+        self.depth -= 1;
+        atom
+    }
+
+    fn atom(&mut self) -> Result<Node> {
+        self.skip_whitespace();
+        let Some(c) = self.peek() else {
+            bail!("missing argument");
+        };
+        self.pos += 1;
+        match c {
+            '{' => Ok(Node::Group(self.parse_list(Until::Brace)?)),
+            '\\' => self.parse_command(),
+            '}' | '^' | '_' => bail!("unexpected '{}'", c),
+            '&' | '#' => bail!("unsupported '{}'", c),
+            c => Ok(char_node(c)),
+        }
+    }
+
+    /// Whether `\name` (as a whole command) starts at the current position
+    fn command_ahead(&self, name: &str) -> bool {
+        let start = self.pos + 1;
+        let end = start + name.chars().count();
+        self.chars.get(start..end).is_some_and(|chars| {
+            chars.iter().copied().eq(name.chars())
+                && !self.chars.get(end).is_some_and(|c| c.is_ascii_alphabetic())
+        })
+    }
+
+    /// Command name after a backslash: a run of letters, or one other character
+    fn command_name(&mut self) -> Result<String> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            let Some(c) = self.peek() else {
+                bail!("trailing backslash");
+            };
+            self.pos += 1;
+            return Ok(c.to_string());
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    /// Raw text of a braced argument (for `\text{...}`)
+    fn raw_argument(&mut self) -> Result<String> {
+        self.skip_whitespace();
+        if self.peek() != Some('{') {
+            bail!("expected '{{'");
+        }
+        self.pos += 1;
+        let start = self.pos;
+        let mut depth = 0;
+        while let Some(c) = self.peek() {
+            match c {
+                '{' => depth += 1,
+                '}' if depth == 0 => {
+                    let text = self.chars[start..self.pos].iter().collect();
+                    self.pos += 1;
+                    return Ok(text);
+                }
+                '}' => depth -= 1,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        bail!("missing closing brace")
+    }
+
+    /// Delimiter after `\left`/`\right` (`.` for none)
+    fn delimiter(&mut self) -> Result<Option<char>> {
+        self.skip_whitespace();
+        let Some(c) = self.peek() else {
+            bail!("missing delimiter");
+        };
+        self.pos += 1;
+        if c != '\\' {
+            return Ok((c != '.').then_some(c));
+        }
+        let name = self.command_name()?;
+        match name.as_str() {
+            "{" | "lbrace" => Ok(Some('{')),
+            "}" | "rbrace" => Ok(Some('}')),
+            "|" | "Vert" => Ok(Some('‖')),
+            "vert" => Ok(Some('|')),
+            "langle" => Ok(Some('⟨')),
+            "rangle" => Ok(Some('⟩')),
+            "lfloor" => Ok(Some('⌊')),
+            "rfloor" => Ok(Some('⌋')),
+            "lceil" => Ok(Some('⌈')),
+            "rceil" => Ok(Some('⌉')),
+            _ => bail!("unsupported delimiter \\{}", name),
+        }
+    }
+
+    fn parse_command(&mut self) -> Result<Node> {
+        let name = self.command_name()?;
+        if let Some(node) = symbol_command(&name) {
+            return Ok(node);
+        }
+
+        let node = match name.as_str() {
+            "frac" | "dfrac" | "tfrac" => Node::Frac {
+                num: Box::new(self.parse_atom()?),
+                den: Box::new(self.parse_atom()?),
+            },
+            "sqrt" => {
+                self.skip_whitespace();
+                if self.peek() == Some('[') {
+                    bail!("\\sqrt with an index is not supported");
+                }
+                Node::Sqrt(Box::new(self.parse_atom()?))
+            }
+            "text" | "textrm" | "mathrm" | "operatorname" | "textit" | "mbox" => {
+                Node::Text(self.raw_argument()?)
+            }
+            "mathbf" | "boldsymbol" | "mathbb" | "textbf" => {
+                restyle(self.parse_atom()?, Variant::Bold)
+            }
+            "mathit" => restyle(self.parse_atom()?, Variant::Italic),
+            "mathcal" | "mathsf" | "mathtt" => restyle(self.parse_atom()?, Variant::Upright),
+            "left" => {
+                let left = self.delimiter()?;
+                let body = self.parse_list(Until::Right)?;
+                // parse_list stops at `\right`
+                self.pos += 1;
+                self.command_name()?;
+                let right = self.delimiter()?;
+                Node::Delimited { left, right, body }
+            }
+            "hat" | "widehat" => self.accent(Some('^'))?,
+            "tilde" | "widetilde" => self.accent(Some('~'))?,
+            "vec" => self.accent(Some('→'))?,
+            "dot" => self.accent(Some('·'))?,
+            "bar" | "overline" => self.accent(None)?,
+            "displaystyle" | "textstyle" | "limits" | "nolimits" => Node::Group(Vec::new()),
+            _ => bail!("unsupported command \\{}", name),
+        };
+        Ok(node)
+    }
+
+    fn accent(&mut self, accent: Option<char>) -> Result<Node> {
+        Ok(Node::Accent {
+            accent,
+            base: Box::new(self.parse_atom()?),
+        })
+    }
+}
+
+fn symbol(ch: char, class: Class) -> Node {
+    Node::Symbol {
+        ch,
+        variant: Variant::Upright,
+        class,
+    }
+}
+
+/// Node for a plain character: letters are italic, operators get their class
+fn char_node(c: char) -> Node {
+    match c {
+        c if c.is_alphabetic() => Node::Symbol {
+            ch: c,
+            variant: Variant::Italic,
+            class: Class::Ord,
+        },
+        '+' | '*' => symbol(c, Class::Bin),
+        '-' => symbol('−', Class::Bin),
+        '=' | '<' | '>' | ':' => symbol(c, Class::Rel),
+        ',' | ';' => symbol(c, Class::Punct),
+        '(' | '[' => symbol(c, Class::Open),
+        ')' | ']' | '!' | '?' => symbol(c, Class::Close),
+        c => symbol(c, Class::Ord),
+    }
+}
+
+/// Attach a super- or subscript to the last node of a list
+fn attach_script(nodes: &mut Vec<Node>, script: Node, is_sup: bool) -> Result<()> {
+    let last = nodes.pop().unwrap_or(Node::Group(Vec::new()));
+    let (base, mut sup, mut sub) = match last {
+        Node::Scripts { base, sup, sub } => (base, sup, sub),
+        other => (Box::new(other), None, None),
+    };
+    let slot = if is_sup { &mut sup } else { &mut sub };
+    if slot.is_some() {
+        bail!(
+            "double {}",
+            if is_sup { "superscript" } else { "subscript" }
+        );
+    }
+    *slot = Some(Box::new(script));
+    nodes.push(Node::Scripts { base, sup, sub });
+    Ok(())
+}
+
+/// Apply a font variant to every symbol in a node
+fn restyle(node: Node, variant: Variant) -> Node {
+    match node {
+        Node::Symbol { ch, class, .. } => Node::Symbol { ch, variant, class },
+        Node::Group(nodes) => Node::Group(
+            nodes
+                .into_iter()
+                .map(|node| restyle(node, variant))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// Commands that stand for a single symbol, operator name or space
+fn symbol_command(name: &str) -> Option<Node> {
+    let greek = |ch: char| Node::Symbol {
+        ch,
+        // Lowercase Greek is italic like Latin letters, uppercase upright
+        variant: if ch.is_lowercase() {
+            Variant::Italic
+        } else {
+            Variant::Upright
+        },
+        class: Class::Ord,
+    };
+    let op = |name: &str, limits: bool| Node::OpName {
+        name: name.to_string(),
+        limits,
+    };
+
+    let node = match name {
+        "alpha" => greek('α'),
+        "beta" => greek('β'),
+        "gamma" => greek('γ'),
+        "delta" => greek('δ'),
+        "epsilon" | "varepsilon" => greek('ε'),
+        "zeta" => greek('ζ'),
+        "eta" => greek('η'),
+        "theta" | "vartheta" => greek('θ'),
+        "iota" => greek('ι'),
+        "kappa" => greek('κ'),
+        "lambda" => greek('λ'),
+        "mu" => greek('μ'),
+        "nu" => greek('ν'),
+        "xi" => greek('ξ'),
+        "pi" => greek('π'),
+        "rho" => greek('ρ'),
+        "sigma" => greek('σ'),
+        "tau" => greek('τ'),
+        "upsilon" => greek('υ'),
+        "phi" | "varphi" => greek('φ'),
+        "chi" => greek('χ'),
+        "psi" => greek('ψ'),
+        "omega" => greek('ω'),
+        "Gamma" => greek('Γ'),
+        "Delta" => greek('Δ'),
+        "Theta" => greek('Θ'),
+        "Lambda" => greek('Λ'),
+        "Xi" => greek('Ξ'),
+        "Pi" => greek('Π'),
+        "Sigma" => greek('Σ'),
+        "Upsilon" => greek('Υ'),
+        "Phi" => greek('Φ'),
+        "Psi" => greek('Ψ'),
+        "Omega" => greek('Ω'),
+
+        "cdot" => symbol('·', Class::Bin),
+        "times" => symbol('×', Class::Bin),
+        "div" => symbol('÷', Class::Bin),
+        "pm" => symbol('±', Class::Bin),
+        "mp" => symbol('∓', Class::Bin),
+        "cup" => symbol('∪', Class::Bin),
+        "cap" => symbol('∩', Class::Bin),
+        "wedge" | "land" => symbol('∧', Class::Bin),
+        "vee" | "lor" => symbol('∨', Class::Bin),
+        "circ" => symbol('∘', Class::Bin),
+        "oplus" => symbol('⊕', Class::Bin),
+        "otimes" => symbol('⊗', Class::Bin),
+        "setminus" => symbol('∖', Class::Bin),
+
+        "le" | "leq" => symbol('≤', Class::Rel),
+        "ge" | "geq" => symbol('≥', Class::Rel),
+        "ne" | "neq" => symbol('≠', Class::Rel),
+        "approx" => symbol('≈', Class::Rel),
+        "equiv" => symbol('≡', Class::Rel),
+        "sim" => symbol('∼', Class::Rel),
+        "propto" => symbol('∝', Class::Rel),
+        "ll" => symbol('≪', Class::Rel),
+        "gg" => symbol('≫', Class::Rel),
+        "in" => symbol('∈', Class::Rel),
+        "notin" => symbol('∉', Class::Rel),
+        "subset" => symbol('⊂', Class::Rel),
+        "supset" => symbol('⊃', Class::Rel),
+        "subseteq" => symbol('⊆', Class::Rel),
+        "supseteq" => symbol('⊇', Class::Rel),
+        "to" | "rightarrow" => symbol('→', Class::Rel),
+        "leftarrow" | "gets" => symbol('←', Class::Rel),
+        "leftrightarrow" => symbol('↔', Class::Rel),
+        "Rightarrow" | "implies" => symbol('⇒', Class::Rel),
+        "Leftarrow" => symbol('⇐', Class::Rel),
+        "Leftrightarrow" | "iff" => symbol('⇔', Class::Rel),
+        "mapsto" => symbol('↦', Class::Rel),
+        "mid" => symbol('|', Class::Rel),
+
+        "infty" => symbol('∞', Class::Ord),
+        "partial" => symbol('∂', Class::Ord),
+        "nabla" => symbol('∇', Class::Ord),
+        "forall" => symbol('∀', Class::Ord),
+        "exists" => symbol('∃', Class::Ord),
+        "neg" | "lnot" => symbol('¬', Class::Ord),
+        "emptyset" | "varnothing" => symbol('∅', Class::Ord),
+        "ell" => symbol('ℓ', Class::Ord),
+        "prime" => symbol('′', Class::Ord),
+        "ldots" | "dots" => symbol('…', Class::Ord),
+        "cdots" => symbol('⋯', Class::Ord),
+        "|" => symbol('‖', Class::Ord),
+        "langle" => symbol('⟨', Class::Open),
+        "rangle" => symbol('⟩', Class::Close),
+        "lfloor" => symbol('⌊', Class::Open),
+        "rfloor" => symbol('⌋', Class::Close),
+        "lceil" => symbol('⌈', Class::Open),
+        "rceil" => symbol('⌉', Class::Close),
+        "{" | "lbrace" => symbol('{', Class::Open),
+        "}" | "rbrace" => symbol('}', Class::Close),
+        "%" | "$" | "#" | "&" | "_" => symbol(name.chars().next()?, Class::Ord),
+
+        "sum" => Node::BigOp {
+            ch: '∑',
+            limits: true,
+        },
+        "prod" => Node::BigOp {
+            ch: '∏',
+            limits: true,
+        },
+        "bigcup" => Node::BigOp {
+            ch: '∪',
+            limits: true,
+        },
+        "bigcap" => Node::BigOp {
+            ch: '∩',
+            limits: true,
+        },
+        "int" => Node::BigOp {
+            ch: '∫',
+            limits: false,
+        },
+        "oint" => Node::BigOp {
+            ch: '∮',
+            limits: false,
+        },
+
+        "lim" | "max" | "min" | "sup" | "inf" | "det" | "argmax" | "argmin" => op(name, true),
+        "sin" | "cos" | "tan" | "log" | "ln" | "exp" | "arg" | "deg" | "dim" | "ker" | "gcd"
+        | "Pr" | "sinh" | "cosh" | "tanh" => op(name, false),
+
+        "," => Node::Space(3.0 / 18.0),
+        ":" | ">" => Node::Space(4.0 / 18.0),
+        ";" => Node::Space(5.0 / 18.0),
+        "!" => Node::Space(-3.0 / 18.0),
+        " " => Node::Space(0.25),
+        "quad" => Node::Space(1.0),
+        "qquad" => Node::Space(2.0),
+        _ => return None,
+    };
+    Some(node)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn italic(ch: char) -> Node {
+        Node::Symbol {
+            ch,
+            variant: Variant::Italic,
+            class: Class::Ord,
+        }
+    }
+
+    #[test]
+    fn test_parse_scripts_and_symbols() {
+        let nodes = parse("x_i^2 + \\alpha").unwrap();
+        assert_eq!(
+            nodes,
+            vec![
+                Node::Scripts {
+                    base: Box::new(italic('x')),
+                    sup: Some(Box::new(symbol('2', Class::Ord))),
+                    sub: Some(Box::new(italic('i'))),
+                },
+                symbol('+', Class::Bin),
+                italic('α'),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_structures() {
+        let nodes =
+            parse("\\frac{a}{\\sqrt{b}} \\left( \\sum_{k=1}^n k \\right) \\text{if } y").unwrap();
+        assert!(matches!(&nodes[0], Node::Frac { den, .. } if matches!(**den, Node::Group(_))));
+        let Node::Delimited { left, right, body } = &nodes[1] else {
+            panic!("expected delimited group, got {:?}", nodes[1]);
+        };
+        assert_eq!((*left, *right), (Some('('), Some(')')));
+        assert!(
+            matches!(&body[0], Node::Scripts { base, .. } if matches!(**base, Node::BigOp { ch: '∑', limits: true }))
+        );
+        assert_eq!(nodes[2], Node::Text("if ".to_string()));
+        assert_eq!(nodes[3], italic('y'));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("\\frac{a}{b").is_err());
+        assert!(parse("x^2^3").is_err());
+        assert!(parse("\\unknowncommand").is_err());
+        assert!(parse("\\left( x").is_err());
+        assert!(parse("\\begin{aligned} a & b \\end{aligned}").is_err());
+        assert!(parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_nesting_limit() {
+        let nested = |depth: usize| format!("{}x{}", "{".repeat(depth), "}".repeat(depth));
+        assert!(parse(&nested(MAX_DEPTH - 1)).is_ok());
+        assert!(parse(&nested(5000)).is_err());
+        assert!(parse(&format!("{}x", "\\sqrt".repeat(5000))).is_err());
+        assert!(parse(&format!("\\left({}x", "\\left(".repeat(5000))).is_err());
+    }
+}
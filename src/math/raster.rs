@@ -0,0 +1,134 @@
+/// Rasterize a laid-out formula into an image
+use super::layout::{px_scale, Item, MathBox, MathFonts};
+use ab_glyph::{point, Font};
+use eframe::egui;
+
+/// Per-pixel coverage (0..1) of an image being painted
+struct Canvas {
+    width: usize,
+    height: usize,
+    coverage: Vec<f32>,
+}
+
+impl Canvas {
+    fn cell(&mut self, x: i32, y: i32) -> Option<&mut f32> {
+        let (x, y) = (usize::try_from(x).ok()?, usize::try_from(y).ok()?);
+        (x < self.width && y < self.height).then(|| &mut self.coverage[y * self.width + x])
+    }
+
+    /// Accumulate coverage (glyph outlines, rules)
+    fn add(&mut self, x: i32, y: i32, c: f32) {
+        if let Some(cell) = self.cell(x, y) {
+            *cell = (*cell + c).min(1.0);
+        }
+    }
+
+    /// Keep the stronger coverage (polyline segments overlap at their joints)
+    fn max(&mut self, x: i32, y: i32, c: f32) {
+        if let Some(cell) = self.cell(x, y) {
+            *cell = cell.max(c);
+        }
+    }
+}
+
+/// Paint a box into an image of the given size, with its baseline origin at `origin`
+///
+/// Coverage becomes the alpha of `color`.
+pub fn rasterize(
+    math: &MathBox,
+    fonts: &MathFonts,
+    color: egui::Color32,
+    size: [usize; 2],
+    origin: (f32, f32),
+) -> egui::ColorImage {
+    let mut canvas = Canvas {
+        width: size[0],
+        height: size[1],
+        coverage: vec![0.0; size[0] * size[1]],
+    };
+
+    for item in &math.items {
+        match item {
+            Item::Glyph {
+                font,
+                id,
+                size,
+                x,
+                y,
+            } => {
+                let font = fonts.font(*font);
+                let glyph = id.with_scale_and_position(
+                    px_scale(font, *size),
+                    point(origin.0 + x, origin.1 + y),
+                );
+                if let Some(outline) = font.outline_glyph(glyph) {
+                    let bounds = outline.px_bounds();
+                    outline.draw(|gx, gy, c| {
+                        canvas.add(
+                            bounds.min.x as i32 + gx as i32,
+                            bounds.min.y as i32 + gy as i32,
+                            c,
+                        )
+                    });
+                }
+            }
+            Item::Rule { x, y, w, h } => {
+                let (left, top) = (origin.0 + x, origin.1 + y);
+                let (right, bottom) = (left + w, top + h);
+                for py in top.floor() as i32..bottom.ceil() as i32 {
+                    let cover_y = overlap(py as f32, top, bottom);
+                    for px in left.floor() as i32..right.ceil() as i32 {
+                        canvas.add(px, py, cover_y * overlap(px as f32, left, right));
+                    }
+                }
+            }
+            Item::Line { points, width } => {
+                let half = width / 2.0;
+                for segment in points.windows(2) {
+                    let a = (origin.0 + segment[0].0, origin.1 + segment[0].1);
+                    let b = (origin.0 + segment[1].0, origin.1 + segment[1].1);
+                    let reach = half + 1.0;
+                    for py in
+                        (a.1.min(b.1) - reach).floor() as i32..(a.1.max(b.1) + reach).ceil() as i32
+                    {
+                        for px in (a.0.min(b.0) - reach).floor() as i32
+                            ..(a.0.max(b.0) + reach).ceil() as i32
+                        {
+                            let distance =
+                                segment_distance((px as f32 + 0.5, py as f32 + 0.5), a, b);
+                            canvas.max(px, py, (half + 0.5 - distance).clamp(0.0, 1.0));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let pixels = canvas
+        .coverage
+        .into_iter()
+        .map(|c| {
+            let alpha = (c * color.a() as f32).round() as u8;
+            egui::Color32::from_rgba_unmultiplied(color.r(), color.g(), color.b(), alpha)
+        })
+        .collect();
+    egui::ColorImage::new(size, pixels)
+}
+
+/// Length of the pixel span `[cell, cell + 1]` covered by `[from, to]`
+fn overlap(cell: f32, from: f32, to: f32) -> f32 {
+    ((cell + 1.0).min(to) - cell.max(from)).max(0.0)
+}
+
+/// Distance from a point to the segment a-b
+fn segment_distance(p: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared > 0.0 {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (cx, cy) = (a.0 + t * dx, a.1 + t * dy);
+    ((p.0 - cx).powi(2) + (p.1 - cy).powi(2)).sqrt()
+}
@@ -9,26 +9,81 @@ pub enum Alignment {
     Right,
 }
 
+/// List item marker (on the first chunk of each item)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListItem {
+    /// Item number in an ordered list (None for bullet lists)
+    pub number: Option<u64>,
+    /// GFM task list checkbox state (None for plain items)
+    pub checked: Option<bool>,
+}
+
+/// GitHub alert kind of a blockquote (`> [!NOTE]`, `> [!WARNING]`, ...)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlertKind {
+    Note,
+    Tip,
+    Important,
+    Warning,
+    Caution,
+}
+
+impl AlertKind {
+    /// Callout title shown above the alert's content
+    pub fn title(self) -> &'static str {
+        match self {
+            AlertKind::Note => "Note",
+            AlertKind::Tip => "Tip",
+            AlertKind::Important => "Important",
+            AlertKind::Warning => "Warning",
+            AlertKind::Caution => "Caution",
+        }
+    }
+}
+
+/// Blockquote around a chunk (the innermost one when nested)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Quote {
+    /// Nesting depth (1 for a top-level blockquote)
+    pub depth: usize,
+    /// GitHub alert kind, if the blockquote is an alert
+    pub alert: Option<AlertKind>,
+    /// First chunk of the blockquote (alerts show their title above it)
+    pub first: bool,
+}
+
+/// Math formula kind (the chunk text is the formula's TeX source)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Math {
+    /// `$...$`, flowing with the surrounding text
+    Inline,
+    /// `$$...$$`, on its own centered row
+    Display,
+}
+
+/// Footnote role of a chunk, by footnote label
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Footnote {
+    /// `[^label]` reference (the chunk text is the footnote number, shown raised)
+    Reference(String),
+    /// Content of the `[^label]:` definition, moved to the footer of the document
+    Definition(String),
+}
+
 /// A rendered chunk of text with its source position
 #[derive(Clone, Debug)]
 pub struct TextChunk {
     pub text: String,
-    /// Byte range in source markdown (for column precision feature)
-    #[allow(dead_code)]
+    /// Byte range in source markdown (maps rendered characters back to source)
     pub byte_range: Range<usize>,
-    /// Line/col range in source markdown (1-indexed)
+    /// Line range in source markdown (1-indexed; `byte_range` is column-precise)
     pub line_start: usize,
-    /// Column start (for column precision feature)
-    #[allow(dead_code)]
-    pub col_start: usize,
     pub line_end: usize,
-    /// Column end (for column precision feature)
-    #[allow(dead_code)]
-    pub col_end: usize,
     /// Styling
     pub bold: bool,
     pub italic: bool,
     pub code: bool,
+    pub strikethrough: bool,
     pub heading_level: Option<u8>,
     /// Layout hints
     pub newline_after: bool,
@@ -44,6 +99,50 @@ pub struct TextChunk {
     pub code_block_lang: Option<String>,
     /// Table data (if this is a table)
     pub table: Option<Table>,
+    /// Link destination (if this text is inside a link)
+    pub link: Option<String>,
+    /// List nesting depth (0 outside lists, 1 inside a top-level list)
+    pub list_depth: usize,
+    /// List item marker (if this chunk starts a list item)
+    pub list_item: Option<ListItem>,
+    /// Enclosing blockquote (if this chunk is quoted)
+    pub quote: Option<Quote>,
+    /// Footnote reference or definition content
+    pub footnote: Option<Footnote>,
+    /// Math formula (if this chunk is one)
+    pub math: Option<Math>,
     /// Cached render height (for lazy loading without flicker)
     pub cached_height: Option<f32>,
 }
+
+/// Map a character index in rendered text back to a source byte offset
+///
+/// Rendered text that matches its source span byte-for-byte maps exactly. Text that
+/// was transformed during parsing (entities, escapes) snaps to the span boundaries.
+/// The result is always a char boundary of `source`.
+pub fn source_byte_at(
+    source: &str,
+    text: &str,
+    byte_range: &Range<usize>,
+    char_index: usize,
+) -> usize {
+    let byte = if text.len() == byte_range.len() {
+        let offset = text
+            .char_indices()
+            .nth(char_index)
+            .map(|(idx, _)| idx)
+            .unwrap_or(text.len());
+        byte_range.start + offset
+    } else if char_index == 0 {
+        byte_range.start
+    } else {
+        byte_range.end
+    };
+
+    // Same-length text can still differ from its source (e.g. multibyte chars moved)
+    let mut byte = byte.min(source.len());
+    while !source.is_char_boundary(byte) {
+        byte -= 1;
+    }
+    byte
+}
@@ -1,3 +1,5 @@
+use crate::diff::HunkContext;
+
 /// A review comment with source position
 #[derive(Clone)]
 pub struct Comment {
@@ -6,6 +8,11 @@ pub struct Comment {
     pub col_start: usize,
     pub line_end: usize,
     pub col_end: usize,
+    /// Original excerpt, kept once the anchored text no longer exists (None = anchor valid)
+    pub outdated_text: Option<String>,
+    /// Diff rows the comment was made on (`--diff` row selections, which can include
+    /// removed lines); None = derived from the line range when stored
+    pub hunk: Option<HunkContext>,
 }
 
 impl Comment {
@@ -22,13 +29,27 @@ impl Comment {
             col_start,
             line_end,
             col_end,
+            outdated_text: None,
+            hunk: None,
         }
     }
 
-    pub fn format(&self) -> String {
+    /// The commented text was removed from the document (e.g. after a reload)
+    pub fn is_outdated(&self) -> bool {
+        self.outdated_text.is_some()
+    }
+
+    /// Source anchor label, e.g. "[L5:C10 → L5:C15]"
+    pub fn location(&self) -> String {
         format!(
-            "[L{}:C{} → L{}:C{}] {}",
-            self.line_start, self.col_start, self.line_end, self.col_end, self.text
+            "[L{}:C{} → L{}:C{}]",
+            self.line_start, self.col_start, self.line_end, self.col_end
         )
     }
+
+    /// Location and text on one line (used in tests and logs)
+    #[allow(dead_code)]
+    pub fn format(&self) -> String {
+        format!("{} {}", self.location(), self.text)
+    }
 }
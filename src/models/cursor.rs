@@ -0,0 +1,133 @@
+/// Keyboard line cursor: a visible marker moved line-by-line or block-by-block
+use super::Selection;
+
+/// Cursor movement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorMotion {
+    LineDown,
+    LineUp,
+    /// Start of the next block (rendered chunk, or hunk in diff mode)
+    BlockDown,
+    /// Start of the current block, or of the previous one when already there
+    BlockUp,
+}
+
+/// Current cursor position
+#[derive(Debug, Default, Clone)]
+pub struct LineCursor {
+    /// Cursor line (1-indexed); None until the first cursor key press
+    pub line: Option<usize>,
+    /// Set when the cursor moves; the view scrolls it into view and clears the flag
+    pub reveal: bool,
+}
+
+impl LineCursor {
+    /// Line reached from `from` by a motion, clamped to [1, line_count]
+    ///
+    /// `blocks` are the sorted line ranges of the document's blocks. Line motions skip
+    /// lines outside any block (blank lines render nothing to put the cursor on);
+    /// without blocks every line is a stop and block motions go to the first/last line.
+    pub fn target(
+        from: usize,
+        motion: CursorMotion,
+        line_count: usize,
+        blocks: &[(usize, usize)],
+    ) -> usize {
+        let last = line_count.max(1);
+        let in_block = |line: usize| {
+            blocks.is_empty()
+                || blocks
+                    .iter()
+                    .any(|&(start, end)| line >= start && line <= end)
+        };
+        let line = match motion {
+            CursorMotion::LineDown => (from + 1..=last).find(|&line| in_block(line)),
+            CursorMotion::LineUp => (1..from).rev().find(|&line| in_block(line)),
+            CursorMotion::BlockDown => blocks
+                .iter()
+                .map(|&(start, _)| start)
+                .find(|&start| start > from)
+                .or(blocks.is_empty().then_some(last)),
+            CursorMotion::BlockUp => blocks
+                .iter()
+                .rev()
+                .map(|&(start, _)| start)
+                .find(|&start| start < from)
+                .or(blocks.is_empty().then_some(1)),
+        };
+        // Nothing further in that direction: stay put
+        line.unwrap_or(from).clamp(1, last)
+    }
+
+    /// Move the cursor; `extend` grows the selection to the new line instead of
+    /// collapsing it
+    ///
+    /// The first plain press only shows the cursor (at the selection end, or line 1).
+    /// Extending continues from the selection end, so mouse selections can be
+    /// adjusted from the keyboard.
+    pub fn move_by(
+        &mut self,
+        motion: CursorMotion,
+        extend: bool,
+        selection: &mut Selection,
+        line_count: usize,
+        blocks: &[(usize, usize)],
+    ) {
+        let from = if extend {
+            selection.end_line.or(self.line)
+        } else {
+            self.line.or(selection.end_line)
+        };
+        let line = match from {
+            Some(from) if extend || self.line.is_some() => {
+                Self::target(from, motion, line_count, blocks)
+            }
+            Some(from) => from,
+            None => 1,
+        };
+
+        if extend {
+            let from = from.unwrap_or(line);
+            if !selection.is_active() {
+                selection.select_lines(from, from);
+            }
+            selection.extend_lines(line as isize - from as isize, line_count);
+        } else if !selection.is_dragging {
+            selection.clear();
+        }
+
+        self.line = Some(line);
+        self.reveal = true;
+    }
+
+    /// Select the cursor line (or keep an existing selection) as the comment anchor
+    /// Returns false when there is nothing to anchor
+    pub fn select_line(&self, selection: &mut Selection) -> bool {
+        if selection.is_active() {
+            return true;
+        }
+        match self.line {
+            Some(line) => {
+                selection.select_lines(line, line);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Line to scroll into view this frame (clears the reveal flag)
+    pub fn take_reveal(&mut self) -> Option<usize> {
+        if std::mem::take(&mut self.reveal) {
+            self.line
+        } else {
+            None
+        }
+    }
+
+    /// Keep the cursor inside the document after it shrinks (live reload)
+    pub fn clamp(&mut self, line_count: usize) {
+        if let Some(line) = &mut self.line {
+            *line = (*line).clamp(1, line_count.max(1));
+        }
+    }
+}
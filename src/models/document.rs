@@ -1,7 +1,80 @@
+use crate::anchoring::{relocate, AnchorStatus};
+use crate::diagram::diagram_kind;
+use crate::diff::{DiffView, HunkContext, RevisionDiff};
 use crate::image_manager::ImageManager;
-use crate::models::{Comment, LayoutMap, Selection, TextChunk};
-use crate::storage::{ProjectType, ReviewStorage};
-use std::path::PathBuf;
+use crate::models::{
+    Comment, CursorMotion, FrontMatter, LayoutMap, LineCursor, ReviewRound, Selection, TextChunk,
+    Verdict,
+};
+use crate::parsing::{parse_front_matter, parse_markdown, LineOffsets};
+use crate::search::SearchState;
+use crate::storage::{content_hash, CommentRecord, ProjectType, ReviewComment, ReviewStorage};
+use crate::watcher::FileWatcher;
+use anyhow::Context;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Extract text snippet from source for the given anchor
+///
+/// Columns are 1-indexed with an exclusive end; col 0 on either end means
+/// whole lines (line-only selections).
+pub fn extract_text_snippet(
+    source: &str,
+    start_line: usize,
+    start_col: usize,
+    end_line: usize,
+    end_col: usize,
+) -> String {
+    if start_col > 0 && end_col > 0 {
+        let offsets = LineOffsets::new(source);
+        let start = offsets.line_col_to_byte(source, start_line, start_col);
+        let end = offsets.line_col_to_byte(source, end_line, end_col);
+        return source[start..end.max(start)].to_string();
+    }
+
+    let lines: Vec<&str> = source.lines().collect();
+    let start_idx = start_line.saturating_sub(1).min(lines.len()); // Lines are 1-indexed
+    let end_idx = end_line.min(lines.len()).max(start_idx);
+
+    lines[start_idx..end_idx].join("\n")
+}
+
+/// Outcome of reloading a file that changed on disk
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReloadSummary {
+    /// Queued comments whose anchor moved (exact or fuzzy match)
+    pub moved: usize,
+    /// Queued comments whose text no longer exists
+    pub outdated: usize,
+}
+
+/// Carry cached render heights over to re-parsed chunks with identical text
+/// (matching prefix and suffix), so unchanged regions keep stable scroll geometry
+fn carry_cached_heights(old: &[TextChunk], new: &mut [TextChunk]) {
+    let same = |a: &TextChunk, b: &TextChunk| a.text == b.text;
+
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(a, b)| same(a, b))
+        .count();
+    for i in 0..prefix {
+        new[i].cached_height = old[i].cached_height;
+    }
+
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let suffix = old
+        .iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| same(a, b))
+        .count();
+    for i in 0..suffix {
+        let (old_idx, new_idx) = (old.len() - 1 - i, new.len() - 1 - i);
+        new[new_idx].cached_height = old[old_idx].cached_height;
+    }
+}
 
 /// A single document being reviewed
 pub struct Document {
@@ -10,7 +83,11 @@ pub struct Document {
     pub base_path: PathBuf,
     pub file_path: PathBuf,
     pub chunks: Option<Vec<TextChunk>>,
+    /// Metadata from a leading `---`/`+++` block (shown as a card, stored with reviews)
+    pub front_matter: Option<FrontMatter>,
     pub selection: Selection,
+    /// Keyboard line cursor (moves and extends the selection without the mouse)
+    pub cursor: LineCursor,
     pub comment_text: String,
     pub comments: Vec<Comment>,
     pub image_manager: ImageManager,
@@ -18,6 +95,28 @@ pub struct Document {
     pub storage: ReviewStorage,
     pub project_type: ProjectType,
     pub approved: bool,
+    /// Review outcome (reported via --json on exit)
+    pub verdict: Verdict,
+    /// Path returned by the storage backend when the verdict was written
+    pub review_path: Option<PathBuf>,
+    /// Line to scroll into view on the next frame (e.g. jump to a queued comment)
+    pub scroll_to_line: Option<usize>,
+    /// Heading or footnote line to scroll to the top of the view (contents click, anchor link)
+    pub scroll_to_heading: Option<usize>,
+    /// Heading line of the section at the top of the view (updated every frame)
+    pub current_heading: Option<usize>,
+    /// In-document search (Ctrl+F)
+    pub search: SearchState,
+    /// Previously stored review rounds, oldest first (see `load_history`)
+    pub history: Vec<ReviewRound>,
+    /// Detects on-disk changes to `file_path`
+    pub watcher: FileWatcher,
+    /// Set after a live reload (drives the "file changed" banner until dismissed)
+    pub reload_summary: Option<ReloadSummary>,
+    /// Diff against an older revision (`--diff OLD NEW`; this document is NEW)
+    pub diff: Option<DiffView>,
+    /// Changes since a git revision (`--since REV`), shown as gutter markers
+    pub revision: Option<RevisionDiff>,
 }
 
 impl Document {
@@ -32,6 +131,8 @@ impl Document {
     ) -> Self {
         let storage = ReviewStorage::new(out_dir, filename.clone(), session_id);
         let image_manager = ImageManager::new(&base_path);
+        let watcher = FileWatcher::new(file_path.clone());
+        let front_matter = parse_front_matter(&source);
 
         Self {
             filename,
@@ -39,7 +140,9 @@ impl Document {
             base_path,
             file_path,
             chunks: None, // Parse lazily on first render
+            front_matter,
             selection: Selection::default(),
+            cursor: LineCursor::default(),
             comment_text: String::new(),
             comments: Vec::new(),
             image_manager,
@@ -47,7 +150,352 @@ impl Document {
             storage,
             project_type,
             approved: false,
+            verdict: Verdict::default(),
+            review_path: None,
+            scroll_to_line: None,
+            scroll_to_heading: None,
+            current_heading: None,
+            search: SearchState::default(),
+            history: Vec::new(),
+            watcher,
+            reload_summary: None,
+            diff: None,
+            revision: None,
+        }
+    }
+
+    /// Read a markdown file into a Document
+    ///
+    /// Review files go to `out_dir`, or next to the file when not given. Prior review
+    /// rounds are not loaded (see load_history).
+    pub fn open(
+        file_path: &Path,
+        out_dir: Option<&Path>,
+        session_id: Option<String>,
+        project_type: ProjectType,
+    ) -> anyhow::Result<Self> {
+        let source = std::fs::read_to_string(file_path)
+            .with_context(|| format!("Failed to read {}", file_path.display()))?;
+
+        let base_path = file_path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        let filename = file_path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown.md")
+            .to_string();
+
+        // Convert to absolute path for Hegel mode
+        let abs_file_path = if file_path.is_absolute() {
+            file_path.to_path_buf()
+        } else {
+            std::env::current_dir()
+                .unwrap_or_else(|_| Path::new(".").to_path_buf())
+                .join(file_path)
+        };
+
+        // Use explicit out_dir if provided, otherwise use file's directory
+        let review_dir = out_dir.map_or_else(|| base_path.clone(), Path::to_path_buf);
+
+        Ok(Self::new(
+            filename,
+            source,
+            base_path,
+            abs_file_path,
+            review_dir,
+            session_id,
+            project_type,
+        ))
+    }
+
+    /// Re-read the file after it changed on disk
+    ///
+    /// Re-parses the markdown (keeping cached heights of unchanged chunks) and re-anchors
+    /// queued comments, the current selection and prior review rounds in the new source.
+    pub fn reload(&mut self) -> anyhow::Result<ReloadSummary> {
+        let new_source = std::fs::read_to_string(&self.file_path)
+            .with_context(|| format!("Failed to read {:?}", self.file_path))?;
+        let mut summary = ReloadSummary::default();
+
+        for comment in &mut self.comments {
+            // Already-outdated comments keep their original excerpt
+            if comment.is_outdated() {
+                let line_count = new_source.lines().count().max(1);
+                comment.line_start = comment.line_start.min(line_count);
+                comment.line_end = comment.line_end.min(line_count);
+                summary.outdated += 1;
+                continue;
+            }
+
+            let snippet = extract_text_snippet(
+                &self.source,
+                comment.line_start,
+                comment.col_start,
+                comment.line_end,
+                comment.col_end,
+            );
+            let anchor = relocate(
+                &new_source,
+                &snippet,
+                comment.line_start,
+                comment.col_start,
+                comment.line_end,
+                comment.col_end,
+            );
+
+            match anchor.status {
+                AnchorStatus::Unchanged => {}
+                AnchorStatus::Moved | AnchorStatus::Fuzzy => summary.moved += 1,
+                AnchorStatus::Outdated => {
+                    summary.outdated += 1;
+                    comment.outdated_text = Some(snippet);
+                }
+            }
+            comment.line_start = anchor.line_start;
+            comment.col_start = anchor.col_start;
+            comment.line_end = anchor.line_end;
+            comment.col_end = anchor.col_end;
+        }
+
+        // Keep the selection on the same text (byte offsets would be stale mid-drag)
+        if self.selection.is_dragging {
+            self.selection.start_byte = None;
+            self.selection.end_byte = None;
+        } else if let Some((line_start, col_start, line_end, col_end)) =
+            self.selection.anchor(&self.source)
+        {
+            let snippet =
+                extract_text_snippet(&self.source, line_start, col_start, line_end, col_end);
+            let anchor = relocate(
+                &new_source,
+                &snippet,
+                line_start,
+                col_start,
+                line_end,
+                col_end,
+            );
+            if anchor.is_outdated() {
+                self.selection
+                    .select_lines(anchor.line_start, anchor.line_end);
+            } else {
+                self.selection.select_anchor(
+                    &new_source,
+                    anchor.line_start,
+                    anchor.col_start,
+                    anchor.line_end,
+                    anchor.col_end,
+                );
+            }
+        }
+
+        self.cursor.clamp(new_source.lines().count());
+        self.search.invalidate();
+
+        for round in &mut self.history {
+            round.reanchor(&new_source);
+        }
+
+        if let Some(diff) = &mut self.diff {
+            diff.refresh(&new_source);
+        }
+        if let Some(revision) = &mut self.revision {
+            revision.refresh(&new_source);
+        }
+
+        // Unparsed documents stay lazy (parsed on first display)
+        if let Some(old_chunks) = self.chunks.take() {
+            let mut chunks = parse_markdown(&new_source, &self.base_path, &mut self.image_manager);
+            carry_cached_heights(&old_chunks, &mut chunks);
+
+            // Drop rendered formulas and diagrams the new source no longer contains
+            let rendered: HashSet<String> = chunks
+                .iter()
+                .filter(|chunk| {
+                    chunk.math.is_some()
+                        || (chunk.code_block_lang.as_deref())
+                            .is_some_and(|lang| diagram_kind(lang).is_some())
+                })
+                .map(|chunk| content_hash(&chunk.text))
+                .collect();
+            self.image_manager.retain_rendered(&rendered);
+            self.chunks = Some(chunks);
+        }
+
+        self.front_matter = parse_front_matter(&new_source);
+        self.source = new_source;
+        self.reload_summary = Some(summary);
+        Ok(summary)
+    }
+
+    /// Load prior review rounds from storage (routes to appropriate backend)
+    /// The most recent round with comments starts visible; others are toggled on demand
+    pub fn load_history(&mut self) -> anyhow::Result<()> {
+        use crate::storage::{compute_relative_path, read_review_entries, read_review_file};
+
+        let source = &self.source;
+        let mut rounds = match &self.project_type {
+            ProjectType::Hegel { root } => {
+                let relative_path = compute_relative_path(root, &self.file_path)?;
+                read_review_entries(root)?
+                    .remove(&relative_path)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|entry| ReviewRound::from_entry(entry, source))
+                    .collect()
+            }
+            ProjectType::Standalone => self
+                .storage
+                .review_files()
+                .into_iter()
+                .map(|(_, path)| {
+                    read_review_file(&path)
+                        .map(|records| ReviewRound::from_records(records, source))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?,
+        };
+
+        if let Some(latest) = rounds.iter_mut().rev().find(|r| !r.comments.is_empty()) {
+            latest.visible = true;
+        }
+        self.history = rounds;
+        Ok(())
+    }
+
+    /// Record the review outcome and mark the document as done
+    pub fn record_verdict(&mut self, verdict: Verdict, review_path: Option<PathBuf>) {
+        self.verdict = verdict;
+        self.review_path = review_path;
+        self.approved = true;
+    }
+
+    /// Line ranges of the document's blocks, sorted (cursor stops)
+    /// Diff mode uses hunks; otherwise parsed chunks (none before the first render)
+    pub fn blocks(&self) -> Vec<(usize, usize)> {
+        let mut blocks: Vec<(usize, usize)> = match (&self.diff, &self.chunks) {
+            (Some(diff), _) => diff
+                .hunks
+                .iter()
+                .filter_map(|hunk| hunk.new_range())
+                .collect(),
+            (None, Some(chunks)) => chunks
+                .iter()
+                .map(|chunk| (chunk.line_start, chunk.line_end))
+                .collect(),
+            (None, None) => Vec::new(),
+        };
+        blocks.sort_unstable();
+        blocks.dedup();
+        blocks
+    }
+
+    /// Move the keyboard cursor (`extend` grows the selection with it)
+    pub fn move_cursor(&mut self, motion: CursorMotion, extend: bool) {
+        let line_count = self.source.lines().count();
+        let blocks = self.blocks();
+        self.cursor
+            .move_by(motion, extend, &mut self.selection, line_count, &blocks);
+    }
+
+    /// Queue a comment anchored to the current selection, then clear the selection
+    /// Returns false if there is no selection or the comment is blank
+    pub fn add_comment(&mut self, text: String) -> bool {
+        let Some((line_start, col_start, line_end, col_end)) = self.selection.anchor(&self.source)
+        else {
+            return false;
+        };
+        if text.trim().is_empty() {
+            return false;
+        }
+
+        let mut comment = Comment::new(text, line_start, col_start, line_end, col_end);
+        if let Some(diff) = &mut self.diff {
+            comment.hunk = diff.rows_context(self.selection.line_range());
+            diff.selected_rows = None;
+        }
+        self.comments.push(comment);
+        self.selection.clear();
+        true
+    }
+
+    /// Write queued comments as a review and mark the document as reviewed
+    /// (comments edited down to blank text are dropped)
+    pub fn submit_review(&mut self) -> anyhow::Result<PathBuf> {
+        self.comments.retain(|c| !c.text.trim().is_empty());
+        if self.comments.is_empty() {
+            anyhow::bail!("No comments queued for {}", self.filename);
+        }
+
+        let path = self.write_review_with_hunks(self.comment_data(), self.comment_hunks())?;
+        self.record_verdict(Verdict::Reviewed, Some(path.clone()));
+        Ok(path)
+    }
+
+    /// Write an approval (LGTM) and mark the document as approved
+    pub fn approve(&mut self) -> anyhow::Result<PathBuf> {
+        let path = self.write_approval()?;
+        self.record_verdict(Verdict::Lgtm, Some(path.clone()));
+        Ok(path)
+    }
+
+    /// Write a rejection (Nope) and mark the document as rejected - more discussion needed
+    pub fn reject(&mut self, reason: Option<String>) -> anyhow::Result<PathBuf> {
+        let path = self.write_rejection(reason)?;
+        self.record_verdict(Verdict::Nope, Some(path.clone()));
+        Ok(path)
+    }
+
+    /// Build (text, comment, line_start, col_start, line_end, col_end) tuples for storage
+    pub fn comment_data(&self) -> Vec<(String, String, usize, usize, usize, usize)> {
+        self.comments
+            .iter()
+            .map(|c| {
+                (
+                    self.comment_snippet(c),
+                    c.text.clone(),
+                    c.line_start,
+                    c.col_start,
+                    c.line_end,
+                    c.col_end,
+                )
+            })
+            .collect()
+    }
+
+    /// Text a comment refers to: the original excerpt for outdated comments, the
+    /// unified diff rows for diff selections with removed lines, else the anchored
+    /// source text
+    fn comment_snippet(&self, c: &Comment) -> String {
+        if let Some(text) = &c.outdated_text {
+            return text.clone();
+        }
+
+        let diff_text = self.diff.as_ref().and_then(|diff| {
+            let context = self.comment_hunk(c)?;
+            diff.removed_text(&context)
+        });
+        if let Some(text) = diff_text {
+            return text;
         }
+
+        extract_text_snippet(
+            &self.source,
+            c.line_start,
+            c.col_start,
+            c.line_end,
+            c.col_end,
+        )
+    }
+
+    /// Diff-hunk context per queued comment (all None outside diff mode)
+    pub fn comment_hunks(&self) -> Vec<Option<HunkContext>> {
+        self.comments.iter().map(|c| self.comment_hunk(c)).collect()
+    }
+
+    /// Diff rows a comment was made on, else the hunk its NEW lines fall in
+    fn comment_hunk(&self, c: &Comment) -> Option<HunkContext> {
+        let diff = self.diff.as_ref()?;
+        c.hunk
+            .clone()
+            .or_else(|| diff.context_for(c.line_start, c.line_end))
     }
 
     /// Count of comments for this document (for tab label)
@@ -55,106 +503,298 @@ impl Document {
         self.comments.len()
     }
 
-    /// Write review comments (routes to appropriate backend)
+    /// Write review comments without diff context
+    #[cfg(test)]
     pub fn write_review(
         &self,
         comments: Vec<(String, String, usize, usize, usize, usize)>,
     ) -> anyhow::Result<PathBuf> {
-        use crate::storage::{
-            compute_relative_path, read_hegel_reviews, write_hegel_reviews, HegelReviewEntry,
-            ReviewComment,
-        };
+        self.write_review_with_hunks(comments, Vec::new())
+    }
+
+    /// Write review comments with per-comment diff-hunk context (routes to appropriate backend)
+    pub fn write_review_with_hunks(
+        &self,
+        comments: Vec<(String, String, usize, usize, usize, usize)>,
+        hunks: Vec<Option<HunkContext>>,
+    ) -> anyhow::Result<PathBuf> {
+        use crate::storage::{append_review_entry, compute_relative_path, ReviewEntry};
 
         match &self.project_type {
             ProjectType::Hegel { root } => {
                 // Compute relative path for this file
                 let relative_path = compute_relative_path(root, &self.file_path)?;
 
-                // Read existing reviews
-                let mut reviews_map = read_hegel_reviews(root)?;
-
-                // Create review comments
-                let review_comments: Vec<ReviewComment> = comments
-                    .into_iter()
-                    .map(
-                        |(text, comment, line_start, col_start, line_end, col_end)| {
-                            ReviewComment::new(
-                                relative_path.clone(),
-                                self.storage.session_id.clone(),
-                                text,
-                                comment,
-                                line_start,
-                                col_start,
-                                line_end,
-                                col_end,
-                            )
-                        },
-                    )
-                    .collect();
-
-                // Create new entry
-                let entry = HegelReviewEntry {
-                    comments: review_comments,
-                    timestamp: chrono::Utc::now().to_rfc3339(),
-                    session_id: self.storage.session_id.clone(),
-                };
-
-                // Append to file's review list
-                reviews_map
-                    .entry(relative_path.clone())
-                    .or_insert_with(Vec::new)
-                    .push(entry);
-
-                // Write back to disk
-                write_hegel_reviews(root, &reviews_map)?;
+                // Append new entry to file's review list
+                let entry = ReviewEntry::new(
+                    self.comment_records(&relative_path, comments, hunks),
+                    self.storage.session_id.clone(),
+                    Verdict::Reviewed,
+                    None,
+                );
+                append_review_entry(root, &relative_path, entry)?;
 
                 // Return path to reviews.json
                 Ok(root.join("reviews.json"))
             }
             ProjectType::Standalone => {
                 // Use existing ReviewStorage logic
-                self.storage.write_review(comments)
+                let records = self.comment_records(&self.filename, comments, hunks);
+                self.storage.write_comment_records(records)
             }
         }
     }
 
+    /// Build stored comment records for `file`, attaching diff hunks (`hunks[i]` belongs
+    /// to `comments[i]`) and, in `--since` mode, the base revision and content hash
+    fn comment_records(
+        &self,
+        file: &str,
+        comments: Vec<(String, String, usize, usize, usize, usize)>,
+        hunks: Vec<Option<HunkContext>>,
+    ) -> Vec<CommentRecord> {
+        let base = self.revision.as_ref().map(|revision| revision.base.clone());
+        let source_hash = base.as_ref().map(|_| content_hash(&self.source));
+        let mut hunks = hunks.into_iter();
+
+        comments
+            .into_iter()
+            .map(
+                |(text, comment, line_start, col_start, line_end, col_end)| CommentRecord {
+                    comment: ReviewComment::new(
+                        file.to_string(),
+                        self.storage.session_id.clone(),
+                        text,
+                        comment,
+                        line_start,
+                        col_start,
+                        line_end,
+                        col_end,
+                    ),
+                    hunk: hunks.next().flatten(),
+                    base: base.clone(),
+                    content_hash: source_hash.clone(),
+                    front_matter: self.front_matter.clone(),
+                    extra: serde_json::Map::new(),
+                },
+            )
+            .collect()
+    }
+
     /// Write approval (LGTM) (routes to appropriate backend)
     pub fn write_approval(&self) -> anyhow::Result<PathBuf> {
-        use crate::storage::{
-            compute_relative_path, read_hegel_reviews, write_hegel_reviews, HegelReviewEntry,
-        };
+        use crate::storage::{append_review_entry, compute_relative_path, ReviewEntry};
 
         match &self.project_type {
             ProjectType::Hegel { root } => {
                 // Compute relative path for this file
                 let relative_path = compute_relative_path(root, &self.file_path)?;
 
-                // Read existing reviews
-                let mut reviews_map = read_hegel_reviews(root)?;
+                // Append LGTM entry with empty comments
+                let entry =
+                    ReviewEntry::new(vec![], self.storage.session_id.clone(), Verdict::Lgtm, None)
+                        .with_front_matter(self.front_matter.clone());
+                append_review_entry(root, &relative_path, entry)?;
 
-                // Create LGTM entry with empty comments
-                let entry = HegelReviewEntry {
-                    comments: vec![],
-                    timestamp: chrono::Utc::now().to_rfc3339(),
-                    session_id: self.storage.session_id.clone(),
-                };
+                // Return path to reviews.json
+                Ok(root.join("reviews.json"))
+            }
+            ProjectType::Standalone => {
+                // Use existing ReviewStorage logic
+                self.storage
+                    .write_approval(&self.source, self.front_matter.clone())
+            }
+        }
+    }
 
-                // Append to file's review list
-                reviews_map
-                    .entry(relative_path.clone())
-                    .or_insert_with(Vec::new)
-                    .push(entry);
+    /// Write rejection (Nope) with optional reason (routes to appropriate backend)
+    pub fn write_rejection(&self, reason: Option<String>) -> anyhow::Result<PathBuf> {
+        use crate::storage::{append_review_entry, compute_relative_path, ReviewEntry};
 
-                // Write back to disk
-                write_hegel_reviews(root, &reviews_map)?;
+        match &self.project_type {
+            ProjectType::Hegel { root } => {
+                // Compute relative path for this file
+                let relative_path = compute_relative_path(root, &self.file_path)?;
+
+                // Append rejection entry with empty comments
+                let entry = ReviewEntry::new(
+                    vec![],
+                    self.storage.session_id.clone(),
+                    Verdict::Nope,
+                    reason,
+                )
+                .with_front_matter(self.front_matter.clone());
+                append_review_entry(root, &relative_path, entry)?;
 
                 // Return path to reviews.json
                 Ok(root.join("reviews.json"))
             }
             ProjectType::Standalone => {
-                // Use existing ReviewStorage logic
-                self.storage.write_approval()
+                self.storage
+                    .write_rejection(&self.source, reason, self.front_matter.clone())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_whole_lines() {
+        let source = "line one\nline two\nline three";
+        assert_eq!(
+            extract_text_snippet(source, 2, 0, 3, 0),
+            "line two\nline three"
+        );
+    }
+
+    #[test]
+    fn test_extract_column_precise() {
+        let source = "line one\nline two\nline three";
+        assert_eq!(extract_text_snippet(source, 2, 6, 2, 9), "two");
+        assert_eq!(extract_text_snippet(source, 1, 6, 2, 5), "one\nline");
+    }
+
+    #[test]
+    fn test_extract_out_of_range_lines() {
+        assert_eq!(extract_text_snippet("only line", 3, 0, 5, 0), "");
+    }
+
+    fn reload_document(temp_dir: &tempfile::TempDir, source: &str) -> Document {
+        let file_path = temp_dir.path().join("SPEC.md");
+        std::fs::write(&file_path, source).unwrap();
+        Document::new(
+            "SPEC.md".to_string(),
+            source.to_string(),
+            temp_dir.path().to_path_buf(),
+            file_path,
+            temp_dir.path().to_path_buf(),
+            None,
+            ProjectType::Standalone,
+        )
+    }
+
+    #[test]
+    fn test_cursor_moves_between_parsed_blocks() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut doc = reload_document(&temp_dir, "# Title\n\nFirst para.\n\nSecond para.\n");
+        doc.chunks = Some(parse_markdown(
+            &doc.source,
+            &doc.base_path,
+            &mut doc.image_manager,
+        ));
+
+        doc.move_cursor(CursorMotion::LineDown, false);
+        doc.move_cursor(CursorMotion::LineDown, false);
+        assert_eq!(doc.cursor.line, Some(3));
+
+        doc.move_cursor(CursorMotion::BlockDown, true);
+        assert_eq!(doc.cursor.line, Some(5));
+        assert!(doc.add_comment("Both paragraphs".to_string()));
+        assert_eq!(
+            (doc.comments[0].line_start, doc.comments[0].line_end),
+            (3, 5)
+        );
+    }
+
+    #[test]
+    fn test_reload_reanchors_comments_and_selection() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut doc = reload_document(&temp_dir, "# Title\n\nKeep me\n\nDrop me\n");
+
+        doc.selection.select_lines(3, 3);
+        assert!(doc.add_comment("Kept".to_string()));
+        doc.selection.select_lines(5, 5);
+        assert!(doc.add_comment("Dropped".to_string()));
+        doc.selection.select_lines(3, 3);
+
+        std::fs::write(&doc.file_path, "# Title\n\nNew intro\n\nKeep me\n").unwrap();
+        let summary = doc.reload().unwrap();
+
+        assert_eq!(
+            summary,
+            ReloadSummary {
+                moved: 1,
+                outdated: 1
             }
+        );
+        assert_eq!(doc.reload_summary, Some(summary));
+        assert!(doc.source.contains("New intro"));
+        assert_eq!(doc.selection.line_range(), Some((5, 5)));
+
+        let data = doc.comment_data();
+        assert_eq!(data[0].0, "Keep me");
+        assert_eq!((data[0].2, data[0].4), (5, 5));
+        assert!(doc.comments[1].is_outdated());
+        // Outdated comments keep the originally commented text
+        assert_eq!(data[1].0, "Drop me");
+    }
+
+    #[test]
+    fn test_reload_keeps_unparsed_document_lazy() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut doc = reload_document(&temp_dir, "# Title\n");
+
+        std::fs::write(&doc.file_path, "# Renamed\n").unwrap();
+        doc.reload().unwrap();
+        assert!(doc.chunks.is_none());
+        assert_eq!(doc.source, "# Renamed\n");
+    }
+
+    #[test]
+    fn test_reload_drops_renders_of_removed_formulas() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut doc = reload_document(&temp_dir, "Kept $a+b$ and $x^2$\n");
+        doc.chunks = Some(parse_markdown(
+            &doc.source,
+            &doc.base_path,
+            &mut doc.image_manager,
+        ));
+        let ctx = eframe::egui::Context::default();
+        let image = || Ok(eframe::egui::ColorImage::filled([1, 1], Default::default()));
+        for formula in ["a+b", "x^2"] {
+            doc.image_manager
+                .get_or_render_texture(&ctx, formula, formula, image)
+                .unwrap();
         }
+
+        std::fs::write(&doc.file_path, "Kept $a+b$ and $y^2$\n").unwrap();
+        doc.reload().unwrap();
+
+        let mut renders = Vec::new();
+        for formula in ["a+b", "x^2"] {
+            doc.image_manager
+                .get_or_render_texture(&ctx, formula, formula, || {
+                    renders.push(formula);
+                    image()
+                })
+                .unwrap();
+        }
+        assert_eq!(renders, vec!["x^2"]);
+    }
+
+    #[test]
+    fn test_blank_comments_are_not_queued_or_submitted() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut doc = reload_document(&temp_dir, "# Title\n\nFirst para.\n");
+
+        doc.selection.select_lines(3, 3);
+        assert!(!doc.add_comment("  \n".to_string()));
+        assert!(doc.add_comment("Keep".to_string()));
+        doc.selection.select_lines(1, 1);
+        assert!(doc.add_comment("Edited away".to_string()));
+
+        // Inline editing cleared the second comment
+        doc.comments[1].text = " ".to_string();
+        let path = doc.submit_review().unwrap();
+        assert_eq!(doc.comments.len(), 1);
+        let written = std::fs::read_to_string(path).unwrap();
+        assert!(written.contains("Keep") && !written.contains("Edited away"));
+
+        // Nothing left to submit once every comment is blank
+        doc.comments[0].text.clear();
+        assert!(doc.submit_review().is_err());
     }
 }
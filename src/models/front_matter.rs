@@ -0,0 +1,42 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+
+/// Document metadata from a leading `---` (YAML) or `+++` (TOML) block
+///
+/// Values are flattened to display strings (lists joined with ", "); entries keep their
+/// order in the document. Serialized as a JSON object.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FrontMatter {
+    pub entries: Vec<(String, String)>,
+}
+
+impl FrontMatter {
+    /// Value of a key, if present
+    #[cfg(test)]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Serialize for FrontMatter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.entries.iter().map(|(k, v)| (k, v)))
+    }
+}
+
+impl<'de> Deserialize<'de> for FrontMatter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Stored order is not preserved on read (only used for reports)
+        let map = BTreeMap::<String, String>::deserialize(deserializer)?;
+        Ok(Self {
+            entries: map.into_iter().collect(),
+        })
+    }
+}
@@ -1,3 +1,40 @@
+use crate::models::source_byte_at;
+use eframe::egui;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// A rendered text chunk with its laid-out glyphs (for character-level selection)
+#[derive(Clone)]
+pub struct TextSpan {
+    /// Screen area covered by the text
+    pub rect: egui::Rect,
+    /// The galley the text was drawn from, and where it was drawn (inline text wrapped
+    /// after other chunks starts its first row indented, so this can be left of `rect`)
+    pub galley: Arc<egui::Galley>,
+    pub galley_pos: egui::Pos2,
+    pub byte_range: Range<usize>,
+    pub line_start: usize,
+    pub line_end: usize,
+}
+
+impl TextSpan {
+    /// Character index under a screen position (clamped into the span)
+    pub fn char_index_at(&self, pos: egui::Pos2) -> usize {
+        let pos = self.rect.clamp(pos);
+        self.galley.cursor_from_pos(pos - self.galley_pos).index
+    }
+
+    /// Source byte offset under a screen position
+    pub fn source_byte_at(&self, source: &str, pos: egui::Pos2) -> usize {
+        source_byte_at(
+            source,
+            self.galley.text(),
+            &self.byte_range,
+            self.char_index_at(pos),
+        )
+    }
+}
+
 /// Layout information for selection bar rendering
 /// Maps line numbers to Y coordinates in the rendered document
 #[derive(Default)]
@@ -6,6 +43,9 @@ pub struct LayoutMap {
     /// Each entry: (line_start, line_end, y_start, y_end)
     pub chunks: Vec<(usize, usize, f32, f32)>,
 
+    /// Rendered text spans (recorded alongside chunks, for column-precise selection)
+    pub text_spans: Vec<TextSpan>,
+
     /// Legacy field for compatibility during refactor
     pub line_positions: Vec<(usize, f32, f32)>,
 }
@@ -24,9 +64,35 @@ impl LayoutMap {
     /// Clear all cached positions (call at start of frame)
     pub fn clear(&mut self) {
         self.chunks.clear();
+        self.text_spans.clear();
         self.line_positions.clear();
     }
 
+    /// Record a rendered text span
+    pub fn record_text_span(&mut self, span: TextSpan) {
+        if span.line_start > 0 {
+            self.text_spans.push(span);
+        }
+    }
+
+    /// Find the text span under a screen position
+    /// Falls back to the horizontally nearest span on the same row (gaps between inline chunks)
+    pub fn text_span_at(&self, pos: egui::Pos2) -> Option<&TextSpan> {
+        self.text_spans
+            .iter()
+            .find(|span| span.rect.contains(pos))
+            .or_else(|| {
+                self.text_spans
+                    .iter()
+                    .filter(|span| span.rect.y_range().contains(pos.y))
+                    .min_by(|a, b| {
+                        a.rect
+                            .distance_to_pos(pos)
+                            .total_cmp(&b.rect.distance_to_pos(pos))
+                    })
+            })
+    }
+
     /// Record that lines [line_start..=line_end] occupy Y range [y_start, y_end]
     pub fn record_chunk(&mut self, line_start: usize, line_end: usize, y_start: f32, y_end: f32) {
         if line_start > 0 {
@@ -1,15 +1,25 @@
 mod chunk;
 mod comment;
+mod cursor;
 mod document;
+mod front_matter;
 mod layout;
 mod review_mode;
+mod review_round;
 mod selection;
 mod table;
+mod toc;
+mod verdict;
 
-pub use chunk::{Alignment, TextChunk};
+pub use chunk::{source_byte_at, AlertKind, Alignment, Footnote, ListItem, Math, Quote, TextChunk};
 pub use comment::Comment;
-pub use document::Document;
-pub use layout::LayoutMap;
+pub use cursor::{CursorMotion, LineCursor};
+pub use document::{extract_text_snippet, Document};
+pub use front_matter::FrontMatter;
+pub use layout::{LayoutMap, TextSpan};
 pub use review_mode::ReviewMode;
+pub use review_round::ReviewRound;
 pub use selection::Selection;
 pub use table::Table;
+pub use toc::{build_toc, current_section, TocEntry};
+pub use verdict::Verdict;
@@ -0,0 +1,158 @@
+use crate::anchoring::{relocate_comment, Anchor};
+use crate::models::Verdict;
+use crate::storage::{content_hash, ReviewComment, ReviewEntry, ReviewRecord};
+
+/// A stored comment with its position in the current document
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredComment {
+    pub comment: ReviewComment,
+    pub anchor: Anchor,
+    /// Content hash of the source the anchor was computed in
+    pub anchored_in: String,
+}
+
+/// A previously stored review round, shown as margin markers when reopening a document
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewRound {
+    pub timestamp: String,
+    /// Recorded verdict (None for entries written before verdicts were stored)
+    pub verdict: Option<Verdict>,
+    pub comments: Vec<StoredComment>,
+    /// Whether this round's markers are drawn
+    pub visible: bool,
+}
+
+impl ReviewRound {
+    /// Build a round from a `.hegel/reviews.json` entry, anchored in the current source
+    pub fn from_entry(entry: ReviewEntry, source: &str) -> Self {
+        let mut round = Self {
+            timestamp: entry.timestamp,
+            verdict: entry.verdict,
+            comments: Vec::new(),
+            visible: false,
+        };
+        round.push_comments(
+            entry.comments.into_iter().map(|record| record.comment),
+            source,
+        );
+        round
+    }
+
+    /// Build a round from the records of one `.review.N` sidecar, anchored in the current source
+    pub fn from_records(records: Vec<ReviewRecord>, source: &str) -> Self {
+        let mut round = Self {
+            timestamp: String::new(),
+            verdict: None,
+            comments: Vec::new(),
+            visible: false,
+        };
+
+        let mut comments = Vec::new();
+        for record in records {
+            match record {
+                ReviewRecord::Comment(comment) => {
+                    if round.timestamp.is_empty() {
+                        round.timestamp = comment.timestamp.clone();
+                    }
+                    comments.push(comment);
+                }
+                ReviewRecord::Verdict(verdict) => {
+                    round.timestamp = verdict.timestamp;
+                    round.verdict = Some(verdict.verdict);
+                }
+            }
+        }
+
+        // Comment-only sidecars predate verdict records
+        if round.verdict.is_none() && !comments.is_empty() {
+            round.verdict = Some(Verdict::Reviewed);
+        }
+
+        round.push_comments(comments, source);
+        round
+    }
+
+    /// Re-anchor comments in the current source (exact, then fuzzy; unmatched are outdated)
+    fn push_comments(&mut self, comments: impl IntoIterator<Item = ReviewComment>, source: &str) {
+        let hash = content_hash(source);
+        self.comments
+            .extend(comments.into_iter().map(|comment| StoredComment {
+                anchor: relocate_comment(source, &comment),
+                comment,
+                anchored_in: hash.clone(),
+            }));
+    }
+
+    /// Recompute anchors after the document changed (skipped if the content is the same)
+    pub fn reanchor(&mut self, source: &str) {
+        let hash = content_hash(source);
+        for stored in &mut self.comments {
+            if stored.anchored_in != hash {
+                stored.anchor = relocate_comment(source, &stored.comment);
+                stored.anchored_in = hash.clone();
+            }
+        }
+    }
+
+    /// Number of comments whose anchor text no longer exists
+    pub fn outdated_count(&self) -> usize {
+        self.comments
+            .iter()
+            .filter(|c| c.anchor.is_outdated())
+            .count()
+    }
+
+    /// Toggle label, e.g. "Round 2 · 2025-01-01 12:00 · 3 comments"
+    pub fn label(&self, number: usize) -> String {
+        let when = chrono::DateTime::parse_from_rfc3339(&self.timestamp)
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|_| self.timestamp.clone());
+
+        let outcome = match (self.comments.len(), self.verdict) {
+            (0, Some(Verdict::Lgtm)) => "LGTM".to_string(),
+            (0, Some(Verdict::Nope)) => "Nope".to_string(),
+            (1, _) => "1 comment".to_string(),
+            (n, _) => format!("{} comments", n),
+        };
+
+        match self.outdated_count() {
+            0 => format!("Round {} · {} · {}", number, when, outcome),
+            outdated => format!(
+                "Round {} · {} · {} ({} outdated)",
+                number, when, outcome, outdated
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::anchoring::AnchorStatus;
+
+    #[test]
+    fn test_reanchor_skips_unchanged_source() {
+        let comment = ReviewComment::new(
+            "spec.md".to_string(),
+            None,
+            "The system must be fast.".to_string(),
+            "How fast?".to_string(),
+            1,
+            0,
+            1,
+            0,
+        );
+        let source = "# Spec\n\nThe system must be very fast.\n";
+        let mut round = ReviewRound::from_records(vec![ReviewRecord::Comment(comment)], source);
+        assert_eq!(round.comments[0].anchor.status, AnchorStatus::Fuzzy);
+
+        // Same content: the cached anchor is kept as-is
+        round.comments[0].anchor.line_start = 99;
+        round.reanchor(source);
+        assert_eq!(round.comments[0].anchor.line_start, 99);
+
+        round.reanchor("The system must be fast.\n");
+        assert_eq!(round.comments[0].anchor.status, AnchorStatus::Unchanged);
+        assert_eq!(round.comments[0].anchor.line_start, 1);
+    }
+}
@@ -1,9 +1,15 @@
+use crate::parsing::LineOffsets;
+
 /// Current selection state
 #[derive(Default, Clone)]
 pub struct Selection {
-    /// Line-based selection (MVP: no column precision)
+    /// Line range of the selection (drives the margin bar)
     pub start_line: Option<usize>,
     pub end_line: Option<usize>,
+    /// Source byte offsets when the selection started/ended inside rendered text
+    /// (None for line-only selections: images, tables, code blocks, scripted lines)
+    pub start_byte: Option<usize>,
+    pub end_byte: Option<usize>,
     /// Track if we're currently dragging to select
     pub is_dragging: bool,
 }
@@ -20,12 +26,31 @@ impl Selection {
     pub fn start_drag(&mut self, line: usize) {
         self.start_line = Some(line);
         self.end_line = Some(line);
+        self.start_byte = None;
+        self.end_byte = None;
         self.is_dragging = true;
     }
 
+    /// Start dragging at a precise source byte offset
+    pub fn start_drag_at(&mut self, line: usize, byte: usize) {
+        self.start_drag(line);
+        self.start_byte = Some(byte);
+        self.end_byte = Some(byte);
+    }
+
+    /// Extend the drag to a line (drops column precision)
     pub fn update_drag(&mut self, line: usize) {
         if self.is_dragging {
             self.end_line = Some(line);
+            self.end_byte = None;
+        }
+    }
+
+    /// Extend the drag to a precise source byte offset
+    pub fn update_drag_at(&mut self, line: usize, byte: usize) {
+        if self.is_dragging {
+            self.end_line = Some(line);
+            self.end_byte = Some(byte);
         }
     }
 
@@ -33,6 +58,95 @@ impl Selection {
         self.is_dragging = false;
     }
 
+    /// Select a complete line range without dragging (scripted/keyboard selection)
+    pub fn select_lines(&mut self, start_line: usize, end_line: usize) {
+        self.start_line = Some(start_line);
+        self.end_line = Some(end_line);
+        self.start_byte = None;
+        self.end_byte = None;
+        self.is_dragging = false;
+    }
+
+    /// Move the selection end by `delta` lines, keeping the start (keyboard selection)
+    /// Starts at line 1 when nothing is selected; clamps to [1, line_count]
+    pub fn extend_lines(&mut self, delta: isize, line_count: usize) {
+        let last = line_count.max(1);
+        match (self.start_line, self.end_line) {
+            (Some(start), Some(end)) => {
+                let end = end.saturating_add_signed(delta).clamp(1, last);
+                self.select_lines(start, end);
+            }
+            _ => self.select_lines(1, 1),
+        }
+    }
+
+    /// Select a precise source byte range without dragging (scripted/keyboard selection)
+    pub fn select_bytes(&mut self, source: &str, start_byte: usize, end_byte: usize) {
+        let offsets = LineOffsets::new(source);
+        let (start_line, _) = offsets.byte_to_line_col(source, start_byte);
+        let (end_line, _) = offsets.byte_to_line_col(source, end_byte);
+
+        self.select_lines(start_line, end_line);
+        self.start_byte = Some(start_byte);
+        self.end_byte = Some(end_byte);
+    }
+
+    /// Normalized (min, max) line range, regardless of drag direction
+    pub fn line_range(&self) -> Option<(usize, usize)> {
+        match (self.start_line, self.end_line) {
+            (Some(start), Some(end)) if start <= end => Some((start, end)),
+            (Some(start), Some(end)) => Some((end, start)),
+            _ => None,
+        }
+    }
+
+    /// Re-select a stored comment anchor (col 0 = whole lines, as written by `anchor`)
+    pub fn select_anchor(
+        &mut self,
+        source: &str,
+        line_start: usize,
+        col_start: usize,
+        line_end: usize,
+        col_end: usize,
+    ) {
+        if col_start > 0 && col_end > 0 {
+            let offsets = LineOffsets::new(source);
+            let start = offsets.line_col_to_byte(source, line_start, col_start);
+            let end = offsets.line_col_to_byte(source, line_end, col_end);
+            self.select_bytes(source, start, end);
+        } else {
+            self.select_lines(line_start, line_end);
+        }
+    }
+
+    /// Normalized (min, max) source byte range, if the selection is column-precise
+    /// Empty ranges (a click without movement) fall back to whole lines
+    pub fn byte_range(&self) -> Option<(usize, usize)> {
+        match (self.start_byte, self.end_byte) {
+            (Some(start), Some(end)) if start < end => Some((start, end)),
+            (Some(start), Some(end)) if start > end => Some((end, start)),
+            _ => None,
+        }
+    }
+
+    /// Source anchor (line_start, col_start, line_end, col_end) for a comment
+    ///
+    /// Column-precise selections use 1-indexed columns with an exclusive end;
+    /// line-only selections use col 0 (whole lines).
+    pub fn anchor(&self, source: &str) -> Option<(usize, usize, usize, usize)> {
+        let (min_line, max_line) = self.line_range()?;
+
+        match self.byte_range() {
+            Some((start, end)) => {
+                let offsets = LineOffsets::new(source);
+                let (line_start, col_start) = offsets.byte_to_line_col(source, start);
+                let (line_end, col_end) = offsets.byte_to_line_col(source, end);
+                Some((line_start, col_start, line_end, col_end))
+            }
+            None => Some((min_line, 0, max_line, 0)),
+        }
+    }
+
     /// Check if a line is within the selected range (for future highlighting feature)
     #[allow(dead_code)]
     pub fn contains_line(&self, line: usize) -> bool {
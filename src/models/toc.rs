@@ -0,0 +1,78 @@
+/// Table of contents built from parsed heading chunks
+use super::{Comment, LayoutMap, TextChunk};
+
+/// A heading and the section it introduces
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TocEntry {
+    /// Heading level (1-6)
+    pub level: u8,
+    pub title: String,
+    /// Heading line (1-indexed)
+    pub line: usize,
+    /// Last line of the section, including nested subsections
+    pub section_end: usize,
+}
+
+impl TocEntry {
+    /// Queued comments starting inside this section (subsections included)
+    pub fn comment_count(&self, comments: &[Comment]) -> usize {
+        comments
+            .iter()
+            .filter(|c| c.line_start >= self.line && c.line_start <= self.section_end)
+            .count()
+    }
+}
+
+/// Collect headings in document order
+///
+/// Styled headings are split into several chunks on the same line; their text is
+/// joined into one title.
+pub fn build_toc(chunks: &[TextChunk], line_count: usize) -> Vec<TocEntry> {
+    let mut entries: Vec<TocEntry> = Vec::new();
+
+    for chunk in chunks {
+        let Some(level) = chunk.heading_level else {
+            continue;
+        };
+        match entries.last_mut() {
+            Some(last) if last.line == chunk.line_start => last.title.push_str(&chunk.text),
+            _ => entries.push(TocEntry {
+                level,
+                title: chunk.text.clone(),
+                line: chunk.line_start,
+                section_end: line_count.max(chunk.line_start),
+            }),
+        }
+    }
+
+    // A section ends before the next heading of the same or a higher level
+    for i in 0..entries.len() {
+        let (level, line) = (entries[i].level, entries[i].line);
+        if let Some(next) = entries[i + 1..].iter().find(|e| e.level <= level) {
+            entries[i].section_end = next.line.saturating_sub(1).max(line);
+        }
+        entries[i].title = entries[i].title.trim().to_string();
+    }
+
+    entries
+}
+
+/// Index of the section at the top of the view: the last heading at or above `top_y`
+/// (`tolerance` px below counts too, so a heading scrolled exactly to the top wins)
+pub fn current_section(
+    entries: &[TocEntry],
+    layout_map: &LayoutMap,
+    top_y: f32,
+    tolerance: f32,
+) -> Option<usize> {
+    entries
+        .iter()
+        .enumerate()
+        .take_while(|(_, entry)| {
+            layout_map
+                .get_line_y(entry.line)
+                .is_some_and(|y| y <= top_y + tolerance)
+        })
+        .last()
+        .map(|(i, _)| i)
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// Review outcome for a single document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    /// Approved without comments
+    Lgtm,
+    /// Rejected - more discussion needed
+    Nope,
+    /// Comments submitted (changes requested)
+    Reviewed,
+    /// No decision made before exit
+    #[default]
+    Unreviewed,
+}
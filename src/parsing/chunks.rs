@@ -1,8 +1,20 @@
 use crate::image_manager::ImageManager;
-use crate::models::{Alignment, Table, TextChunk};
+use crate::models::{Alignment, Math, Table, TextChunk};
 use crate::parsing::position::LineOffsets;
 use std::ops::Range;
 
+/// Inline formatting of the text being parsed
+#[derive(Debug, Clone, Default)]
+pub struct InlineStyle {
+    pub bold: bool,
+    pub italic: bool,
+    pub strikethrough: bool,
+    pub heading_level: Option<u8>,
+    pub in_code_block: bool,
+    /// Info string of the enclosing fenced code block
+    pub code_block_lang: Option<String>,
+}
+
 /// Push a text chunk to the chunks vector
 pub fn push_text_chunk(
     chunks: &mut Vec<TextChunk>,
@@ -10,37 +22,38 @@ pub fn push_text_chunk(
     source: &str,
     line_offsets: &LineOffsets,
     range: &Range<usize>,
-    bold: bool,
-    italic: bool,
-    in_code_block: bool,
-    heading_level: Option<u8>,
-    code_block_lang: &Option<String>,
+    style: &InlineStyle,
 ) {
-    let (line_start, col_start) = line_offsets.byte_to_line_col(source, range.start);
-    let (line_end, col_end) = line_offsets.byte_to_line_col(source, range.end);
+    let (line_start, _) = line_offsets.byte_to_line_col(source, range.start);
+    let (line_end, _) = line_offsets.byte_to_line_col(source, range.end);
 
     chunks.push(TextChunk {
         text,
         byte_range: range.clone(),
         line_start,
-        col_start,
         line_end,
-        col_end,
-        bold,
-        italic,
-        code: in_code_block,
-        heading_level,
+        bold: style.bold,
+        italic: style.italic,
+        code: style.in_code_block,
+        strikethrough: style.strikethrough,
+        heading_level: style.heading_level,
         newline_after: false,
         image_path: None,
         alignment: None,
         image_width: None,
         image_height: None,
-        code_block_lang: if in_code_block {
-            code_block_lang.clone()
+        code_block_lang: if style.in_code_block {
+            style.code_block_lang.clone()
         } else {
             None
         },
         table: None,
+        link: None,
+        list_depth: 0,
+        list_item: None,
+        quote: None,
+        footnote: None,
+        math: None,
         cached_height: None,
     });
 }
@@ -52,24 +65,21 @@ pub fn push_code_chunk(
     source: &str,
     line_offsets: &LineOffsets,
     range: &Range<usize>,
-    bold: bool,
-    italic: bool,
-    heading_level: Option<u8>,
+    style: &InlineStyle,
 ) {
-    let (line_start, col_start) = line_offsets.byte_to_line_col(source, range.start);
-    let (line_end, col_end) = line_offsets.byte_to_line_col(source, range.end);
+    let (line_start, _) = line_offsets.byte_to_line_col(source, range.start);
+    let (line_end, _) = line_offsets.byte_to_line_col(source, range.end);
 
     chunks.push(TextChunk {
         text,
         byte_range: range.clone(),
         line_start,
-        col_start,
         line_end,
-        col_end,
-        bold,
-        italic,
+        bold: style.bold,
+        italic: style.italic,
         code: true,
-        heading_level,
+        strikethrough: style.strikethrough,
+        heading_level: style.heading_level,
         newline_after: false,
         image_path: None,
         alignment: None,
@@ -77,10 +87,42 @@ pub fn push_code_chunk(
         image_height: None,
         code_block_lang: None,
         table: None,
+        link: None,
+        list_depth: 0,
+        list_item: None,
+        quote: None,
+        footnote: None,
+        math: None,
         cached_height: None,
     });
 }
 
+/// Push a math formula chunk (text is the TeX source) to the chunks vector
+pub fn push_math_chunk(
+    chunks: &mut Vec<TextChunk>,
+    text: String,
+    source: &str,
+    line_offsets: &LineOffsets,
+    range: &Range<usize>,
+    math: Math,
+    heading_level: Option<u8>,
+) {
+    push_text_chunk(
+        chunks,
+        text,
+        source,
+        line_offsets,
+        range,
+        &InlineStyle {
+            heading_level,
+            ..InlineStyle::default()
+        },
+    );
+    if let Some(last) = chunks.last_mut() {
+        last.math = Some(math);
+    }
+}
+
 /// Push a break chunk (soft or hard break) to the chunks vector
 pub fn push_break_chunk(
     chunks: &mut Vec<TextChunk>,
@@ -92,12 +134,11 @@ pub fn push_break_chunk(
         text,
         byte_range: range.clone(),
         line_start: 0,
-        col_start: 0,
         line_end: 0,
-        col_end: 0,
         bold: false,
         italic: false,
         code: false,
+        strikethrough: false,
         heading_level: None,
         newline_after: newline,
         image_path: None,
@@ -106,6 +147,12 @@ pub fn push_break_chunk(
         image_height: None,
         code_block_lang: None,
         table: None,
+        link: None,
+        list_depth: 0,
+        list_item: None,
+        quote: None,
+        footnote: None,
+        math: None,
         cached_height: None,
     });
 }
@@ -142,8 +189,8 @@ pub fn push_image_chunk_with_alignment(
     width: Option<f32>,
     image_manager: &mut ImageManager,
 ) {
-    let (line_start, col_start) = line_offsets.byte_to_line_col(source, range.start);
-    let (line_end, col_end) = line_offsets.byte_to_line_col(source, range.end);
+    let (line_start, _) = line_offsets.byte_to_line_col(source, range.start);
+    let (line_end, _) = line_offsets.byte_to_line_col(source, range.end);
 
     // Store the relative URL (not full path) - ImageManager resolves against base_path
     let image_path = url.to_string();
@@ -165,12 +212,11 @@ pub fn push_image_chunk_with_alignment(
         text: format!("[Image: {}]", url),
         byte_range: range.clone(),
         line_start,
-        col_start,
         line_end,
-        col_end,
         bold: false,
         italic: false,
         code: false,
+        strikethrough: false,
         heading_level: None,
         newline_after: true,
         image_path: Some(image_path),
@@ -179,6 +225,12 @@ pub fn push_image_chunk_with_alignment(
         image_height,
         code_block_lang: None,
         table: None,
+        link: None,
+        list_depth: 0,
+        list_item: None,
+        quote: None,
+        footnote: None,
+        math: None,
         cached_height: None,
     });
 }
@@ -191,19 +243,18 @@ pub fn push_table_chunk(
     line_offsets: &LineOffsets,
     range: &Range<usize>,
 ) {
-    let (line_start, col_start) = line_offsets.byte_to_line_col(source, range.start);
-    let (line_end, col_end) = line_offsets.byte_to_line_col(source, range.end);
+    let (line_start, _) = line_offsets.byte_to_line_col(source, range.start);
+    let (line_end, _) = line_offsets.byte_to_line_col(source, range.end);
 
     chunks.push(TextChunk {
         text: "[Table]".to_string(),
         byte_range: range.clone(),
         line_start,
-        col_start,
         line_end,
-        col_end,
         bold: false,
         italic: false,
         code: false,
+        strikethrough: false,
         heading_level: None,
         newline_after: true,
         image_path: None,
@@ -212,6 +263,12 @@ pub fn push_table_chunk(
         image_height: None,
         code_block_lang: None,
         table: Some(table),
+        link: None,
+        list_depth: 0,
+        list_item: None,
+        quote: None,
+        footnote: None,
+        math: None,
         cached_height: None,
     });
 }
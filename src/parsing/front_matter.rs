@@ -0,0 +1,178 @@
+use crate::models::FrontMatter;
+use pulldown_cmark::{Event, MetadataBlockKind, Options, Parser, Tag, TagEnd};
+
+/// Front matter block syntaxes (also enabled in parse_markdown, which skips the block)
+pub fn metadata_options() -> Options {
+    Options::ENABLE_YAML_STYLE_METADATA_BLOCKS | Options::ENABLE_PLUSES_DELIMITED_METADATA_BLOCKS
+}
+
+/// Extract front matter key/values from the start of a document
+///
+/// Returns None when the document has no metadata block (or an empty one).
+pub fn parse_front_matter(source: &str) -> Option<FrontMatter> {
+    let mut events = Parser::new_ext(source, metadata_options());
+    let Some(Event::Start(Tag::MetadataBlock(kind))) = events.next() else {
+        return None;
+    };
+
+    let mut content = String::new();
+    for event in events {
+        match event {
+            Event::Text(text) => content.push_str(&text),
+            Event::End(TagEnd::MetadataBlock(_)) => break,
+            _ => {}
+        }
+    }
+
+    let entries = match kind {
+        MetadataBlockKind::YamlStyle => parse_yaml(&content),
+        MetadataBlockKind::PlusesStyle => parse_toml(&content),
+    };
+    let front_matter = FrontMatter { entries };
+    (!front_matter.is_empty()).then_some(front_matter)
+}
+
+/// Strip matching surrounding quotes
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+/// Inline `[a, "b"]` lists become `a, b`; other values are unquoted
+fn flatten_value(value: &str) -> String {
+    let value = value.trim();
+    match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        Some(items) => items
+            .split(',')
+            .map(unquote)
+            .filter(|item| !item.is_empty())
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => unquote(value).to_string(),
+    }
+}
+
+/// Append a nested line to the previous entry's value
+fn append(entries: &mut [(String, String)], part: &str, separator: &str) {
+    if let Some((_, value)) = entries.last_mut() {
+        if !value.is_empty() {
+            value.push_str(separator);
+        }
+        value.push_str(part);
+    }
+}
+
+/// Top-level `key: value` pairs; block lists and nested mappings are flattened into
+/// the value of their parent key, block scalars (`|`, `>`) are joined with spaces
+fn parse_yaml(content: &str) -> Vec<(String, String)> {
+    let mut entries: Vec<(String, String)> = Vec::new();
+    let mut in_block_scalar = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let nested = line.starts_with(char::is_whitespace) || trimmed.starts_with("- ");
+        if nested {
+            if in_block_scalar {
+                append(&mut entries, trimmed, " ");
+            } else if let Some(item) = trimmed.strip_prefix("- ") {
+                append(&mut entries, &flatten_value(item), ", ");
+            } else {
+                append(&mut entries, trimmed, ", ");
+            }
+            continue;
+        }
+
+        in_block_scalar = false;
+        if let Some((key, value)) = trimmed.split_once(':') {
+            let value = value.trim();
+            in_block_scalar = matches!(value.trim_end_matches(['-', '+']), "|" | ">");
+            let value = if in_block_scalar {
+                String::new()
+            } else {
+                flatten_value(value)
+            };
+            entries.push((unquote(key).to_string(), value));
+        }
+    }
+
+    entries
+}
+
+/// `key = value` pairs; keys under a `[table]` header are prefixed with `table.`
+fn parse_toml(content: &str) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+    let mut table = String::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = trimmed.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            table = format!("{}.", name.trim_matches(['[', ']']).trim());
+        } else if let Some((key, value)) = trimmed.split_once('=') {
+            entries.push((format!("{}{}", table, unquote(key)), flatten_value(value)));
+        }
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(front_matter: &FrontMatter) -> Vec<(&str, &str)> {
+        front_matter
+            .entries
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_yaml_front_matter() {
+        let source = "---\nstatus: draft\nowner: \"Alex\"\ntags: [plan, 'phase 2']\nreviewers:\n  - sam\n  - kim\nsummary: >\n  Two line\n  summary\n---\n\n# Title\n";
+        let front_matter = parse_front_matter(source).unwrap();
+        assert_eq!(
+            entries(&front_matter),
+            vec![
+                ("status", "draft"),
+                ("owner", "Alex"),
+                ("tags", "plan, phase 2"),
+                ("reviewers", "sam, kim"),
+                ("summary", "Two line summary"),
+            ]
+        );
+        assert_eq!(front_matter.get("owner"), Some("Alex"));
+    }
+
+    #[test]
+    fn test_toml_front_matter() {
+        let source = "+++\ntitle = \"Spec\"\nphase = 3\n[owner]\nname = \"Alex\"\n+++\n\nBody\n";
+        let front_matter = parse_front_matter(source).unwrap();
+        assert_eq!(
+            entries(&front_matter),
+            vec![("title", "Spec"), ("phase", "3"), ("owner.name", "Alex")]
+        );
+    }
+
+    #[test]
+    fn test_no_front_matter() {
+        assert_eq!(parse_front_matter("# Title\n\n---\n\nstatus: text\n"), None);
+        assert_eq!(parse_front_matter(""), None);
+    }
+}
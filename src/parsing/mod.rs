@@ -1,6 +1,9 @@
 mod chunks;
+mod front_matter;
 mod html;
 mod parser;
 mod position;
 
+pub use front_matter::parse_front_matter;
 pub use parser::parse_markdown;
+pub use position::LineOffsets;
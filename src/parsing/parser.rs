@@ -1,12 +1,14 @@
 use crate::image_manager::ImageManager;
-use crate::models::{Table, TextChunk};
+use crate::links::{footnote_anchor, footnote_ref_anchor};
+use crate::models::{AlertKind, Footnote, ListItem, Math, Quote, Table, TextChunk};
 use crate::parsing::chunks::{
     push_break_chunk, push_code_chunk, push_image_chunk, push_image_chunk_with_alignment,
-    push_table_chunk, push_text_chunk,
+    push_math_chunk, push_table_chunk, push_text_chunk, InlineStyle,
 };
+use crate::parsing::front_matter::metadata_options;
 use crate::parsing::html::parse_html_image;
 use crate::parsing::position::LineOffsets;
-use pulldown_cmark::{CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+use pulldown_cmark::{BlockQuoteKind, CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
 use std::ops::Range;
 use std::path::Path;
 
@@ -21,18 +23,30 @@ pub fn parse_markdown(
     // Build line offset table once for O(log n) lookups
     let line_offsets = LineOffsets::new(source);
 
-    let mut options = Options::empty();
+    // Front matter is parsed separately (parse_front_matter); skip it here
+    let mut options = metadata_options();
     options.insert(Options::ENABLE_TABLES);
     options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_FOOTNOTES);
+    options.insert(Options::ENABLE_MATH);
+    // GitHub alerts (`> [!NOTE]`)
+    options.insert(Options::ENABLE_GFM);
     let parser = Parser::new_ext(source, options);
 
     // Track style state
-    let mut bold = false;
-    let mut italic = false;
-    let mut heading_level = None;
-    let mut current_image_url: Option<CowStr> = None;
-    let mut in_code_block = false;
-    let mut code_block_lang: Option<String> = None;
+    let mut inline = InlineState::default();
+
+    // Track list state: next item number per open list (None for bullet lists), and
+    // the marker of an item whose first chunk hasn't been pushed yet
+    let mut list_numbers: Vec<Option<u64>> = Vec::new();
+    let mut pending_item: Option<ListItem> = None;
+    let mut item_start = 0;
+
+    // Track blockquote state: alert kind per open blockquote, and whether the
+    // innermost one has had its first chunk yet
+    let mut quote_alerts: Vec<Option<AlertKind>> = Vec::new();
+    let mut quote_started = false;
 
     // Track table state
     let mut in_table = false;
@@ -42,13 +56,27 @@ pub fn parse_markdown(
     let mut current_cell: String = String::new();
     let mut table_start_range: Option<Range<usize>> = None;
 
+    // Track footnote state: labels numbered in order of first use, the definition being
+    // parsed (with the index of its first chunk), and finished definitions by number
+    let mut footnote_labels: Vec<String> = Vec::new();
+    let mut current_footnote: Option<(String, usize)> = None;
+    let mut footnotes: Vec<(usize, Vec<TextChunk>)> = Vec::new();
+
+    let mut in_metadata_block = false;
+
     // Track HTML block state (for centered images)
     let mut in_html_block = false;
     let mut html_block_content = String::new();
     let mut html_block_range: Option<Range<usize>> = None;
 
     for (event, range) in parser.into_offset_iter() {
+        let chunk_count = chunks.len();
+        let list_depth = list_numbers.len();
+
         match event {
+            Event::Start(Tag::MetadataBlock(_)) => in_metadata_block = true,
+            Event::End(TagEnd::MetadataBlock(_)) => in_metadata_block = false,
+            Event::Text(_) if in_metadata_block => {}
             Event::Start(Tag::HtmlBlock) => {
                 in_html_block = true;
                 html_block_content.clear();
@@ -75,6 +103,90 @@ pub fn parse_markdown(
                 }
                 html_block_content.clear();
             }
+            Event::Start(Tag::List(start)) => {
+                // An item that opens with a nested list gets a line of its own for its marker
+                if pending_item.is_some() {
+                    push_text_chunk(
+                        &mut chunks,
+                        String::new(),
+                        source,
+                        &line_offsets,
+                        &(item_start..item_start),
+                        &InlineStyle::default(),
+                    );
+                    if let Some(last) = chunks.last_mut() {
+                        last.newline_after = true;
+                    }
+                }
+                list_numbers.push(start);
+            }
+            Event::End(TagEnd::List(_)) => {
+                list_numbers.pop();
+                // An empty last item has no chunk to carry its marker
+                pending_item = None;
+            }
+            Event::Start(Tag::Item) => {
+                let number = list_numbers.last_mut().and_then(|next| {
+                    let number = *next;
+                    *next = next.map(|n| n + 1);
+                    number
+                });
+                pending_item = Some(ListItem {
+                    number,
+                    checked: None,
+                });
+                item_start = range.start;
+            }
+            Event::TaskListMarker(checked) => {
+                if let Some(item) = &mut pending_item {
+                    item.checked = Some(checked);
+                }
+            }
+            Event::Start(Tag::BlockQuote(kind)) => {
+                quote_alerts.push(kind.map(alert_kind));
+                quote_started = true;
+            }
+            Event::End(TagEnd::BlockQuote(_)) => {
+                quote_alerts.pop();
+                quote_started = false;
+            }
+            Event::FootnoteReference(label) if !in_table => {
+                let number = footnote_number(&mut footnote_labels, &label);
+                push_text_chunk(
+                    &mut chunks,
+                    number.to_string(),
+                    source,
+                    &line_offsets,
+                    &range,
+                    &inline.style,
+                );
+                if let Some(last) = chunks.last_mut() {
+                    last.link = Some(format!("#{}", footnote_anchor(&label)));
+                    last.footnote = Some(Footnote::Reference(label.to_string()));
+                }
+            }
+            Event::FootnoteReference(label) => {
+                let number = footnote_number(&mut footnote_labels, &label);
+                current_cell.push_str(&number.to_string());
+            }
+            Event::Start(Tag::FootnoteDefinition(label)) => {
+                let number = footnote_number(&mut footnote_labels, &label);
+                current_footnote = Some((label.to_string(), chunks.len()));
+                // Definitions render as an ordered list in the footer
+                pending_item = Some(ListItem {
+                    number: Some(number as u64),
+                    checked: None,
+                });
+            }
+            Event::End(TagEnd::FootnoteDefinition) => {
+                pending_item = None;
+                if let Some((label, first)) = current_footnote.take() {
+                    let mut definition: Vec<TextChunk> = chunks.drain(first..).collect();
+                    push_back_link(&mut definition, &label, source, &line_offsets);
+                    let number = footnote_number(&mut footnote_labels, &label);
+                    footnotes.push((number, definition));
+                }
+            }
             Event::Start(Tag::Table(alignments)) => {
                 in_table = true;
                 current_table = Some(Table::new(alignments));
@@ -114,19 +226,16 @@ pub fn parse_markdown(
             Event::Text(text) => {
                 if in_table {
                     current_cell.push_str(&text);
-                } else if current_image_url.is_none() {
+                } else if inline.image_url.is_none() {
                     push_text_chunk(
                         &mut chunks,
                         text.to_string(),
                         source,
                         &line_offsets,
                         &range,
-                        bold,
-                        italic,
-                        in_code_block,
-                        heading_level,
-                        &code_block_lang,
+                        &inline.style,
                     );
+                    mark_link(&mut chunks, &inline.link);
                 }
             }
             Event::Code(text) => {
@@ -139,12 +248,32 @@ pub fn parse_markdown(
                         source,
                         &line_offsets,
                         &range,
-                        bold,
-                        italic,
-                        heading_level,
+                        &inline.style,
                     );
+                    mark_link(&mut chunks, &inline.link);
                 }
             }
+            Event::InlineMath(text) | Event::DisplayMath(text) if in_table => {
+                current_cell.push_str(&text);
+            }
+            Event::InlineMath(text) => push_math_chunk(
+                &mut chunks,
+                text.to_string(),
+                source,
+                &line_offsets,
+                &range,
+                Math::Inline,
+                inline.style.heading_level,
+            ),
+            Event::DisplayMath(text) => push_math_chunk(
+                &mut chunks,
+                text.to_string(),
+                source,
+                &line_offsets,
+                &range,
+                Math::Display,
+                inline.style.heading_level,
+            ),
             Event::SoftBreak => {
                 if !in_table {
                     push_break_chunk(&mut chunks, " ".to_string(), &range, false);
@@ -157,27 +286,14 @@ pub fn parse_markdown(
             }
             Event::Start(tag) => {
                 if !in_table {
-                    handle_start_tag(
-                        tag,
-                        &mut bold,
-                        &mut italic,
-                        &mut heading_level,
-                        &mut current_image_url,
-                        &mut in_code_block,
-                        &mut code_block_lang,
-                    );
+                    handle_start_tag(tag, &mut inline);
                 }
             }
             Event::End(tag) => {
                 if !in_table {
                     handle_end_tag(
                         tag,
-                        &mut bold,
-                        &mut italic,
-                        &mut heading_level,
-                        &mut current_image_url,
-                        &mut in_code_block,
-                        &mut code_block_lang,
+                        &mut inline,
                         &mut chunks,
                         source,
                         &line_offsets,
@@ -188,61 +304,153 @@ pub fn parse_markdown(
             }
             _ => {}
         }
+
+        // New chunks inherit list, blockquote and footnote nesting; the first one of an
+        // item carries its marker
+        if chunks.len() > chunk_count {
+            let footnote_depth = usize::from(current_footnote.is_some());
+            for chunk in &mut chunks[chunk_count..] {
+                chunk.list_depth = list_depth + footnote_depth;
+                if let Some((label, _)) = &current_footnote {
+                    chunk.footnote = Some(Footnote::Definition(label.clone()));
+                }
+            }
+            if let Some(item) = pending_item.take() {
+                chunks[chunk_count].list_item = Some(item);
+            }
+            if let Some(&alert) = quote_alerts.last() {
+                for (i, chunk) in chunks[chunk_count..].iter_mut().enumerate() {
+                    chunk.quote = Some(Quote {
+                        depth: quote_alerts.len(),
+                        alert,
+                        first: quote_started && i == 0,
+                    });
+                }
+                quote_started = false;
+            }
+        }
     }
 
+    // Footnote definitions go after the document, in footnote number order
+    footnotes.sort_by_key(|(number, _)| *number);
+    chunks.extend(footnotes.into_iter().flat_map(|(_, definition)| definition));
+
     chunks
 }
 
-fn handle_start_tag<'a>(
-    tag: Tag<'a>,
-    bold: &mut bool,
-    italic: &mut bool,
-    heading_level: &mut Option<u8>,
-    current_image_url: &mut Option<CowStr<'a>>,
-    in_code_block: &mut bool,
-    code_block_lang: &mut Option<String>,
+/// Number of a footnote label (1-based), assigning the next one on first use
+fn footnote_number(labels: &mut Vec<String>, label: &str) -> usize {
+    match labels.iter().position(|l| l == label) {
+        Some(index) => index + 1,
+        None => {
+            labels.push(label.to_string());
+            labels.len()
+        }
+    }
+}
+
+/// Append a `↩` link back to the footnote's first reference, on the definition's
+/// last row
+fn push_back_link(
+    definition: &mut Vec<TextChunk>,
+    label: &str,
+    source: &str,
+    line_offsets: &LineOffsets,
 ) {
+    let Some(last) = definition.last_mut() else {
+        return;
+    };
+    let end = last.byte_range.end;
+    let (list_depth, quote) = (last.list_depth, last.quote.clone());
+    let newline_after = std::mem::take(&mut last.newline_after);
+
+    push_text_chunk(
+        definition,
+        " \u{21a9}".to_string(),
+        source,
+        line_offsets,
+        &(end..end),
+        &InlineStyle::default(),
+    );
+    if let Some(back_link) = definition.last_mut() {
+        back_link.newline_after = newline_after;
+        back_link.list_depth = list_depth;
+        back_link.quote = quote;
+        back_link.link = Some(format!("#{}", footnote_ref_anchor(label)));
+        back_link.footnote = Some(Footnote::Definition(label.to_string()));
+    }
+}
+
+/// Model alert kind for a GitHub alert blockquote
+fn alert_kind(kind: BlockQuoteKind) -> AlertKind {
+    match kind {
+        BlockQuoteKind::Note => AlertKind::Note,
+        BlockQuoteKind::Tip => AlertKind::Tip,
+        BlockQuoteKind::Important => AlertKind::Important,
+        BlockQuoteKind::Warning => AlertKind::Warning,
+        BlockQuoteKind::Caution => AlertKind::Caution,
+    }
+}
+
+/// Record the enclosing link's destination on the chunk just pushed
+fn mark_link(chunks: &mut [TextChunk], current_link: &Option<String>) {
+    if let (Some(last), Some(dest)) = (chunks.last_mut(), current_link) {
+        last.link = Some(dest.clone());
+    }
+}
+
+/// Inline state while walking the event stream: formatting plus the enclosing link
+/// and image
+#[derive(Default)]
+struct InlineState<'a> {
+    style: InlineStyle,
+    image_url: Option<CowStr<'a>>,
+    link: Option<String>,
+}
+
+fn handle_start_tag<'a>(tag: Tag<'a>, inline: &mut InlineState<'a>) {
+    let style = &mut inline.style;
     match tag {
-        Tag::Strong => *bold = true,
-        Tag::Emphasis => *italic = true,
+        Tag::Strong => style.bold = true,
+        Tag::Emphasis => style.italic = true,
+        Tag::Strikethrough => style.strikethrough = true,
         Tag::CodeBlock(kind) => {
-            *in_code_block = true;
-            *code_block_lang = match kind {
+            style.in_code_block = true;
+            style.code_block_lang = match kind {
                 CodeBlockKind::Fenced(lang) => Some(lang.to_string()),
                 CodeBlockKind::Indented => None,
             };
         }
-        Tag::Heading { level, .. } => *heading_level = Some(level as u8),
+        Tag::Heading { level, .. } => style.heading_level = Some(level as u8),
         Tag::Image { dest_url, .. } => {
-            *current_image_url = Some(dest_url);
+            inline.image_url = Some(dest_url);
         }
+        Tag::Link { dest_url, .. } => inline.link = Some(dest_url.to_string()),
         _ => {}
     }
 }
 
 fn handle_end_tag(
     tag: TagEnd,
-    bold: &mut bool,
-    italic: &mut bool,
-    heading_level: &mut Option<u8>,
-    current_image_url: &mut Option<CowStr>,
-    in_code_block: &mut bool,
-    code_block_lang: &mut Option<String>,
+    inline: &mut InlineState,
     chunks: &mut Vec<TextChunk>,
     source: &str,
     line_offsets: &LineOffsets,
     range: &Range<usize>,
     image_manager: &mut ImageManager,
 ) {
+    let style = &mut inline.style;
     match tag {
-        TagEnd::Strong => *bold = false,
-        TagEnd::Emphasis => *italic = false,
+        TagEnd::Strong => style.bold = false,
+        TagEnd::Emphasis => style.italic = false,
+        TagEnd::Strikethrough => style.strikethrough = false,
+        TagEnd::Link => inline.link = None,
         TagEnd::CodeBlock => {
-            *in_code_block = false;
-            *code_block_lang = None;
+            style.in_code_block = false;
+            style.code_block_lang = None;
         }
         TagEnd::Heading(_) => {
-            *heading_level = None;
+            style.heading_level = None;
             if let Some(last) = chunks.last_mut() {
                 last.newline_after = true;
             }
@@ -258,7 +466,7 @@ fn handle_end_tag(
             }
         }
         TagEnd::Image => {
-            if let Some(url) = current_image_url.take() {
+            if let Some(url) = inline.image_url.take() {
                 push_image_chunk(
                     chunks,
                     url.as_ref(),
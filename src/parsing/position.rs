@@ -29,6 +29,25 @@ impl LineOffsets {
 
         (line, col)
     }
+
+    /// Convert (line, col) back to a byte offset - both 1-indexed, col counted in chars
+    /// Col 0 means the line start; out-of-range values clamp to the line or source end
+    pub fn line_col_to_byte(&self, source: &str, line: usize, col: usize) -> usize {
+        let Some(&line_start) = self.offsets.get(line.saturating_sub(1)) else {
+            return source.len();
+        };
+        let line_end = self
+            .offsets
+            .get(line)
+            .map(|&next| next - 1) // Exclude the newline
+            .unwrap_or(source.len());
+
+        source[line_start..line_end]
+            .char_indices()
+            .nth(col.saturating_sub(1))
+            .map(|(idx, _)| line_start + idx)
+            .unwrap_or(line_end)
+    }
 }
 
 /// Convert byte offset to (line, col) - both 1-indexed (legacy function for tests)
@@ -49,4 +68,29 @@ mod tests {
         assert_eq!(byte_to_line_col(source, 7), (2, 1));
         assert_eq!(byte_to_line_col(source, 14), (3, 1));
     }
+
+    #[test]
+    fn test_line_col_to_byte_round_trip() {
+        let source = "line 1\nline 2\nline 3";
+        let offsets = LineOffsets::new(source);
+
+        for byte in 0..source.len() {
+            let (line, col) = offsets.byte_to_line_col(source, byte);
+            assert_eq!(offsets.line_col_to_byte(source, line, col), byte);
+        }
+    }
+
+    #[test]
+    fn test_line_col_to_byte_multibyte_and_clamping() {
+        let source = "héllo\nworld";
+        let offsets = LineOffsets::new(source);
+
+        // Col counts chars, not bytes
+        assert_eq!(offsets.line_col_to_byte(source, 1, 3), 3);
+        // Col 0 is line start, past-the-end clamps to the newline
+        assert_eq!(offsets.line_col_to_byte(source, 2, 0), 7);
+        assert_eq!(offsets.line_col_to_byte(source, 1, 99), 6);
+        // Past the last line clamps to source end
+        assert_eq!(offsets.line_col_to_byte(source, 9, 1), source.len());
+    }
 }
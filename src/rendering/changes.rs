@@ -0,0 +1,83 @@
+/// Gutter markers for changes since a git revision (`--since REV`)
+use crate::diff::{ChangeKind, ChangeMarker};
+use crate::models::{LayoutMap, TextChunk};
+use crate::theme::Theme;
+use eframe::egui;
+
+/// Mark every chunk touched by a change with a bar just left of the content column
+/// (deletions get a small triangle at the top of the following chunk)
+pub fn draw_change_markers(
+    ui: &mut egui::Ui,
+    chunks: &[TextChunk],
+    markers: &[ChangeMarker],
+    layout_map: &LayoutMap,
+    theme: &Theme,
+) {
+    if markers.is_empty() {
+        return;
+    }
+
+    let [added, modified, removed] = theme.colors.change_markers;
+    let right = ui.max_rect().left() - 4.0;
+    let bar_width = 3.0;
+
+    for chunk in chunks {
+        let touching = markers
+            .iter()
+            .filter(|marker| marker.overlaps(chunk.line_start, chunk.line_end));
+
+        // Strongest change wins: modified > added > removed
+        let mut kind = None;
+        for marker in touching {
+            kind = match (kind, marker.kind) {
+                (Some(ChangeKind::Modified), _) | (_, ChangeKind::Modified) => {
+                    Some(ChangeKind::Modified)
+                }
+                (Some(ChangeKind::Added), _) | (_, ChangeKind::Added) => Some(ChangeKind::Added),
+                _ => Some(ChangeKind::Removed),
+            };
+        }
+        let Some(kind) = kind else {
+            continue;
+        };
+        let Some((start_y, end_y)) = layout_map.get_y_range(chunk.line_start, chunk.line_end)
+        else {
+            continue;
+        };
+
+        let (color, label) = match kind {
+            ChangeKind::Added => (added, "Added"),
+            ChangeKind::Modified => (modified, "Changed"),
+            ChangeKind::Removed => (removed, "Text removed"),
+        };
+
+        let rect = if kind == ChangeKind::Removed {
+            let size = 6.0;
+            let tip = egui::pos2(right, start_y);
+            ui.painter().add(egui::Shape::convex_polygon(
+                vec![
+                    tip + egui::vec2(-size, -size / 2.0),
+                    tip + egui::vec2(0.0, 0.0),
+                    tip + egui::vec2(-size, size / 2.0),
+                ],
+                color,
+                egui::Stroke::NONE,
+            ));
+            egui::Rect::from_center_size(tip, egui::vec2(size, size))
+        } else {
+            let rect = egui::Rect::from_min_max(
+                egui::pos2(right - bar_width, start_y),
+                egui::pos2(right, end_y),
+            );
+            ui.painter().rect_filled(rect, 1.0, color);
+            rect
+        };
+
+        ui.interact(
+            rect.expand(2.0),
+            ui.id().with(("change_marker", chunk.line_start)),
+            egui::Sense::hover(),
+        )
+        .on_hover_text(format!("{} since base revision", label));
+    }
+}
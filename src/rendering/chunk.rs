@@ -1,50 +1,30 @@
-use crate::image_manager::ImageManager;
-use crate::models::{LayoutMap, Selection, TextChunk};
+use crate::diagram::diagram_kind;
 use crate::rendering::chunk_renderer::{
-    ChunkRenderer, CodeRenderer, ImageRenderer, RenderContext, TableRenderer, TextRenderer,
+    ChunkRenderer, CodeRenderer, DiagramRenderer, ImageRenderer, MathRenderer, RenderContext,
+    TableRenderer, TextRenderer,
 };
 use crate::rendering::viewport::ViewportCuller;
-use crate::syntax::SyntaxHighlighter;
-use crate::theme::Theme;
-use eframe::egui;
 
-/// Render a single chunk (image, code, table, or text) with viewport culling
+/// Render a single chunk (image, diagram, code, table, math, or text) with viewport culling
 /// Returns true if the chunk was clicked
-pub fn render_chunk(
-    ui: &mut egui::Ui,
-    ctx: &egui::Context,
-    chunk: &mut TextChunk,
-    idx: usize,
-    selection: &mut Selection,
-    image_manager: &mut ImageManager,
-    highlighter: &SyntaxHighlighter,
-    theme: &Theme,
-    layout_map: &mut LayoutMap,
-    need_layout_map: bool,
-    culler: &ViewportCuller,
-) -> bool {
-    // Build render context
-    let mut render_ctx = RenderContext {
-        ui,
-        ctx,
-        chunk,
-        idx,
-        selection,
-        image_manager,
-        highlighter,
-        theme,
-        layout_map,
-        need_layout_map,
-    };
-
+pub fn render_chunk(render_ctx: &mut RenderContext, culler: &ViewportCuller) -> bool {
     // Select appropriate renderer based on chunk type
     if render_ctx.chunk.image_path.is_some() {
-        ImageRenderer.render(&mut render_ctx, culler)
+        ImageRenderer.render(render_ctx, culler)
+    } else if render_ctx
+        .chunk
+        .code_block_lang
+        .as_deref()
+        .is_some_and(|lang| diagram_kind(lang).is_some())
+    {
+        DiagramRenderer.render(render_ctx, culler)
     } else if render_ctx.chunk.code_block_lang.is_some() {
-        CodeRenderer.render(&mut render_ctx, culler)
+        CodeRenderer.render(render_ctx, culler)
     } else if render_ctx.chunk.table.is_some() {
-        TableRenderer.render(&mut render_ctx, culler)
+        TableRenderer.render(render_ctx, culler)
+    } else if render_ctx.chunk.math.is_some() {
+        MathRenderer.render(render_ctx, culler)
     } else {
-        TextRenderer.render(&mut render_ctx, culler)
+        TextRenderer.render(render_ctx, culler)
     }
 }
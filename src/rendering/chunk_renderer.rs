@@ -1,6 +1,7 @@
 /// Trait-based chunk rendering system to eliminate duplication
+use crate::diagram::diagram_kind;
 use crate::image_manager::ImageManager;
-use crate::models::{LayoutMap, Selection, TextChunk};
+use crate::models::{LayoutMap, Math, Selection, TextChunk, TextSpan};
 use crate::rendering::selection_manager::SelectionManager;
 use crate::rendering::viewport::ViewportCuller;
 use crate::syntax::SyntaxHighlighter;
@@ -13,6 +14,8 @@ pub struct RenderContext<'a> {
     pub ctx: &'a egui::Context,
     pub chunk: &'a mut TextChunk,
     pub idx: usize,
+    /// Document source (the chunk's byte range indexes it)
+    pub source: &'a str,
     pub selection: &'a mut Selection,
     pub image_manager: &'a mut ImageManager,
     pub highlighter: &'a SyntaxHighlighter,
@@ -59,8 +62,10 @@ pub trait ChunkRenderer {
 
         let after_y = ctx.ui.cursor().min.y;
 
-        // Record position in layout map (only if needed for selection)
-        if ctx.need_layout_map {
+        // Record position in layout map (only if needed for selection; headings and
+        // footnotes always, for table of contents and footnote navigation)
+        if ctx.need_layout_map || ctx.chunk.heading_level.is_some() || ctx.chunk.footnote.is_some()
+        {
             ctx.layout_map.record_chunk(
                 ctx.chunk.line_start,
                 ctx.chunk.line_end,
@@ -90,15 +95,36 @@ impl ChunkRenderer for TextRenderer {
     fn render_visible(&self, ctx: &mut RenderContext) -> (f32, bool) {
         let before_y = ctx.ui.cursor().min.y;
 
-        let response = crate::rendering::text::render_text_chunk(ctx.ui, ctx.chunk, ctx.theme);
+        let (galley_pos, galley, response) =
+            crate::rendering::text::render_text_chunk(ctx.ui, ctx.chunk, ctx.theme);
 
         let after_y = ctx.ui.cursor().min.y;
         let actual_height = after_y - before_y;
 
+        // Glyph layout for character-level selection (only when a selection needs it)
+        let span = (ctx.need_layout_map || response.drag_started()).then(|| TextSpan {
+            rect: response.rect,
+            galley,
+            galley_pos,
+            byte_range: ctx.chunk.byte_range.clone(),
+            line_start: ctx.chunk.line_start,
+            line_end: ctx.chunk.line_end,
+        });
+
         // Handle selection interactions
-        let mut selection_manager = SelectionManager::new(ctx.selection, ctx.layout_map);
-        let was_clicked =
-            selection_manager.handle_interaction(&response, ctx.chunk, before_y, after_y);
+        let mut selection_manager =
+            SelectionManager::new(ctx.selection, ctx.layout_map, ctx.source);
+        let was_clicked = selection_manager.handle_interaction(
+            &response,
+            ctx.chunk,
+            span.as_ref(),
+            before_y,
+            after_y,
+        );
+
+        if let (true, Some(span)) = (ctx.need_layout_map, span) {
+            ctx.layout_map.record_text_span(span);
+        }
 
         (actual_height, was_clicked)
     }
@@ -133,6 +159,38 @@ impl ChunkRenderer for CodeRenderer {
     }
 }
 
+/// Renderer for diagram code blocks (mermaid, DOT)
+pub struct DiagramRenderer;
+
+impl ChunkRenderer for DiagramRenderer {
+    fn estimate_height(&self, chunk: &TextChunk, theme: &Theme) -> f32 {
+        // Drawn diagrams run taller than their source
+        let line_count = chunk.text.lines().count().max(1);
+        (line_count as f32 * theme.spacing.min_line_height * 2.0)
+            + theme.spacing.code_block_padding * 2.0
+    }
+
+    fn render_visible(&self, ctx: &mut RenderContext) -> (f32, bool) {
+        let before_y = ctx.ui.cursor().min.y;
+
+        // Like code blocks, diagrams are not selectable via dragging
+        let lang = ctx.chunk.code_block_lang.as_deref().unwrap_or("");
+        if let Some(kind) = diagram_kind(lang) {
+            crate::rendering::diagram::render_diagram_block(
+                ctx.ui,
+                ctx.chunk,
+                kind,
+                ctx.image_manager,
+                ctx.highlighter,
+                ctx.theme,
+            );
+        }
+
+        let after_y = ctx.ui.cursor().min.y;
+        (after_y - before_y, false)
+    }
+}
+
 /// Renderer for tables
 pub struct TableRenderer;
 
@@ -168,9 +226,15 @@ impl ChunkRenderer for TableRenderer {
         );
 
         // Handle selection interactions
-        let mut selection_manager = SelectionManager::new(ctx.selection, ctx.layout_map);
-        let was_clicked =
-            selection_manager.handle_interaction(&table_response, ctx.chunk, before_y, after_y);
+        let mut selection_manager =
+            SelectionManager::new(ctx.selection, ctx.layout_map, ctx.source);
+        let was_clicked = selection_manager.handle_interaction(
+            &table_response,
+            ctx.chunk,
+            None,
+            before_y,
+            after_y,
+        );
 
         (actual_height, was_clicked)
     }
@@ -205,7 +269,8 @@ impl ChunkRenderer for ImageRenderer {
                 let actual_height = response.rect.height();
 
                 // Images can be selected by clicking (single line selection)
-                let mut selection_manager = SelectionManager::new(ctx.selection, ctx.layout_map);
+                let mut selection_manager =
+                    SelectionManager::new(ctx.selection, ctx.layout_map, ctx.source);
                 selection_manager.handle_single_line_interaction(&response, ctx.chunk.line_start);
 
                 return (actual_height, false);
@@ -216,3 +281,40 @@ impl ChunkRenderer for ImageRenderer {
         (after_y - before_y, false)
     }
 }
+
+/// Renderer for math formulas
+pub struct MathRenderer;
+
+impl ChunkRenderer for MathRenderer {
+    fn estimate_height(&self, chunk: &TextChunk, theme: &Theme) -> f32 {
+        match chunk.math {
+            Some(Math::Display) => theme.spacing.min_line_height * 2.5,
+            _ => theme.spacing.min_line_height,
+        }
+    }
+
+    fn render_visible(&self, ctx: &mut RenderContext) -> (f32, bool) {
+        let before_y = ctx.ui.cursor().min.y;
+
+        let math = ctx.chunk.math.unwrap_or(Math::Inline);
+        let response = crate::rendering::math::render_math_chunk(
+            ctx.ui,
+            ctx.ctx,
+            ctx.chunk,
+            math,
+            ctx.image_manager,
+            ctx.theme,
+        );
+
+        let after_y = ctx.ui.cursor().min.y;
+        let actual_height = after_y - before_y;
+
+        // Formulas select as a whole (no glyph layout to hit-test)
+        let mut selection_manager =
+            SelectionManager::new(ctx.selection, ctx.layout_map, ctx.source);
+        let was_clicked =
+            selection_manager.handle_interaction(&response, ctx.chunk, None, before_y, after_y);
+
+        (actual_height, was_clicked)
+    }
+}
@@ -1,65 +1,56 @@
-use crate::models::{Comment, LayoutMap, ReviewMode, Selection};
-use crate::storage::ReviewStorage;
+use crate::models::{Document, ReviewMode};
 use crate::theme::Theme;
 use eframe::egui;
 
+/// Widget id of the comment text box
+fn comment_input_id() -> egui::Id {
+    egui::Id::new("comment_input")
+}
+
+/// Give the comment text box keyboard focus (it appears once a selection is active)
+pub fn focus_comment_input(ctx: &egui::Context) {
+    ctx.memory_mut(|memory| memory.request_focus(comment_input_id()));
+}
+
 /// Render the comment UI section as a floating panel in the right margin
 pub fn render_comment_section(
     ctx: &egui::Context,
-    layout_map: &LayoutMap,
-    selection: &mut Selection,
-    comment_text: &mut String,
-    comments: &mut Vec<Comment>,
+    doc: &mut Document,
     theme: &Theme,
     review_mode: &mut ReviewMode,
-    storage: &ReviewStorage,
-    source: &str,
 ) {
     // Only show if there's an active selection
-    if let (Some(start_line), Some(end_line)) = (selection.start_line, selection.end_line) {
-        render_comment_input(
-            ctx,
-            layout_map,
-            start_line,
-            end_line,
-            comment_text,
-            comments,
-            selection,
-            theme,
-            review_mode,
-            storage,
-            source,
-        );
+    if let (Some(start_line), Some(end_line)) = (doc.selection.start_line, doc.selection.end_line) {
+        render_comment_input(ctx, doc, start_line, end_line, theme, review_mode);
     }
 
     // Show existing comments list at the bottom
-    if !comments.is_empty() {
-        render_comments_list(ctx, comments, theme);
+    if !doc.comments.is_empty() {
+        render_comments_list(ctx, doc, theme);
     }
 }
 
 fn render_comment_input(
     ctx: &egui::Context,
-    layout_map: &LayoutMap,
+    doc: &mut Document,
     start_line: usize,
     end_line: usize,
-    comment_text: &mut String,
-    comments: &mut Vec<Comment>,
-    selection: &mut Selection,
     theme: &Theme,
     review_mode: &mut ReviewMode,
-    _storage: &ReviewStorage,
-    _source: &str,
 ) {
     let (min_line, max_line) = if start_line <= end_line {
         (start_line, end_line)
     } else {
         (end_line, start_line)
     };
+    let anchor = doc.selection.anchor(&doc.source);
 
     // Get the Y position for the selection start and end
-    if let Some(selection_y_start) = layout_map.get_line_y(min_line) {
-        let selection_y_end = layout_map.get_line_y(max_line).unwrap_or(selection_y_start);
+    if let Some(selection_y_start) = doc.layout_map.get_line_y(min_line) {
+        let selection_y_end = doc
+            .layout_map
+            .get_line_y(max_line)
+            .unwrap_or(selection_y_start);
 
         // Position the comment box at the selection Y position, in the right margin
         let content_rect = ctx.content_rect();
@@ -104,50 +95,49 @@ fn render_comment_input(
                     ui.separator();
                 }
 
-                ui.label(format!("Selection: Lines {}-{}", min_line, max_line));
+                match anchor {
+                    Some((line_start, col_start, line_end, col_end)) if col_start > 0 => {
+                        ui.label(format!(
+                            "Selection: L{}:C{} → L{}:C{}",
+                            line_start, col_start, line_end, col_end
+                        ));
+                    }
+                    _ => {
+                        ui.label(format!("Selection: Lines {}-{}", min_line, max_line));
+                    }
+                }
                 ui.add_space(5.0);
 
                 ui.label("Comment:");
-                ui.text_edit_multiline(comment_text);
+                ui.add(egui::TextEdit::multiline(&mut doc.comment_text).id(comment_input_id()));
                 ui.add_space(5.0);
 
                 // Show different buttons based on review mode
-                match *review_mode {
-                    ReviewMode::Immediate => {
-                        if ui.button("Start Review").clicked() && !comment_text.is_empty() {
-                            // Enter batched mode: queue comment in memory
-                            *review_mode = ReviewMode::Batched;
-                            comments.push(Comment::new(
-                                comment_text.clone(),
-                                min_line,
-                                0,
-                                max_line,
-                                0,
-                            ));
-                            comment_text.clear();
-                            selection.clear();
-                        }
-                    }
-                    ReviewMode::Batched => {
-                        if ui.button("Add to Review").clicked() && !comment_text.is_empty() {
-                            // Batched mode: queue comment in memory
-                            comments.push(Comment::new(
-                                comment_text.clone(),
-                                min_line,
-                                0,
-                                max_line,
-                                0,
-                            ));
-                            comment_text.clear();
-                            selection.clear();
-                        }
-                    }
+                let button = match *review_mode {
+                    ReviewMode::Immediate => "Start Review",
+                    ReviewMode::Batched => "Add to Review",
+                };
+                // Queue the comment in memory (the first one enters batched mode)
+                if ui.button(button).clicked() && doc.add_comment(doc.comment_text.clone()) {
+                    doc.comment_text.clear();
+                    *review_mode = ReviewMode::Batched;
                 }
             });
     }
 }
 
-fn render_comments_list(ctx: &egui::Context, comments: &[Comment], theme: &Theme) {
+/// Edit to a queued comment, applied after the list is rendered
+enum CommentAction {
+    Jump(usize),
+    Delete(usize),
+    MoveUp(usize),
+    MoveDown(usize),
+}
+
+fn render_comments_list(ctx: &egui::Context, doc: &mut Document, theme: &Theme) {
+    let mut action = None;
+    let count = doc.comments.len();
+
     let screen_height = ctx.content_rect().height();
     egui::Window::new("Comments")
         .fixed_pos(egui::pos2(
@@ -162,20 +152,71 @@ fn render_comments_list(ctx: &egui::Context, comments: &[Comment], theme: &Theme
         .collapsible(true)
         .show(ctx, |ui| {
             egui::ScrollArea::vertical().show(ui, |ui| {
-                for comment in comments {
-                    ui.label(comment.format());
+                for (i, comment) in doc.comments.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        // Click the anchor to jump back to the commented lines
+                        if ui
+                            .link(comment.location())
+                            .on_hover_text("Jump to selection")
+                            .clicked()
+                        {
+                            action = Some(CommentAction::Jump(i));
+                        }
+                        if comment.is_outdated() {
+                            ui.weak("(outdated)")
+                                .on_hover_text("The commented text changed on disk");
+                        }
+
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.small_button("Delete").clicked() {
+                                action = Some(CommentAction::Delete(i));
+                            }
+                            if ui
+                                .add_enabled(i + 1 < count, egui::Button::new("↓").small())
+                                .clicked()
+                            {
+                                action = Some(CommentAction::MoveDown(i));
+                            }
+                            if ui
+                                .add_enabled(i > 0, egui::Button::new("↑").small())
+                                .clicked()
+                            {
+                                action = Some(CommentAction::MoveUp(i));
+                            }
+                        });
+                    });
+
+                    let edit = ui.add(
+                        egui::TextEdit::multiline(&mut comment.text)
+                            .desired_rows(2)
+                            .desired_width(f32::INFINITY),
+                    );
+                    // Clearing a comment's text deletes it
+                    if edit.lost_focus() && comment.text.trim().is_empty() {
+                        action = Some(CommentAction::Delete(i));
+                    }
                     ui.separator();
                 }
             });
         });
-}
-
-/// Extract text snippet from source for the given line range
-#[allow(dead_code)]
-fn extract_text_snippet(source: &str, start_line: usize, end_line: usize) -> String {
-    let lines: Vec<&str> = source.lines().collect();
-    let start_idx = start_line.saturating_sub(1); // Lines are 1-indexed
-    let end_idx = end_line.min(lines.len());
 
-    lines[start_idx..end_idx].join("\n")
+    match action {
+        Some(CommentAction::Jump(i)) => {
+            let c = &doc.comments[i];
+            doc.selection.select_anchor(
+                &doc.source,
+                c.line_start,
+                c.col_start,
+                c.line_end,
+                c.col_end,
+            );
+            doc.scroll_to_line = Some(c.line_start);
+        }
+        Some(CommentAction::Delete(i)) => {
+            doc.comments.remove(i);
+        }
+        Some(CommentAction::MoveUp(i)) => doc.comments.swap(i, i - 1),
+        Some(CommentAction::MoveDown(i)) => doc.comments.swap(i, i + 1),
+        None => {}
+    }
 }
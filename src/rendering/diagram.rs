@@ -0,0 +1,83 @@
+/// Diagram code blocks: the rendered diagram, or its source with a toggle between them
+use crate::diagram::{render_svg, DiagramKind, Palette};
+use crate::image_manager::ImageManager;
+use crate::models::TextChunk;
+use crate::rendering::code::render_code_block;
+use crate::storage::content_hash;
+use crate::syntax::SyntaxHighlighter;
+use crate::theme::Theme;
+use eframe::egui;
+
+/// Render a ```` ```mermaid ````/```` ```dot ```` block as a diagram
+///
+/// The SVG is cached in the image manager by content (and theme colors) and
+/// rasterized by egui's SVG loader. Diagrams that can't be laid out show the
+/// error above the highlighted source.
+pub fn render_diagram_block(
+    ui: &mut egui::Ui,
+    chunk: &TextChunk,
+    kind: DiagramKind,
+    image_manager: &mut ImageManager,
+    highlighter: &SyntaxHighlighter,
+    theme: &Theme,
+) {
+    let lang = chunk.code_block_lang.as_deref().unwrap_or("");
+    let palette = Palette {
+        text: theme.colors.text,
+        stroke: theme.colors.quote_border,
+        fill: theme.colors.code_block_bg,
+        note: theme.colors.quote_bg,
+    };
+    let uri = format!(
+        "bytes://diagram-{}.svg",
+        content_hash(&format!("{:?}\n{}\n{}", palette, lang, chunk.text))
+    );
+
+    let image = match image_manager
+        .get_or_render_svg(&uri, &chunk.text, || render_svg(kind, &chunk.text, palette))
+    {
+        Ok(image) => image,
+        Err(error) => {
+            ui.label(
+                egui::RichText::new(format!("Can't render diagram: {}", error))
+                    .small()
+                    .weak(),
+            );
+            render_code_block(ui, &chunk.text, lang, highlighter, theme);
+            return;
+        }
+    };
+
+    // Diagram/source toggle, remembered per block
+    let toggle_id = ui.id().with(("diagram_source", chunk.byte_range.start));
+    let mut show_source = ui.data(|data| data.get_temp::<bool>(toggle_id).unwrap_or(false));
+    ui.horizontal(|ui| {
+        ui.selectable_value(
+            &mut show_source,
+            false,
+            egui::RichText::new("Diagram").small(),
+        );
+        ui.selectable_value(
+            &mut show_source,
+            true,
+            egui::RichText::new("Source").small(),
+        );
+    });
+    ui.data_mut(|data| data.insert_temp(toggle_id, show_source));
+
+    if show_source {
+        render_code_block(ui, &chunk.text, lang, highlighter, theme);
+    } else {
+        egui::Frame::NONE
+            .stroke(egui::Stroke::new(1.0, theme.colors.code_block_bg))
+            .inner_margin(theme.spacing.code_block_padding)
+            .corner_radius(theme.spacing.corner_radius)
+            .show(ui, |ui| {
+                ui.add(
+                    egui::Image::new(image)
+                        .fit_to_original_size(1.0)
+                        .max_width(ui.available_width()),
+                );
+            });
+    }
+}
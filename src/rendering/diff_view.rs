@@ -0,0 +1,332 @@
+/// Diff view: unified or side-by-side hunks; click lines (shift-click to extend, removed
+/// lines included) or a hunk header to select for commenting
+use crate::diff::{DiffHunk, DiffLayout, DiffView, LineChange, RowSelection};
+use crate::models::{LayoutMap, Selection};
+use crate::rendering::selection_manager::{draw_line_cursor, draw_search_highlights};
+use crate::search::SearchState;
+use crate::theme::Theme;
+use eframe::egui;
+
+/// Width reserved for line numbers (in characters)
+const GUTTER_CHARS: usize = 4;
+
+/// Render the diff of the active document against its older revision
+pub fn render_diff(
+    ui: &mut egui::Ui,
+    diff: &mut DiffView,
+    selection: &mut Selection,
+    layout_map: &mut LayoutMap,
+    cursor: Option<usize>,
+    search: &SearchState,
+    theme: &Theme,
+) {
+    ui.horizontal(|ui| {
+        ui.label(format!("Changes since {}", diff.old_path.display()));
+        ui.separator();
+        ui.selectable_value(&mut diff.layout, DiffLayout::Unified, "Unified");
+        ui.selectable_value(&mut diff.layout, DiffLayout::SideBySide, "Side by side");
+    });
+    ui.add_space(theme.spacing.paragraph);
+
+    if diff.hunks.is_empty() {
+        ui.label(egui::RichText::new("No changes").weak());
+        return;
+    }
+
+    let mut picker = RowPicker {
+        rows: diff.row_selection(selection.line_range()),
+        selection,
+        layout_map,
+    };
+    for (index, hunk) in diff.hunks.iter().enumerate() {
+        render_hunk_header(ui, index, hunk, &mut picker, theme);
+        match diff.layout {
+            DiffLayout::Unified => {
+                for (row, line) in hunk.lines.iter().enumerate() {
+                    let response = diff_row(ui, theme, |painter, rect, font| {
+                        painter.rect_filled(rect, 0.0, line_background(theme, line.change));
+                        painter.text(
+                            rect.left_center(),
+                            egui::Align2::LEFT_CENTER,
+                            format!(
+                                "{} {} {} {}",
+                                gutter(line.old_line),
+                                gutter(line.new_line),
+                                line.prefix(),
+                                line.text
+                            ),
+                            font,
+                            theme.colors.text,
+                        );
+                    });
+                    let cells = (Some(row), Some(row));
+                    finish_row(ui, &response, index, hunk, cells, &mut picker, theme);
+                }
+            }
+            DiffLayout::SideBySide => {
+                for cells in side_by_side_rows(hunk) {
+                    let response = diff_row(ui, theme, |painter, rect, font| {
+                        let half = rect.width() / 2.0;
+                        let halves = [
+                            (
+                                egui::Rect::from_min_size(
+                                    rect.min,
+                                    egui::vec2(half, rect.height()),
+                                ),
+                                cells.0,
+                                true,
+                            ),
+                            (
+                                egui::Rect::from_min_size(
+                                    rect.min + egui::vec2(half, 0.0),
+                                    egui::vec2(half, rect.height()),
+                                ),
+                                cells.1,
+                                false,
+                            ),
+                        ];
+                        for (half_rect, row, is_old) in halves {
+                            let Some(line) = row.map(|row| &hunk.lines[row]) else {
+                                continue;
+                            };
+                            painter.rect_filled(
+                                half_rect,
+                                0.0,
+                                line_background(theme, line.change),
+                            );
+                            let number = if is_old { line.old_line } else { line.new_line };
+                            painter.with_clip_rect(half_rect).text(
+                                half_rect.left_center(),
+                                egui::Align2::LEFT_CENTER,
+                                format!("{} {} {}", gutter(number), line.prefix(), line.text),
+                                font.clone(),
+                                theme.colors.text,
+                            );
+                        }
+                    });
+                    finish_row(ui, &response, index, hunk, cells, &mut picker, theme);
+                }
+            }
+        }
+        ui.add_space(theme.spacing.paragraph * 2.0);
+    }
+    diff.selected_rows = picker.rows;
+
+    if let Some(line) = cursor {
+        draw_line_cursor(ui, line, layout_map, theme);
+    }
+    if search.open {
+        draw_search_highlights(ui, &search.matches, search.current, layout_map, theme);
+    }
+}
+
+/// Hunk header - click selects the whole hunk (including removed lines)
+fn render_hunk_header(
+    ui: &mut egui::Ui,
+    index: usize,
+    hunk: &DiffHunk,
+    picker: &mut RowPicker,
+    theme: &Theme,
+) {
+    let header = egui::RichText::new(hunk.header())
+        .monospace()
+        .size(theme.typography.code_block_size)
+        .weak();
+    let response = ui
+        .add(egui::Label::new(header).sense(egui::Sense::click()))
+        .on_hover_text("Select the whole hunk");
+    if response.clicked() && !hunk.lines.is_empty() {
+        let rows = RowSelection {
+            hunk: index,
+            first: 0,
+            last: hunk.lines.len() - 1,
+        };
+        picker.pick(hunk, rows);
+    }
+}
+
+/// Allocate one full-width monospace row and paint it
+fn diff_row(
+    ui: &mut egui::Ui,
+    theme: &Theme,
+    paint: impl FnOnce(&egui::Painter, egui::Rect, egui::FontId),
+) -> egui::Response {
+    let font = egui::FontId::monospace(theme.typography.code_block_size);
+    let height = (theme.typography.code_block_size + 6.0).max(theme.spacing.min_line_height);
+    let (rect, response) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), height),
+        egui::Sense::click(),
+    );
+    paint(&ui.painter().with_clip_rect(rect), rect, font);
+    response
+}
+
+/// Line selection while rendering a diff, with the rows it was picked from
+struct RowPicker<'a> {
+    selection: &'a mut Selection,
+    /// Picked rows (None for plain line selections, e.g. from the keyboard)
+    rows: Option<RowSelection>,
+    layout_map: &'a mut LayoutMap,
+}
+
+impl RowPicker<'_> {
+    /// Select rows, anchoring the line selection to the NEW lines they stand for
+    fn pick(&mut self, hunk: &DiffHunk, rows: RowSelection) {
+        let (start, end) = hunk.anchor_lines(rows.first, rows.last);
+        self.selection.select_lines(start, end);
+        self.rows = Some(rows);
+    }
+
+    /// Click on `row` of hunk `index` (`extend` = shift-click)
+    fn click(&mut self, index: usize, hunk: &DiffHunk, row: usize, extend: bool) {
+        let rows = match self.rows {
+            Some(rows) if extend && rows.hunk == index => RowSelection {
+                hunk: index,
+                first: rows.first.min(row),
+                last: rows.last.max(row),
+            },
+            // Extending past a hunk selects NEW lines only
+            _ if extend => {
+                if let Some(line) = hunk.lines[row].new_line {
+                    let start = self.selection.line_range().map_or(line, |(start, _)| start);
+                    self.selection
+                        .select_lines(start.min(line), start.max(line));
+                    self.rows = None;
+                }
+                return;
+            }
+            _ => RowSelection {
+                hunk: index,
+                first: row,
+                last: row,
+            },
+        };
+        self.pick(hunk, rows);
+    }
+
+    /// Whether a display row showing hunk rows `cells` is selected
+    fn is_selected(
+        &self,
+        index: usize,
+        hunk: &DiffHunk,
+        cells: (Option<usize>, Option<usize>),
+    ) -> bool {
+        if let Some(rows) = self.rows {
+            return rows.hunk == index
+                && [cells.0, cells.1]
+                    .into_iter()
+                    .flatten()
+                    .any(|row| row >= rows.first && row <= rows.last);
+        }
+        let new_line = cells.1.and_then(|row| hunk.lines[row].new_line);
+        match (self.selection.line_range(), new_line) {
+            (Some((start, end)), Some(line)) => line >= start && line <= end,
+            (Some((start, end)), None) => hunk.is_whole(start, end),
+            _ => false,
+        }
+    }
+}
+
+/// Record the row for comment placement, handle clicks and draw the selection bar
+///
+/// `cells` are the hunk rows shown on the OLD and NEW side (the same row in unified view).
+fn finish_row(
+    ui: &egui::Ui,
+    response: &egui::Response,
+    index: usize,
+    hunk: &DiffHunk,
+    cells: (Option<usize>, Option<usize>),
+    picker: &mut RowPicker,
+    theme: &Theme,
+) {
+    let rect = response.rect;
+    if let Some(line) = cells.1.and_then(|row| hunk.lines[row].new_line) {
+        picker
+            .layout_map
+            .record_chunk(line, line, rect.top(), rect.bottom());
+    }
+
+    if response.clicked() {
+        // Side by side, the clicked half picks the side
+        let left = response
+            .interact_pointer_pos()
+            .is_some_and(|pos| pos.x < rect.center().x);
+        let row = if left {
+            cells.0.or(cells.1)
+        } else {
+            cells.1.or(cells.0)
+        };
+        if let Some(row) = row {
+            picker.click(index, hunk, row, ui.input(|i| i.modifiers.shift));
+        }
+    }
+
+    if picker.is_selected(index, hunk, cells) {
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(rect.left() - 8.0, rect.top()),
+            egui::pos2(rect.left() - 4.0, rect.bottom()),
+        );
+        ui.painter()
+            .rect_filled(bar, 0.0, theme.colors.selection_highlight);
+    }
+}
+
+fn line_background(theme: &Theme, change: LineChange) -> egui::Color32 {
+    match change {
+        LineChange::Context => egui::Color32::TRANSPARENT,
+        LineChange::Added => theme.colors.diff_added_bg,
+        LineChange::Removed => theme.colors.diff_removed_bg,
+    }
+}
+
+fn gutter(line: Option<usize>) -> String {
+    match line {
+        Some(n) => format!("{:>width$}", n, width = GUTTER_CHARS),
+        None => " ".repeat(GUTTER_CHARS),
+    }
+}
+
+/// A side-by-side row: OLD line on the left, NEW line on the right (indices into
+/// `DiffHunk::lines`)
+type SideBySideRow = (Option<usize>, Option<usize>);
+
+/// Pair hunk lines for side-by-side display: context on both sides, runs of
+/// removed lines next to the added lines that replace them
+fn side_by_side_rows(hunk: &DiffHunk) -> Vec<SideBySideRow> {
+    let mut rows = Vec::new();
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+
+    for (row, line) in hunk.lines.iter().enumerate() {
+        match line.change {
+            LineChange::Removed => {
+                // A removal after additions starts a new change block
+                if !added.is_empty() {
+                    flush_change_block(&mut rows, &mut removed, &mut added);
+                }
+                removed.push(row);
+            }
+            LineChange::Added => added.push(row),
+            LineChange::Context => {
+                flush_change_block(&mut rows, &mut removed, &mut added);
+                rows.push((Some(row), Some(row)));
+            }
+        }
+    }
+    flush_change_block(&mut rows, &mut removed, &mut added);
+
+    rows
+}
+
+/// Emit removed/added runs as paired rows (the shorter side is padded with None)
+fn flush_change_block(
+    rows: &mut Vec<SideBySideRow>,
+    removed: &mut Vec<usize>,
+    added: &mut Vec<usize>,
+) {
+    for i in 0..removed.len().max(added.len()) {
+        rows.push((removed.get(i).copied(), added.get(i).copied()));
+    }
+    removed.clear();
+    added.clear();
+}
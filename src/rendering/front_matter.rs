@@ -0,0 +1,50 @@
+/// Front matter card: the document's metadata as a key/value grid above the content
+use crate::models::FrontMatter;
+use crate::rendering::text_builder::{build_styled_text, TextContext};
+use crate::theme::Theme;
+use eframe::egui;
+
+/// Render the metadata card shown between the title and the document body
+pub fn render_front_matter(ui: &mut egui::Ui, front_matter: &FrontMatter, theme: &Theme) {
+    egui::Frame::new()
+        .fill(theme.colors.quote_bg)
+        .stroke(egui::Stroke::new(1.0, theme.colors.quote_border))
+        .corner_radius(theme.spacing.corner_radius)
+        .inner_margin(egui::Margin::symmetric(12, 8))
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            egui::Grid::new("front_matter")
+                .num_columns(2)
+                .spacing([16.0, 4.0])
+                .show(ui, |ui| {
+                    for (key, value) in &front_matter.entries {
+                        ui.label(
+                            build_styled_text(
+                                key,
+                                true,
+                                false,
+                                false,
+                                false,
+                                TextContext::Body,
+                                theme,
+                            )
+                            .color(theme.colors.heading),
+                        );
+                        ui.add(
+                            egui::Label::new(build_styled_text(
+                                value,
+                                false,
+                                false,
+                                false,
+                                false,
+                                TextContext::Body,
+                                theme,
+                            ))
+                            .wrap(),
+                        );
+                        ui.end_row();
+                    }
+                });
+        });
+    ui.add_space(theme.spacing.paragraph);
+}
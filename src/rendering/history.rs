@@ -0,0 +1,90 @@
+/// Prior review rounds: per-round toggles and margin markers
+use crate::models::{LayoutMap, ReviewRound};
+use crate::theme::Theme;
+use eframe::egui;
+
+/// Marker color for a round (cycles through the theme palette)
+fn round_color(theme: &Theme, round_idx: usize) -> egui::Color32 {
+    let palette = &theme.colors.history_markers;
+    palette[round_idx % palette.len()]
+}
+
+/// Render the bar listing prior review rounds, with a visibility toggle per round
+pub fn render_history_bar(ctx: &egui::Context, history: &mut [ReviewRound], theme: &Theme) {
+    if history.is_empty() {
+        return;
+    }
+
+    egui::TopBottomPanel::top("review_history").show(ctx, |ui| {
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Previous reviews:");
+            for (i, round) in history.iter_mut().enumerate() {
+                let label = egui::RichText::new(round.label(i + 1)).color(round_color(theme, i));
+                ui.checkbox(&mut round.visible, label);
+            }
+        });
+    });
+}
+
+/// Draw margin markers for visible rounds at their stored selection ranges
+/// Each round gets its own column; hovering a marker shows the prior comment
+pub fn draw_history_markers(
+    ui: &mut egui::Ui,
+    history: &[ReviewRound],
+    layout_map: &LayoutMap,
+    theme: &Theme,
+) {
+    let marker_width = 4.0;
+    let content_left = ui.max_rect().left() - 12.0; // 12px left of the content column
+    let mut column = 0;
+
+    for (round_idx, round) in history.iter().enumerate() {
+        if !round.visible || round.comments.is_empty() {
+            continue;
+        }
+
+        let right = content_left - column as f32 * (marker_width + 2.0);
+        column += 1;
+
+        for (comment_idx, stored) in round.comments.iter().enumerate() {
+            let anchor = &stored.anchor;
+            let Some((start_y, end_y)) = layout_map.get_y_range(anchor.line_start, anchor.line_end)
+            else {
+                continue;
+            };
+
+            let marker_rect = egui::Rect::from_min_max(
+                egui::pos2(right - marker_width, start_y),
+                egui::pos2(right, end_y),
+            );
+            let color = round_color(theme, round_idx);
+
+            // Outdated comments: hollow marker at the original position
+            let status = if anchor.is_outdated() {
+                ui.painter().rect_stroke(
+                    marker_rect,
+                    2.0,
+                    egui::Stroke::new(1.0, color),
+                    egui::StrokeKind::Inside,
+                );
+                " (outdated - text no longer in document)"
+            } else {
+                ui.painter().rect_filled(marker_rect, 2.0, color);
+                ""
+            };
+
+            ui.interact(
+                marker_rect.expand(2.0),
+                ui.id().with(("history_marker", round_idx, comment_idx)),
+                egui::Sense::hover(),
+            )
+            .on_hover_text(format!(
+                "Round {}{}: {}\n\n{}",
+                round_idx + 1,
+                status,
+                stored.comment.comment,
+                stored.comment.text
+            ));
+        }
+    }
+}
@@ -1,5 +1,5 @@
 /// Batch consecutive inline text chunks into horizontal_wrapped layouts
-use crate::models::TextChunk;
+use crate::models::{Math, TextChunk};
 
 /// Determines if chunks should be batched together in horizontal_wrapped layout
 pub struct InlineTextBatcher;
@@ -7,12 +7,13 @@ pub struct InlineTextBatcher;
 impl InlineTextBatcher {
     /// Check if a chunk should be batched with others (is inline text)
     pub fn is_inline_chunk(chunk: &TextChunk) -> bool {
-        // Inline chunks are: text, not image, not code block, not table,
+        // Inline chunks are: text, not image, not code block, not table, not display math,
         // not ending with newline, and not starting with a list bullet
         if chunk.image_path.is_some()
             || chunk.code_block_lang.is_some()
             || chunk.table.is_some()
             || chunk.heading_level.is_some()
+            || chunk.math == Some(Math::Display)
             || chunk.newline_after
         {
             return false;
@@ -41,6 +42,7 @@ impl InlineTextBatcher {
             || first_chunk.code_block_lang.is_some()
             || first_chunk.table.is_some()
             || first_chunk.heading_level.is_some()
+            || first_chunk.math == Some(Math::Display)
             || Self::starts_with_bullet(&first_chunk.text)
         {
             return None;
@@ -52,10 +54,14 @@ impl InlineTextBatcher {
             let chunk = &chunks[end_idx];
 
             // Check if chunk is batchable (similar check but allow newline_after temporarily)
+            // A new list item, or a change of list nesting, starts a new row
             if chunk.image_path.is_some()
                 || chunk.code_block_lang.is_some()
                 || chunk.table.is_some()
                 || chunk.heading_level.is_some()
+                || chunk.math == Some(Math::Display)
+                || chunk.list_item.is_some()
+                || chunk.list_depth != first_chunk.list_depth
                 || Self::starts_with_bullet(&chunk.text)
             {
                 break;
@@ -76,18 +82,18 @@ impl InlineTextBatcher {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::ListItem;
 
     fn create_text_chunk(text: &str, newline_after: bool) -> TextChunk {
         TextChunk {
             text: text.to_string(),
             byte_range: 0..text.len(),
             line_start: 1,
-            col_start: 1,
             line_end: 1,
-            col_end: text.len(),
             bold: false,
             italic: false,
             code: false,
+            strikethrough: false,
             heading_level: None,
             newline_after,
             image_path: None,
@@ -96,6 +102,12 @@ mod tests {
             image_height: None,
             code_block_lang: None,
             table: None,
+            link: None,
+            list_depth: 0,
+            list_item: None,
+            quote: None,
+            footnote: None,
+            math: None,
             cached_height: None,
         }
     }
@@ -118,6 +130,24 @@ mod tests {
         assert!(!InlineTextBatcher::is_inline_chunk(&chunk));
     }
 
+    #[test]
+    fn test_is_inline_chunk_strikethrough() {
+        // Strikethrough is a text style: it flows inline with its paragraph
+        let mut chunk = create_text_chunk("removed", false);
+        chunk.strikethrough = true;
+        assert!(InlineTextBatcher::is_inline_chunk(&chunk));
+    }
+
+    #[test]
+    fn test_is_inline_chunk_math() {
+        // Inline formulas flow with the text; display formulas get their own row
+        let mut chunk = create_text_chunk("x^2", false);
+        chunk.math = Some(Math::Inline);
+        assert!(InlineTextBatcher::is_inline_chunk(&chunk));
+        chunk.math = Some(Math::Display);
+        assert!(!InlineTextBatcher::is_inline_chunk(&chunk));
+    }
+
     #[test]
     fn test_is_inline_chunk_with_image() {
         let mut chunk = create_text_chunk("Alt text", false);
@@ -178,6 +208,32 @@ mod tests {
         assert_eq!(result, None);
     }
 
+    #[test]
+    fn test_find_inline_batch_stops_at_list_item() {
+        let mut chunks = vec![
+            create_text_chunk("Parent ", false),
+            create_text_chunk("item", false),
+            create_text_chunk("Child", true),
+        ];
+        chunks[0].list_item = Some(ListItem {
+            number: None,
+            checked: None,
+        });
+        chunks[2].list_item = chunks[0].list_item.clone();
+        for (chunk, depth) in chunks.iter_mut().zip([1, 1, 2]) {
+            chunk.list_depth = depth;
+        }
+
+        assert_eq!(
+            InlineTextBatcher::find_inline_batch(&chunks, 0),
+            Some((0, 2))
+        );
+        assert_eq!(
+            InlineTextBatcher::find_inline_batch(&chunks, 2),
+            Some((2, 3))
+        );
+    }
+
     #[test]
     fn test_starts_with_bullet_variations() {
         assert!(InlineTextBatcher::starts_with_bullet("- item"));
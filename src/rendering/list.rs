@@ -0,0 +1,71 @@
+/// List item markers: bullets by nesting depth, ordered numbers, task checkboxes
+use crate::models::ListItem;
+use crate::theme::Theme;
+use eframe::egui;
+
+/// Allocate the marker column of a list row and paint the item's marker
+///
+/// Rows continuing an item (later paragraphs, code blocks) pass `None` and get an
+/// empty column, so their content lines up with the item text.
+pub fn render_list_marker(ui: &mut egui::Ui, item: Option<&ListItem>, depth: usize, theme: &Theme) {
+    let font_id = egui::FontId::proportional(theme.typography.body_size);
+    let row_height = ui.fonts_mut(|fonts| fonts.row_height(&font_id));
+    let (rect, _) = ui.allocate_exact_size(
+        egui::vec2(theme.spacing.list_indent, row_height),
+        egui::Sense::hover(),
+    );
+    let Some(item) = item else {
+        return;
+    };
+
+    let painter = ui.painter();
+    let color = theme.colors.text;
+    // Markers sit right-aligned in the column, a small gap before the text
+    let anchor = egui::pos2(rect.right() - 8.0, rect.center().y);
+
+    if let Some(checked) = item.checked {
+        let size = theme.typography.body_size * 0.85;
+        let checkbox = egui::Rect::from_center_size(
+            egui::pos2(anchor.x - size / 2.0, anchor.y),
+            egui::vec2(size, size),
+        );
+        if checked {
+            painter.rect_filled(checkbox, 2.0, theme.colors.link);
+            let check = [
+                checkbox.lerp_inside(egui::vec2(0.22, 0.52)),
+                checkbox.lerp_inside(egui::vec2(0.42, 0.72)),
+                checkbox.lerp_inside(egui::vec2(0.78, 0.3)),
+            ];
+            painter.line(check.to_vec(), egui::Stroke::new(1.5, egui::Color32::WHITE));
+        } else {
+            painter.rect_stroke(
+                checkbox,
+                2.0,
+                egui::Stroke::new(1.0, color),
+                egui::StrokeKind::Inside,
+            );
+        }
+    } else if let Some(number) = item.number {
+        painter.text(
+            anchor,
+            egui::Align2::RIGHT_CENTER,
+            format!("{}.", number),
+            font_id,
+            color,
+        );
+    } else {
+        // Bullet style cycles with depth: disc, circle, square
+        let radius = 2.5;
+        let center = egui::pos2(anchor.x - radius, anchor.y);
+        let shape = match (depth.max(1) - 1) % 3 {
+            0 => egui::Shape::circle_filled(center, radius, color),
+            1 => egui::Shape::circle_stroke(center, radius, egui::Stroke::new(1.0, color)),
+            _ => egui::Shape::rect_filled(
+                egui::Rect::from_center_size(center, egui::vec2(radius * 2.0, radius * 2.0)),
+                0.0,
+                color,
+            ),
+        };
+        painter.add(shape);
+    }
+}
@@ -0,0 +1,68 @@
+/// Math formulas: rendered formula textures, the source in monospace if rendering fails
+use crate::image_manager::ImageManager;
+use crate::math::render_formula;
+use crate::models::{Math, TextChunk};
+use crate::rendering::text_builder::{build_styled_text, TextContext};
+use crate::storage::content_hash;
+use crate::theme::Theme;
+use eframe::egui;
+
+/// Render a formula chunk (display formulas centered on their own row)
+///
+/// Formulas are rendered at the screen's pixel density and cached in the image
+/// manager. Hovering shows the TeX source, or why it couldn't be rendered.
+pub fn render_math_chunk(
+    ui: &mut egui::Ui,
+    ctx: &egui::Context,
+    chunk: &TextChunk,
+    math: Math,
+    image_manager: &mut ImageManager,
+    theme: &Theme,
+) -> egui::Response {
+    let (size, color) = match chunk.heading_level {
+        Some(level) => (
+            theme.typography.heading_sizes[(level - 1).min(5) as usize],
+            theme.colors.heading,
+        ),
+        None => (theme.typography.body_size, theme.colors.text),
+    };
+    let display = math == Math::Display;
+    let pixels_per_point = ctx.pixels_per_point();
+    let key = format!(
+        "math:{}:{}:{}",
+        display,
+        size * pixels_per_point,
+        content_hash(&chunk.text)
+    );
+
+    let rendered = image_manager.get_or_render_texture(ctx, &key, &chunk.text, || {
+        render_formula(&chunk.text, display, size * pixels_per_point, color)
+    });
+    let (response, hover_text) = match rendered {
+        Ok(texture) => {
+            let image =
+                egui::Image::new(texture).fit_to_exact_size(texture.size_vec2() / pixels_per_point);
+            let response = if display {
+                ui.vertical_centered(|ui| ui.add(image)).inner
+            } else {
+                ui.add(image)
+            };
+            (response, chunk.text.clone())
+        }
+        Err(error) => {
+            let delimiter = if display { "$$" } else { "$" };
+            let source = format!("{}{}{}", delimiter, chunk.text, delimiter);
+            let styled_text =
+                build_styled_text(&source, false, false, true, false, TextContext::Body, theme);
+            (
+                ui.label(styled_text),
+                format!("Can't render formula: {}", error),
+            )
+        }
+    };
+
+    // Sense drags for selection, as for text chunks
+    let unique_id = ui.id().with((chunk.line_start, chunk.byte_range.start));
+    ui.interact(response.rect, unique_id, egui::Sense::click_and_drag())
+        .on_hover_text(hover_text)
+}
@@ -1,16 +1,31 @@
+pub mod changes;
 mod chunk;
 pub(crate) mod chunk_renderer;
 pub mod code;
 pub mod comments;
+pub mod diagram;
+pub mod diff_view;
+pub mod front_matter;
 pub(crate) mod helpers;
+pub mod history;
 pub mod image;
 pub(crate) mod inline_batcher;
+pub mod list;
+pub mod math;
+pub mod quote;
+pub mod search;
 pub(crate) mod selection_manager;
 pub mod table;
 pub mod text;
 pub(crate) mod text_builder;
+pub mod toc;
 pub mod ui;
 pub(crate) mod viewport;
 
 pub use comments::render_comment_section;
+pub use diff_view::render_diff;
+pub use front_matter::render_front_matter;
+pub use history::render_history_bar;
+pub use search::render_search_bar;
+pub use toc::render_toc_panel;
 pub use ui::render_content;
@@ -0,0 +1,84 @@
+/// Blockquotes and GitHub alerts: left border per nesting level, tinted background,
+/// colored callout title for `> [!NOTE]`-style alerts
+use crate::models::{AlertKind, Quote};
+use crate::rendering::text_builder::{build_styled_text, TextContext};
+use crate::theme::Theme;
+use eframe::egui;
+
+/// Width of the border bar drawn for each nesting level
+const BORDER_WIDTH: f32 = 3.0;
+
+/// Accent and background colors of an alert
+fn alert_colors(kind: AlertKind, theme: &Theme) -> (egui::Color32, egui::Color32) {
+    let i = match kind {
+        AlertKind::Note => 0,
+        AlertKind::Tip => 1,
+        AlertKind::Important => 2,
+        AlertKind::Warning => 3,
+        AlertKind::Caution => 4,
+    };
+    (theme.colors.alert_markers[i], theme.colors.alert_bg[i])
+}
+
+/// Render one row of quoted content, indented past the quote borders
+///
+/// The background spans the full content width and overlaps the item spacing to the
+/// neighbouring rows, so consecutive rows of a quote form one continuous block.
+pub fn render_quote_row(
+    ui: &mut egui::Ui,
+    quote: &Quote,
+    theme: &Theme,
+    add_contents: impl FnOnce(&mut egui::Ui),
+) {
+    let (accent, background) = match quote.alert {
+        Some(kind) => alert_colors(kind, theme),
+        None => (theme.colors.quote_border, theme.colors.quote_bg),
+    };
+
+    // Reserve a paint slot below the content for the background and borders
+    let backdrop = ui.painter().add(egui::Shape::Noop);
+
+    let response = ui.horizontal_top(|ui| {
+        ui.add_space(quote.depth as f32 * theme.spacing.quote_indent);
+        ui.vertical(|ui| {
+            if let (true, Some(kind)) = (quote.first, quote.alert) {
+                ui.label(
+                    build_styled_text(
+                        kind.title(),
+                        true,
+                        false,
+                        false,
+                        false,
+                        TextContext::Body,
+                        theme,
+                    )
+                    .color(accent),
+                );
+            }
+            add_contents(ui);
+        });
+    });
+
+    let gap = ui.spacing().item_spacing.y / 2.0;
+    let rect = egui::Rect::from_x_y_ranges(
+        ui.max_rect().x_range(),
+        response.response.rect.top() - gap..=response.response.rect.bottom() + gap,
+    );
+
+    let mut shapes = vec![egui::Shape::rect_filled(rect, 0.0, background)];
+    for level in 0..quote.depth {
+        // Outer levels are plain quotes; the innermost one carries the alert accent
+        let color = if level + 1 == quote.depth {
+            accent
+        } else {
+            theme.colors.quote_border
+        };
+        let left = rect.left() + level as f32 * theme.spacing.quote_indent;
+        shapes.push(egui::Shape::rect_filled(
+            egui::Rect::from_x_y_ranges(left..=left + BORDER_WIDTH, rect.y_range()),
+            0.0,
+            color,
+        ));
+    }
+    ui.painter().set(backdrop, egui::Shape::Vec(shapes));
+}
@@ -0,0 +1,197 @@
+/// Search UI: document search bar (Ctrl+F) and cross-document search panel
+/// (Ctrl+Shift+F) - query field, case/regex toggles, match stepping, results by file
+use crate::search::{GlobalSearch, SearchQuery, SearchState};
+use eframe::egui;
+
+/// Widget id of the document search field
+fn search_input_id() -> egui::Id {
+    egui::Id::new("search_input")
+}
+
+/// Widget id of the cross-document search field
+fn global_search_input_id() -> egui::Id {
+    egui::Id::new("global_search_input")
+}
+
+/// Give the document search field keyboard focus
+pub fn focus_search_input(ctx: &egui::Context) {
+    ctx.memory_mut(|memory| memory.request_focus(search_input_id()));
+}
+
+/// Give the cross-document search field keyboard focus
+pub fn focus_global_search_input(ctx: &egui::Context) {
+    ctx.memory_mut(|memory| memory.request_focus(global_search_input_id()));
+}
+
+/// Query text field followed by the case and regex toggles
+fn query_field(
+    ui: &mut egui::Ui,
+    query: &mut SearchQuery,
+    id: egui::Id,
+    width: f32,
+) -> egui::Response {
+    let response = ui.add(
+        egui::TextEdit::singleline(&mut query.text)
+            .id(id)
+            .hint_text(if query.regex {
+                "Regular expression"
+            } else {
+                "Search text"
+            })
+            .desired_width(width),
+    );
+    ui.toggle_value(&mut query.case_sensitive, "Aa")
+        .on_hover_text("Match case");
+    ui.toggle_value(&mut query.regex, ".*")
+        .on_hover_text("Regular expression");
+    response
+}
+
+/// Render the search bar while search is open
+///
+/// Enter/Shift+Enter in the field step to the next/previous match. Returns true when
+/// "Comment on match" was clicked.
+pub fn render_search_bar(ctx: &egui::Context, search: &mut SearchState) -> bool {
+    if !search.open {
+        return false;
+    }
+    let mut comment_requested = false;
+
+    egui::TopBottomPanel::top("search_bar").show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("Find:");
+            let response = query_field(ui, &mut search.query, search_input_id(), 240.0);
+            // Single-line fields give up focus on Enter: step and keep typing
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                search.step(!ui.input(|i| i.modifiers.shift));
+                response.request_focus();
+            }
+            ui.separator();
+
+            if let Some(error) = &search.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            } else if !search.query.text.is_empty() {
+                let position = match search.current {
+                    Some(i) => format!("{} of {}", i + 1, search.matches.len()),
+                    None => "No matches".to_string(),
+                };
+                ui.label(position);
+            }
+
+            let has_matches = !search.matches.is_empty();
+            if ui
+                .add_enabled(has_matches, egui::Button::new("↑"))
+                .on_hover_text("Previous match (Shift+Enter)")
+                .clicked()
+            {
+                search.step(false);
+            }
+            if ui
+                .add_enabled(has_matches, egui::Button::new("↓"))
+                .on_hover_text("Next match (Enter)")
+                .clicked()
+            {
+                search.step(true);
+            }
+            if ui
+                .add_enabled(
+                    search.current.is_some(),
+                    egui::Button::new("Comment on match"),
+                )
+                .clicked()
+            {
+                comment_requested = true;
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .small_button("✕")
+                    .on_hover_text("Close (Escape)")
+                    .clicked()
+                {
+                    search.open = false;
+                }
+            });
+        });
+    });
+
+    comment_requested
+}
+
+/// Render the cross-document search panel while it is open
+///
+/// Returns the (document index, line) of a clicked result.
+pub fn render_global_search(
+    ctx: &egui::Context,
+    global: &mut GlobalSearch,
+    active_document: usize,
+) -> Option<(usize, usize)> {
+    if !global.open {
+        return None;
+    }
+    let mut jump = None;
+
+    egui::SidePanel::right("global_search")
+        .resizable(true)
+        .default_width(320.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.strong("Search all documents");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui
+                        .small_button("✕")
+                        .on_hover_text("Close (Escape)")
+                        .clicked()
+                    {
+                        global.open = false;
+                    }
+                });
+            });
+            ui.horizontal(|ui| {
+                query_field(ui, &mut global.query, global_search_input_id(), 180.0);
+            });
+
+            if let Some(error) = &global.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            } else if !global.query.text.is_empty() {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} match(es) in {} file(s)",
+                        global.hit_count(),
+                        global.results.len()
+                    ))
+                    .weak(),
+                );
+            }
+            ui.separator();
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for file in &global.results {
+                    let header = format!("{} ({})", file.filename, file.hits.len());
+                    let header = if file.doc_index == active_document {
+                        egui::RichText::new(header).strong()
+                    } else {
+                        egui::RichText::new(header)
+                    };
+                    egui::CollapsingHeader::new(header)
+                        .id_salt(("global_search_file", file.doc_index))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for hit in &file.hits {
+                                let label = format!("{:>4}  {}", hit.hit.line_start, hit.line_text);
+                                let response = ui.add(
+                                    egui::Label::new(egui::RichText::new(label).monospace())
+                                        .truncate()
+                                        .sense(egui::Sense::click()),
+                                );
+                                if response.on_hover_text(&hit.line_text).clicked() {
+                                    jump = Some((file.doc_index, hit.hit.line_start));
+                                }
+                            }
+                        });
+                }
+            });
+        });
+
+    jump
+}
@@ -1,6 +1,7 @@
 /// Centralized selection handling for all chunk types
-use crate::models::{LayoutMap, Selection, TextChunk};
+use crate::models::{LayoutMap, Selection, TextChunk, TextSpan};
 use crate::rendering::helpers::calculate_line_from_y;
+use crate::search::SearchMatch;
 use crate::theme::Theme;
 use eframe::egui;
 
@@ -8,23 +9,28 @@ use eframe::egui;
 pub struct SelectionManager<'a> {
     selection: &'a mut Selection,
     layout_map: &'a LayoutMap,
+    /// Document source (drag positions map to its bytes)
+    source: &'a str,
 }
 
 impl<'a> SelectionManager<'a> {
     /// Create a new selection manager
-    pub fn new(selection: &'a mut Selection, layout_map: &'a LayoutMap) -> Self {
+    pub fn new(selection: &'a mut Selection, layout_map: &'a LayoutMap, source: &'a str) -> Self {
         Self {
             selection,
             layout_map,
+            source,
         }
     }
 
     /// Handle selection interaction for a chunk (drag start, click)
+    /// Text chunks pass their span so the drag starts at a precise source byte
     /// Returns true if the chunk was clicked (for click-to-clear detection)
     pub fn handle_interaction(
         &mut self,
         response: &egui::Response,
         chunk: &TextChunk,
+        span: Option<&TextSpan>,
         before_y: f32,
         after_y: f32,
     ) -> bool {
@@ -40,7 +46,13 @@ impl<'a> SelectionManager<'a> {
                     after_y,
                     interact_pos.y,
                 );
-                self.selection.start_drag(precise_line);
+                match span {
+                    Some(span) => self.selection.start_drag_at(
+                        precise_line,
+                        span.source_byte_at(self.source, interact_pos),
+                    ),
+                    None => self.selection.start_drag(precise_line),
+                }
             } else {
                 self.selection.start_drag(chunk.line_start);
             }
@@ -71,6 +83,20 @@ impl<'a> SelectionManager<'a> {
         }
 
         if let Some(hover_pos) = ui.input(|i| i.pointer.hover_pos()) {
+            // Over rendered text: extend to the precise character
+            if let Some(span) = self.layout_map.text_span_at(hover_pos) {
+                let precise_line = calculate_line_from_y(
+                    span.line_start,
+                    span.line_end,
+                    span.rect.top(),
+                    span.rect.bottom(),
+                    hover_pos.y,
+                );
+                self.selection
+                    .update_drag_at(precise_line, span.source_byte_at(self.source, hover_pos));
+                return;
+            }
+
             // Use layout map to find which line we're hovering over
             for &(line_start, line_end, y_start, y_end) in &self.layout_map.chunks {
                 if hover_pos.y >= y_start && hover_pos.y <= y_end {
@@ -114,4 +140,130 @@ impl<'a> SelectionManager<'a> {
             }
         }
     }
+
+    /// Highlight the selected characters within rendered text spans
+    pub fn draw_text_highlight(&self, ui: &mut egui::Ui, theme: &Theme) {
+        let Some((start, end)) = self.selection.byte_range() else {
+            return;
+        };
+        let color = theme.colors.selection_highlight.gamma_multiply(0.25);
+
+        for span in &self.layout_map.text_spans {
+            let sel_start = start.max(span.byte_range.start);
+            let sel_end = end.min(span.byte_range.end);
+            if sel_start >= sel_end {
+                continue;
+            }
+
+            let (from, to) = char_range(span, sel_start, sel_end);
+            for rect in row_rects(&span.galley, from, to) {
+                ui.painter()
+                    .rect_filled(rect.translate(span.galley_pos.to_vec2()), 2.0, color);
+            }
+        }
+    }
+}
+
+/// Draw the keyboard line cursor: a faint outlined band across the line
+pub fn draw_line_cursor(ui: &egui::Ui, line: usize, layout_map: &LayoutMap, theme: &Theme) {
+    let Some((start_y, end_y)) = layout_map.get_y_range(line, line) else {
+        return;
+    };
+    let rect = egui::Rect::from_x_y_ranges(ui.max_rect().x_range(), start_y..=end_y);
+    let color = theme.colors.selection_highlight;
+    ui.painter()
+        .rect_filled(rect, 2.0, color.gamma_multiply(0.1));
+    ui.painter().rect_stroke(
+        rect,
+        2.0,
+        egui::Stroke::new(1.0, color),
+        egui::StrokeKind::Outside,
+    );
+}
+
+/// Highlight search matches: characters within rendered text, whole lines elsewhere
+/// (code blocks, tables, images)
+pub fn draw_search_highlights(
+    ui: &egui::Ui,
+    matches: &[SearchMatch],
+    current: Option<usize>,
+    layout_map: &LayoutMap,
+    theme: &Theme,
+) {
+    for (i, hit) in matches.iter().enumerate() {
+        let color = if current == Some(i) {
+            theme.colors.search_current
+        } else {
+            theme.colors.search_match
+        };
+
+        let mut in_text = false;
+        for span in &layout_map.text_spans {
+            let start = hit.byte_range.start.max(span.byte_range.start);
+            let end = hit.byte_range.end.min(span.byte_range.end);
+            if start >= end {
+                continue;
+            }
+            in_text = true;
+            let (from, to) = char_range(span, start, end);
+            for rect in row_rects(&span.galley, from, to) {
+                ui.painter()
+                    .rect_filled(rect.translate(span.galley_pos.to_vec2()), 2.0, color);
+            }
+        }
+
+        if !in_text {
+            if let Some((start_y, end_y)) = layout_map.get_y_range(hit.line_start, hit.line_end) {
+                let rect = egui::Rect::from_x_y_ranges(ui.max_rect().x_range(), start_y..=end_y);
+                ui.painter()
+                    .rect_filled(rect, 2.0, color.gamma_multiply(0.5));
+            }
+        }
+    }
+}
+
+/// Rendered character range covering source bytes [start, end) of a span
+/// Transformed text (length differs from source) highlights the whole span
+fn char_range(span: &TextSpan, start: usize, end: usize) -> (usize, usize) {
+    let text = span.galley.text();
+    let char_count = text.chars().count();
+    if text.len() != span.byte_range.len() {
+        return (0, char_count);
+    }
+
+    let to_char = |byte: usize| {
+        text.get(..byte - span.byte_range.start)
+            .map_or(char_count, |prefix| prefix.chars().count())
+    };
+    (to_char(start), to_char(end))
+}
+
+/// Galley-relative rects covering characters [from, to), one per wrapped row
+fn row_rects(galley: &egui::Galley, from: usize, to: usize) -> Vec<egui::Rect> {
+    let start = galley.pos_from_cursor(egui::text::CCursor::new(from));
+    let end = galley.pos_from_cursor(egui::text::CCursor::new(to));
+    let right = galley.rect.right();
+
+    if (start.min.y - end.min.y).abs() < 1.0 {
+        return vec![egui::Rect::from_min_max(
+            start.min,
+            egui::pos2(end.min.x, start.max.y),
+        )];
+    }
+
+    let mut rects = vec![egui::Rect::from_min_max(
+        start.min,
+        egui::pos2(right, start.max.y),
+    )];
+    if end.min.y > start.max.y {
+        rects.push(egui::Rect::from_min_max(
+            egui::pos2(galley.rect.left(), start.max.y),
+            egui::pos2(right, end.min.y),
+        ));
+    }
+    rects.push(egui::Rect::from_min_max(
+        egui::pos2(galley.rect.left(), end.min.y),
+        end.max,
+    ));
+    rects
 }
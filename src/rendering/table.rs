@@ -65,6 +65,7 @@ fn render_cell(
         false, // bold
         false, // italic
         false, // code
+        false, // strikethrough
         TextContext::TableCell { is_header },
         theme,
     );
@@ -1,33 +1,139 @@
-use crate::models::TextChunk;
-use crate::rendering::text_builder::{render_styled_text, TextContext};
+use crate::models::{Footnote, TextChunk};
+use crate::rendering::text_builder::{
+    build_styled_text, emoji_runs, paint_emoji, style_link, style_superscript, TextContext,
+};
 use crate::theme::Theme;
 use eframe::egui;
+use eframe::egui::text::{CCursor, LayoutJob, LayoutSection};
+use std::ops::Range;
+use std::sync::Arc;
 
-/// Render a plain text chunk with styling and drag sensing
-pub fn render_text_chunk(ui: &mut egui::Ui, chunk: &TextChunk, theme: &Theme) -> egui::Response {
-    // Determine rendering context
-    let context = if let Some(level) = chunk.heading_level {
+/// Determine rendering context for a text chunk
+fn text_context(chunk: &TextChunk) -> TextContext {
+    if let Some(level) = chunk.heading_level {
         TextContext::Heading(level)
     } else {
         TextContext::Body
-    };
+    }
+}
 
-    // Render using centralized builder with emoji support
-    // Note: EmojiLabel doesn't support .selectable(false), but that's OK
-    // We handle selection via interact() below
-    // Use line_start and byte_range as unique ID to avoid widget ID collisions
-    let unique_id = ui.id().with((chunk.line_start, chunk.byte_range.start));
-    let label = render_styled_text(
-        ui,
+/// Styled text of a chunk (links get link styling on top of their text style, footnote
+/// references are raised)
+fn chunk_rich_text(chunk: &TextChunk, theme: &Theme) -> egui::RichText {
+    let styled_text = build_styled_text(
         &chunk.text,
         chunk.bold,
         chunk.italic,
         chunk.code,
-        context,
+        chunk.strikethrough,
+        text_context(chunk),
         theme,
-        (chunk.line_start, chunk.byte_range.start),
     );
+    match (&chunk.link, &chunk.footnote) {
+        (Some(_), Some(Footnote::Reference(_))) => {
+            style_superscript(style_link(styled_text, theme), theme)
+        }
+        (Some(_), _) => style_link(styled_text, theme),
+        (None, _) => styled_text,
+    }
+}
+
+/// Layout job of a chunk's styled text, emoji drawn transparent (`render_text_chunk`
+/// paints them as images over their glyphs)
+fn chunk_layout_job(ui: &egui::Ui, chunk: &TextChunk, theme: &Theme) -> LayoutJob {
+    let job = egui::WidgetText::from(chunk_rich_text(chunk, theme)).into_layout_job(
+        ui.style(),
+        egui::FontSelection::Default,
+        ui.text_valign(),
+    );
+    let mut job = Arc::unwrap_or_clone(job);
+
+    let runs = emoji_runs(&job.text);
+    if runs.is_empty() {
+        return job;
+    }
+
+    // Split sections at emoji boundaries
+    let sections = std::mem::take(&mut job.sections);
+    for section in sections {
+        let Range { start, end } = section.byte_range.clone();
+        let mut pos = start;
+        let mut push = |range: Range<usize>, emoji: bool| {
+            let mut format = section.format.clone();
+            if emoji {
+                format.color = egui::Color32::TRANSPARENT;
+            }
+            job.sections.push(LayoutSection {
+                leading_space: if range.start == start {
+                    section.leading_space
+                } else {
+                    0.0
+                },
+                byte_range: range,
+                format,
+            });
+        };
+        for run in runs.iter().filter(|run| run.start < end && run.end > start) {
+            let (from, to) = (run.start.max(start), run.end.min(end));
+            if pos < from {
+                push(pos..from, false);
+            }
+            push(from..to, true);
+            pos = to;
+        }
+        if pos < end {
+            push(pos..end, false);
+        }
+    }
+    job
+}
+
+/// Render a plain text chunk with styling and drag sensing
+///
+/// Returns where the text's galley was drawn, the galley, and the response. The same
+/// galley backs character hit-testing, so selections land on the glyphs shown. Link
+/// chunks show a pointing-hand cursor and their destination on hover; following the
+/// link on click is up to the caller.
+pub fn render_text_chunk(
+    ui: &mut egui::Ui,
+    chunk: &TextChunk,
+    theme: &Theme,
+) -> (egui::Pos2, Arc<egui::Galley>, egui::Response) {
+    let job = chunk_layout_job(ui, chunk, theme);
+    let (galley_pos, galley, label) = egui::Label::new(job).selectable(false).layout_in_ui(ui);
+    if ui.is_rect_visible(label.rect) {
+        ui.painter()
+            .galley(galley_pos, galley.clone(), theme.colors.text);
+
+        // Colored emoji over their (transparent) glyphs
+        let text = galley.text();
+        for run in emoji_runs(text) {
+            let from = text[..run.start].chars().count();
+            let to = from + text[run.clone()].chars().count();
+            let start = galley.pos_from_cursor(CCursor::new(from));
+            let end = galley.pos_from_cursor(CCursor::new(to));
+            let rect = egui::Rect::from_min_max(start.min, egui::pos2(end.min.x, start.max.y));
+            let size = galley
+                .job
+                .sections
+                .iter()
+                .find(|section| section.byte_range.contains(&run.start))
+                .map_or(theme.typography.body_size, |section| {
+                    section.format.font_id.size
+                });
+            paint_emoji(ui, rect.translate(galley_pos.to_vec2()), &text[run], size);
+        }
+    }
 
     // Sense drags on the rect for selection using our unique ID, not the label's ID
-    ui.interact(label.rect, unique_id, egui::Sense::click_and_drag())
+    // (line_start and byte_range avoid widget ID collisions)
+    let unique_id = ui.id().with((chunk.line_start, chunk.byte_range.start));
+    let response = ui.interact(label.rect, unique_id, egui::Sense::click_and_drag());
+    let response = match &chunk.link {
+        Some(dest) => response
+            .on_hover_cursor(egui::CursorIcon::PointingHand)
+            .on_hover_text(dest),
+        None => response,
+    };
+    (galley_pos, galley, response)
 }
@@ -1,6 +1,7 @@
 use crate::theme::Theme;
 use eframe::egui;
 use egui_twemoji::EmojiLabel;
+use std::ops::Range;
 
 /// Context for text rendering, determines which theme settings to apply
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -20,12 +21,13 @@ pub enum TextContext {
 ///
 /// This is the single source of truth for text styling in the application.
 /// All text rendering (paragraphs, tables, headings) should use this function
-/// to ensure consistent bold/italic/emoji support.
+/// to ensure consistent bold/italic/strikethrough/emoji support.
 pub fn build_styled_text(
     text: &str,
     bold: bool,
     italic: bool,
     code: bool,
+    strikethrough: bool,
     context: TextContext,
     theme: &Theme,
 ) -> egui::RichText {
@@ -77,31 +79,55 @@ pub fn build_styled_text(
             .color(theme.colors.inline_code);
     }
 
+    if strikethrough {
+        rich = rich.strikethrough();
+    }
+
     rich
 }
 
-/// Render styled text with emoji support
+/// Apply link styling (link color, underline) on top of build_styled_text()
+pub fn style_link(rich: egui::RichText, theme: &Theme) -> egui::RichText {
+    rich.underline().color(theme.colors.link)
+}
+
+/// Apply footnote reference styling (smaller, raised) on top of style_link()
+pub fn style_superscript(rich: egui::RichText, theme: &Theme) -> egui::RichText {
+    rich.size(theme.typography.body_size * 0.7).raised()
+}
+
+/// Byte ranges of emoji sequences in text (pictographs with their joiners, variation
+/// selectors and skin tone modifiers)
+pub fn emoji_runs(text: &str) -> Vec<Range<usize>> {
+    let is_emoji =
+        |ch: char| matches!(ch as u32, 0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B50 | 0x2B55);
+    let joins = |ch: char| matches!(ch, '\u{200D}' | '\u{FE0F}' | '\u{20E3}');
+
+    let mut runs: Vec<Range<usize>> = Vec::new();
+    for (idx, ch) in text.char_indices() {
+        let end = idx + ch.len_utf8();
+        match runs.last_mut() {
+            Some(run) if run.end == idx && (is_emoji(ch) || joins(ch)) => run.end = end,
+            _ if is_emoji(ch) => runs.push(idx..end),
+            _ => {}
+        }
+    }
+    runs
+}
+
+/// Draw emoji as colored images (EmojiLabel) into a rect of already laid-out text
 ///
-/// This wraps build_styled_text() and uses EmojiLabel to render colored emojis.
-/// Use this instead of ui.label(build_styled_text(...)) for automatic emoji support.
-pub fn render_styled_text(
-    ui: &mut egui::Ui,
-    text: &str,
-    bold: bool,
-    italic: bool,
-    code: bool,
-    context: TextContext,
-    theme: &Theme,
-    _id_source: impl std::hash::Hash,
-) -> egui::Response {
-    let styled_text = build_styled_text(text, bold, italic, code, context, theme);
-
-    // Use EmojiLabel which automatically renders emojis as colored images
-    // For non-emoji text, it behaves like a regular label
-    // Important: Keep auto_inline enabled so emojis flow inline with text
-    // Without this, EmojiLabel creates its own layout which breaks horizontal flow
-    // Note: Widget ID collisions are handled by the caller using ui.interact() with a unique ID
-    EmojiLabel::new(styled_text).auto_inline(true).show(ui)
+/// The rect is usually the emoji's glyphs in a galley that drew them transparent; the
+/// child UI doesn't take up space in `ui`.
+pub fn paint_emoji(ui: &mut egui::Ui, rect: egui::Rect, emoji: &str, size: f32) {
+    let mut child = ui.new_child(
+        egui::UiBuilder::new()
+            .max_rect(rect)
+            .layout(egui::Layout::left_to_right(egui::Align::Center)),
+    );
+    EmojiLabel::new(egui::RichText::new(emoji).size(size))
+        .auto_inline(true)
+        .show(&mut child);
 }
 
 #[cfg(test)]
@@ -111,7 +137,15 @@ mod tests {
     #[test]
     fn test_build_styled_text_body() {
         let theme = Theme::default_theme();
-        let rich = build_styled_text("Hello", false, false, false, TextContext::Body, &theme);
+        let rich = build_styled_text(
+            "Hello",
+            false,
+            false,
+            false,
+            false,
+            TextContext::Body,
+            &theme,
+        );
         // Can't easily test RichText internals, but this ensures it compiles and runs
         assert_eq!(rich.text(), "Hello");
     }
@@ -124,6 +158,7 @@ mod tests {
             true,
             false,
             false,
+            false,
             TextContext::Heading(1),
             &theme,
         );
@@ -138,6 +173,7 @@ mod tests {
             false,
             false,
             false,
+            false,
             TextContext::TableCell { is_header: true },
             &theme,
         );
@@ -147,7 +183,22 @@ mod tests {
     #[test]
     fn test_build_styled_text_inline_code() {
         let theme = Theme::default_theme();
-        let rich = build_styled_text("code", false, false, true, TextContext::Body, &theme);
+        let rich = build_styled_text("code", false, false, true, false, TextContext::Body, &theme);
         assert_eq!(rich.text(), "code");
     }
+
+    #[test]
+    fn test_build_styled_text_strikethrough() {
+        let theme = Theme::default_theme();
+        let rich = build_styled_text("old", true, false, false, true, TextContext::Body, &theme);
+        assert_eq!(rich.text(), "old");
+    }
+
+    #[test]
+    fn test_emoji_runs() {
+        let text = "ok 👍🏽 then ❤\u{FE0F} and 👨\u{200D}👩 end";
+        let runs: Vec<&str> = emoji_runs(text).into_iter().map(|r| &text[r]).collect();
+        assert_eq!(runs, vec!["👍🏽", "❤\u{FE0F}", "👨\u{200D}👩"]);
+        assert!(emoji_runs("plain — text").is_empty());
+    }
 }
@@ -0,0 +1,90 @@
+/// Table of contents side panel: heading hierarchy, click-to-scroll, current section
+use crate::models::{Comment, TocEntry};
+use crate::theme::Theme;
+use eframe::egui;
+
+/// Indentation per heading level below the document's top level
+const INDENT_PER_LEVEL: f32 = 12.0;
+
+/// Render the collapsible table of contents on the left
+///
+/// Clicking a heading requests a scroll to it via `scroll_to_heading`; the heading of
+/// `current_line` is highlighted and each entry shows its section's queued comments.
+pub fn render_toc_panel(
+    ctx: &egui::Context,
+    entries: &[TocEntry],
+    comments: &[Comment],
+    current_line: Option<usize>,
+    open: &mut bool,
+    scroll_to_heading: &mut Option<usize>,
+    theme: &Theme,
+) {
+    if entries.is_empty() {
+        return;
+    }
+
+    if !*open {
+        egui::SidePanel::left("toc_collapsed")
+            .resizable(false)
+            .exact_width(24.0)
+            .show(ctx, |ui| {
+                if ui
+                    .small_button("›")
+                    .on_hover_text("Show contents")
+                    .clicked()
+                {
+                    *open = true;
+                }
+            });
+        return;
+    }
+
+    let top_level = entries.iter().map(|e| e.level).min().unwrap_or(1);
+
+    egui::SidePanel::left("toc")
+        .resizable(true)
+        .default_width(220.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.strong("Contents");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui
+                        .small_button("‹")
+                        .on_hover_text("Hide contents")
+                        .clicked()
+                    {
+                        *open = false;
+                    }
+                });
+            });
+            ui.separator();
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for entry in entries {
+                    ui.horizontal(|ui| {
+                        ui.add_space((entry.level - top_level) as f32 * INDENT_PER_LEVEL);
+
+                        let is_current = current_line == Some(entry.line);
+                        let mut title = egui::RichText::new(&entry.title);
+                        if entry.level == top_level {
+                            title = title.strong();
+                        }
+                        let response = ui.selectable_label(is_current, title);
+                        if response.clicked() {
+                            *scroll_to_heading = Some(entry.line);
+                        }
+
+                        let count = entry.comment_count(comments);
+                        if count > 0 {
+                            ui.label(
+                                egui::RichText::new(format!("({})", count))
+                                    .small()
+                                    .color(theme.colors.selection_highlight),
+                            )
+                            .on_hover_text(format!("{} queued comment(s) in this section", count));
+                        }
+                    });
+                }
+            });
+        });
+}
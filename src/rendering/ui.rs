@@ -1,38 +1,136 @@
+use crate::diff::ChangeMarker;
 use crate::image_manager::ImageManager;
-use crate::models::{LayoutMap, Selection, TextChunk};
+use crate::models::{Footnote, LayoutMap, ReviewRound, Selection, TextChunk};
+use crate::rendering::changes::draw_change_markers;
 use crate::rendering::chunk;
+use crate::rendering::chunk_renderer::RenderContext;
+use crate::rendering::history::draw_history_markers;
 use crate::rendering::inline_batcher::InlineTextBatcher;
-use crate::rendering::selection_manager::SelectionManager;
+use crate::rendering::list::render_list_marker;
+use crate::rendering::quote::render_quote_row;
+use crate::rendering::selection_manager::{
+    draw_line_cursor, draw_search_highlights, SelectionManager,
+};
 use crate::rendering::viewport::ViewportCuller;
+use crate::search::SearchState;
 use crate::syntax::SyntaxHighlighter;
 use crate::theme::Theme;
 use eframe::egui;
 
+/// Per-frame state of the document being rendered
+pub struct ContentView<'a> {
+    pub chunks: &'a mut [TextChunk],
+    /// Source the chunks were parsed from
+    pub source: &'a str,
+    pub selection: &'a mut Selection,
+    pub image_manager: &'a mut ImageManager,
+    pub layout_map: &'a mut LayoutMap,
+    /// Prior review rounds (visible ones get margin markers)
+    pub history: &'a [ReviewRound],
+    /// Changes since the base revision (gutter markers)
+    pub changes: &'a [ChangeMarker],
+    /// Keyboard cursor line
+    pub cursor: Option<usize>,
+    pub search: &'a SearchState,
+}
+
 /// Render the main UI with markdown content (with stable lazy loading)
+///
+/// Returns the destination of a link clicked this frame.
 pub fn render_content(
     ui: &mut egui::Ui,
     ctx: &egui::Context,
-    chunks: &mut [TextChunk],
-    selection: &mut Selection,
-    image_manager: &mut ImageManager,
+    view: ContentView,
     highlighter: &SyntaxHighlighter,
     theme: &Theme,
-    layout_map: &mut LayoutMap,
-) {
+) -> Option<String> {
+    let ContentView {
+        chunks,
+        source,
+        selection,
+        image_manager,
+        layout_map,
+        history,
+        changes,
+        cursor,
+        search,
+    } = view;
+
     // Handle drag release
     {
-        let mut selection_manager = SelectionManager::new(selection, layout_map);
+        let mut selection_manager = SelectionManager::new(selection, layout_map, source);
         selection_manager.handle_drag_release(ui);
     }
 
-    // Only build layout map if we have an active selection or are dragging
-    let need_layout_map = selection.is_active() || selection.is_dragging;
+    // Only build layout map if we have an active selection, are dragging,
+    // or have a keyboard cursor / search matches / prior review / change markers to place
+    let need_layout_map = selection.is_active()
+        || selection.is_dragging
+        || cursor.is_some()
+        || (search.open && !search.matches.is_empty())
+        || !changes.is_empty()
+        || history
+            .iter()
+            .any(|round| round.visible && !round.comments.is_empty());
 
     // Create viewport culler for this frame
     let mut culler = ViewportCuller::new(ui);
 
     // Track if any chunk was clicked (to distinguish click from drag)
     let mut any_chunk_clicked = false;
+    let mut clicked_link = None;
+
+    // Render one row: a batch of inline chunks, or a single block chunk
+    // Returns the index of the first chunk after the row
+    let mut render_row = |ui: &mut egui::Ui,
+                          chunks: &mut [TextChunk],
+                          layout_map: &mut LayoutMap,
+                          culler: &ViewportCuller,
+                          idx: usize|
+     -> usize {
+        // Find the batch of consecutive inline text chunks; non-inline chunks (images,
+        // code blocks, tables, headings) render alone
+        let batch = InlineTextBatcher::is_inline_chunk(&chunks[idx])
+            .then(|| InlineTextBatcher::find_inline_batch(chunks, idx))
+            .flatten();
+        let (batch_start, batch_end) = batch.unwrap_or((idx, idx + 1));
+
+        let mut render_batch = |ui: &mut egui::Ui| {
+            for local_idx in batch_start..batch_end {
+                let was_clicked = chunk::render_chunk(
+                    &mut RenderContext {
+                        ui,
+                        ctx,
+                        chunk: &mut chunks[local_idx],
+                        idx: local_idx,
+                        source,
+                        selection,
+                        image_manager,
+                        highlighter,
+                        theme,
+                        layout_map,
+                        need_layout_map,
+                    },
+                    culler,
+                );
+
+                if was_clicked {
+                    any_chunk_clicked = true;
+                    if clicked_link.is_none() {
+                        clicked_link = chunks[local_idx].link.clone();
+                    }
+                }
+            }
+        };
+
+        if batch.is_some() {
+            // Batch render consecutive inline text chunks in horizontal_wrapped layout
+            ui.horizontal_wrapped(render_batch);
+        } else {
+            render_batch(ui);
+        }
+        batch_end
+    };
 
     let mut idx = 0;
     while idx < chunks.len() {
@@ -41,66 +139,59 @@ pub fn render_content(
         // Check if we can skip rendering early (past viewport with cached height)
         if !culler.should_render(start_pos, 0.0) && chunks[idx].cached_height.is_some() {
             // Fast path: skip this chunk entirely
-            culler.render_offscreen(ui, &mut chunks[idx], 0.0, theme);
+            let before_y = ui.cursor().min.y;
+            let height = culler.render_offscreen(ui, &mut chunks[idx], 0.0, theme);
+
+            // Still record position so selections and jumps can reach offscreen lines
+            if need_layout_map
+                || chunks[idx].heading_level.is_some()
+                || chunks[idx].footnote.is_some()
+            {
+                layout_map.record_chunk(
+                    chunks[idx].line_start,
+                    chunks[idx].line_end,
+                    before_y,
+                    before_y + height,
+                );
+            }
             idx += 1;
             continue;
         }
 
-        // Check if this is an inline text chunk
-        if InlineTextBatcher::is_inline_chunk(&chunks[idx]) {
-            // Find the batch of consecutive inline chunks
-            if let Some((batch_start, batch_end)) =
-                InlineTextBatcher::find_inline_batch(chunks, idx)
-            {
-                // Batch render consecutive inline text chunks in horizontal_wrapped layout
-                ui.horizontal_wrapped(|ui| {
-                    for local_idx in batch_start..batch_end {
-                        let was_clicked = chunk::render_chunk(
-                            ui,
-                            ctx,
-                            &mut chunks[local_idx],
-                            local_idx,
-                            selection,
-                            image_manager,
-                            highlighter,
-                            theme,
-                            layout_map,
-                            need_layout_map,
-                            &culler,
-                        );
-
-                        if was_clicked {
-                            any_chunk_clicked = true;
-                        }
-                    }
-                });
+        // Footnote definitions follow the document, set off by a rule
+        let is_definition =
+            |chunk: &TextChunk| matches!(chunk.footnote, Some(Footnote::Definition(_)));
+        if is_definition(&chunks[idx]) && (idx == 0 || !is_definition(&chunks[idx - 1])) {
+            ui.add_space(theme.spacing.paragraph);
+            ui.separator();
+        }
 
-                // Move index past the batched chunks
-                idx = batch_end;
-                continue;
+        let list_depth = chunks[idx].list_depth;
+        let item = chunks[idx].list_item.clone();
+        let quote = chunks[idx].quote.clone();
+
+        let mut render_nested_row = |ui: &mut egui::Ui| {
+            if list_depth == 0 {
+                idx = render_row(ui, chunks, layout_map, &culler, idx);
+                return;
             }
-        }
 
-        // Render non-inline chunks normally (images, code blocks, tables, headings)
-        let was_clicked = chunk::render_chunk(
-            ui,
-            ctx,
-            &mut chunks[idx],
-            idx,
-            selection,
-            image_manager,
-            highlighter,
-            theme,
-            layout_map,
-            need_layout_map,
-            &culler,
-        );
-
-        if was_clicked {
-            any_chunk_clicked = true;
-        }
+            // List rows: indent by nesting depth, marker column, then the content with a
+            // hanging indent (wrapped lines stay aligned with the item text)
+            ui.horizontal_top(|ui| {
+                ui.add_space((list_depth - 1) as f32 * theme.spacing.list_indent);
+                render_list_marker(ui, item.as_ref(), list_depth, theme);
+                ui.vertical(|ui| {
+                    idx = render_row(ui, chunks, layout_map, &culler, idx);
+                });
+            });
+        };
 
-        idx += 1;
+        // Quoted rows get their border and background around any list layout
+        match &quote {
+            Some(quote) => render_quote_row(ui, quote, theme, render_nested_row),
+            None => render_nested_row(ui),
+        }
     }
 
     // Clear selection if clicked without dragging
@@ -111,13 +202,30 @@ pub fn render_content(
     // Update selection based on hover (second pass after all chunks rendered)
     // This ensures bidirectional drag works (both up and down)
     {
-        let mut selection_manager = SelectionManager::new(selection, layout_map);
+        let mut selection_manager = SelectionManager::new(selection, layout_map, source);
         selection_manager.update_from_hover(ui);
     }
 
     // Draw selection bar using layout map
     {
-        let selection_manager = SelectionManager::new(selection, layout_map);
+        let selection_manager = SelectionManager::new(selection, layout_map, source);
         selection_manager.draw_selection_bar(ui, theme);
+        selection_manager.draw_text_highlight(ui, theme);
     }
+
+    if let Some(line) = cursor {
+        draw_line_cursor(ui, line, layout_map, theme);
+    }
+
+    if search.open {
+        draw_search_highlights(ui, &search.matches, search.current, layout_map, theme);
+    }
+
+    // Draw markers for prior review rounds
+    draw_history_markers(ui, history, layout_map, theme);
+
+    // Draw gutter markers for changes since the base revision
+    draw_change_markers(ui, chunks, changes, layout_map, theme);
+
+    clicked_link
 }
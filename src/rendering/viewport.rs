@@ -105,12 +105,11 @@ mod tests {
             text: "test".to_string(),
             byte_range: 0..4,
             line_start: 1,
-            col_start: 1,
             line_end: 1,
-            col_end: 4,
             bold: false,
             italic: false,
             code: false,
+            strikethrough: false,
             heading_level: None,
             newline_after: false,
             image_path: None,
@@ -119,6 +118,12 @@ mod tests {
             image_height: None,
             code_block_lang: None,
             table: None,
+            link: None,
+            list_depth: 0,
+            list_item: None,
+            quote: None,
+            footnote: None,
+            math: None,
             cached_height: None,
         }
     }
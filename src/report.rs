@@ -0,0 +1,328 @@
+/// Machine-readable review outcome emitted on stdout with --json
+use crate::anchoring::AnchorStatus;
+use crate::diff::HunkContext;
+use crate::git::BaseRevision;
+use crate::models::{Document, FrontMatter, ReviewRound, Verdict};
+use crate::storage::{Position, SelectionRange};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// All documents approved (LGTM)
+pub const EXIT_APPROVED: u8 = 0;
+/// Window closed with undecided documents
+pub const EXIT_CANCELLED: u8 = 2;
+/// Review comments submitted for at least one document
+pub const EXIT_CHANGES_REQUESTED: u8 = 3;
+/// At least one document rejected via Nope
+pub const EXIT_REJECTED: u8 = 4;
+
+/// Aggregate exit code across all documents (most severe outcome wins)
+///
+/// Severity: rejected > changes requested > cancelled > approved.
+/// Exit code 1 is reserved for errors (returned by `main` via anyhow).
+pub fn exit_code(documents: &[Document]) -> u8 {
+    let has = |verdict: Verdict| documents.iter().any(|d| d.verdict == verdict);
+
+    if has(Verdict::Nope) {
+        EXIT_REJECTED
+    } else if has(Verdict::Reviewed) {
+        EXIT_CHANGES_REQUESTED
+    } else if has(Verdict::Unreviewed) {
+        EXIT_CANCELLED
+    } else {
+        EXIT_APPROVED
+    }
+}
+
+/// Review outcome for all documents in the session
+#[derive(Debug, Serialize)]
+pub struct ReviewReport {
+    pub documents: Vec<DocumentReport>,
+}
+
+/// Review outcome for a single document
+#[derive(Debug, Serialize)]
+pub struct DocumentReport {
+    pub filename: String,
+    pub path: PathBuf,
+    pub verdict: Verdict,
+    /// Review file written by the storage backend (None if nothing was written)
+    pub review_file: Option<PathBuf>,
+    /// Git revision the document was reviewed against (`--since` mode only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<BaseRevision>,
+    /// Metadata from the document's front matter block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub front_matter: Option<FrontMatter>,
+    pub comments: Vec<CommentReport>,
+    /// Previously stored review rounds, re-anchored in the current document
+    pub history: Vec<RoundReport>,
+}
+
+/// A single comment with its source anchor
+#[derive(Debug, Serialize)]
+pub struct CommentReport {
+    pub selection: SelectionRange,
+    /// The commented text was removed by a live reload (text is the original excerpt)
+    pub outdated: bool,
+    /// Diff hunk the comment targets (`--diff` mode only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hunk: Option<HunkContext>,
+    pub text: String,
+    pub comment: String,
+}
+
+/// A previously stored review round
+#[derive(Debug, Serialize)]
+pub struct RoundReport {
+    pub timestamp: String,
+    pub verdict: Option<Verdict>,
+    pub comments: Vec<StoredCommentReport>,
+}
+
+/// A stored comment with its original and current anchors
+#[derive(Debug, Serialize)]
+pub struct StoredCommentReport {
+    /// Anchor in the current document (original range if outdated)
+    pub selection: SelectionRange,
+    /// Anchor as stored when the review was written
+    pub original_selection: SelectionRange,
+    pub anchor: AnchorStatus,
+    /// The commented text no longer exists in the document
+    pub outdated: bool,
+    pub text: String,
+    pub comment: String,
+}
+
+/// Build a selection range from (line, col) pairs
+fn selection_range(
+    line_start: usize,
+    col_start: usize,
+    line_end: usize,
+    col_end: usize,
+) -> SelectionRange {
+    SelectionRange {
+        start: Position {
+            line: line_start,
+            col: col_start,
+        },
+        end: Position {
+            line: line_end,
+            col: col_end,
+        },
+    }
+}
+
+impl ReviewReport {
+    /// Build the report from the final document states
+    pub fn from_documents(documents: &[Document]) -> Self {
+        Self {
+            documents: documents
+                .iter()
+                .map(DocumentReport::from_document)
+                .collect(),
+        }
+    }
+
+    /// Serialize to pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("Failed to serialize review report")
+    }
+}
+
+impl DocumentReport {
+    fn from_document(doc: &Document) -> Self {
+        let comments = doc
+            .comment_data()
+            .into_iter()
+            .zip(&doc.comments)
+            .zip(doc.comment_hunks())
+            .map(
+                |(((text, comment, line_start, col_start, line_end, col_end), queued), hunk)| {
+                    CommentReport {
+                        selection: selection_range(line_start, col_start, line_end, col_end),
+                        outdated: queued.is_outdated(),
+                        hunk,
+                        text,
+                        comment,
+                    }
+                },
+            )
+            .collect();
+
+        Self {
+            filename: doc.filename.clone(),
+            path: doc.file_path.clone(),
+            verdict: doc.verdict,
+            review_file: doc.review_path.clone(),
+            base: doc.revision.as_ref().map(|revision| revision.base.clone()),
+            front_matter: doc.front_matter.clone(),
+            comments,
+            history: doc.history.iter().map(RoundReport::from_round).collect(),
+        }
+    }
+}
+
+impl RoundReport {
+    fn from_round(round: &ReviewRound) -> Self {
+        Self {
+            timestamp: round.timestamp.clone(),
+            verdict: round.verdict,
+            comments: round
+                .comments
+                .iter()
+                .map(|stored| {
+                    let anchor = &stored.anchor;
+                    StoredCommentReport {
+                        selection: selection_range(
+                            anchor.line_start,
+                            anchor.col_start,
+                            anchor.line_end,
+                            anchor.col_end,
+                        ),
+                        original_selection: stored.comment.selection.clone(),
+                        anchor: anchor.status,
+                        outdated: anchor.is_outdated(),
+                        text: stored.comment.text.clone(),
+                        comment: stored.comment.comment.clone(),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Comment;
+    use crate::storage::ProjectType;
+    use std::path::Path;
+
+    fn create_test_document(source: &str) -> Document {
+        Document::new(
+            "test.md".to_string(),
+            source.to_string(),
+            Path::new(".").to_path_buf(),
+            Path::new("/tmp/test.md").to_path_buf(),
+            Path::new(".").to_path_buf(),
+            None,
+            ProjectType::Standalone,
+        )
+    }
+
+    #[test]
+    fn test_unreviewed_document_report() {
+        let doc = create_test_document("# Test");
+        let report = ReviewReport::from_documents(&[doc]);
+
+        let json = report.to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        let entry = &value["documents"][0];
+        assert_eq!(entry["filename"], "test.md");
+        assert_eq!(entry["verdict"], "unreviewed");
+        assert!(entry["review_file"].is_null());
+        assert_eq!(entry["comments"].as_array().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_reviewed_document_includes_comments() {
+        let mut doc = create_test_document("line one\nline two\nline three");
+        doc.comments
+            .push(Comment::new("Clarify".to_string(), 2, 0, 3, 0));
+        doc.record_verdict(Verdict::Reviewed, Some(Path::new("test.review.1").into()));
+
+        let report = ReviewReport::from_documents(&[doc]);
+        let value: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+
+        let entry = &value["documents"][0];
+        assert_eq!(entry["verdict"], "reviewed");
+        assert_eq!(entry["review_file"], "test.review.1");
+
+        let comment = &entry["comments"][0];
+        assert_eq!(comment["selection"]["start"]["line"], 2);
+        assert_eq!(comment["selection"]["end"]["line"], 3);
+        assert_eq!(comment["text"], "line two\nline three");
+        assert_eq!(comment["comment"], "Clarify");
+    }
+
+    #[test]
+    fn test_history_reports_outdated_comments() {
+        use crate::storage::{ReviewComment, ReviewEntry};
+
+        let source = "# Spec\n\nNew wording entirely.\n\nKept paragraph.";
+        let mut doc = create_test_document(source);
+
+        let comment = |text: &str, line: usize| {
+            ReviewComment::new(
+                "test.md".to_string(),
+                None,
+                text.to_string(),
+                "Check this".to_string(),
+                line,
+                0,
+                line,
+                0,
+            )
+        };
+        let entry = ReviewEntry::new(
+            vec![
+                comment("Kept paragraph.", 3).into(),
+                comment("Removed sentence about caching.", 5).into(),
+            ],
+            None,
+            Verdict::Reviewed,
+            None,
+        );
+        doc.history = vec![ReviewRound::from_entry(entry, source)];
+
+        let report = ReviewReport::from_documents(&[doc]);
+        let value: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+
+        let comments = &value["documents"][0]["history"][0]["comments"];
+        assert_eq!(comments[0]["anchor"], "moved");
+        assert_eq!(comments[0]["selection"]["start"]["line"], 5);
+        assert_eq!(comments[0]["original_selection"]["start"]["line"], 3);
+        assert_eq!(comments[0]["outdated"], false);
+
+        assert_eq!(comments[1]["anchor"], "outdated");
+        assert_eq!(comments[1]["outdated"], true);
+    }
+
+    fn documents_with_verdicts(verdicts: &[Verdict]) -> Vec<Document> {
+        verdicts
+            .iter()
+            .map(|&verdict| {
+                let mut doc = create_test_document("# Test");
+                doc.verdict = verdict;
+                doc
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_exit_code_all_approved() {
+        let docs = documents_with_verdicts(&[Verdict::Lgtm, Verdict::Lgtm]);
+        assert_eq!(exit_code(&docs), EXIT_APPROVED);
+    }
+
+    #[test]
+    fn test_exit_code_cancelled_with_undecided() {
+        let docs = documents_with_verdicts(&[Verdict::Lgtm, Verdict::Unreviewed]);
+        assert_eq!(exit_code(&docs), EXIT_CANCELLED);
+    }
+
+    #[test]
+    fn test_exit_code_changes_requested() {
+        let docs = documents_with_verdicts(&[Verdict::Reviewed, Verdict::Unreviewed]);
+        assert_eq!(exit_code(&docs), EXIT_CHANGES_REQUESTED);
+    }
+
+    #[test]
+    fn test_exit_code_rejection_wins() {
+        let docs = documents_with_verdicts(&[Verdict::Reviewed, Verdict::Nope, Verdict::Lgtm]);
+        assert_eq!(exit_code(&docs), EXIT_REJECTED);
+    }
+}
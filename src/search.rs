@@ -0,0 +1,485 @@
+/// In-document search (Ctrl+F): plain or regex queries over the source and rendered text
+///
+/// Matches are source byte ranges, so they can be highlighted in rendered text spans
+/// and turned into a column-precise selection for commenting.
+use crate::models::{source_byte_at, Document, TextChunk};
+use crate::parsing::{parse_markdown, LineOffsets};
+use anyhow::{Context, Result};
+use regex::{Regex, RegexBuilder};
+use std::ops::Range;
+
+/// Query text and matching options
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    pub text: String,
+    pub case_sensitive: bool,
+    /// Interpret `text` as a regular expression
+    pub regex: bool,
+}
+
+impl SearchQuery {
+    fn compile(&self) -> Result<Regex> {
+        let pattern = if self.regex {
+            self.text.clone()
+        } else {
+            regex::escape(&self.text)
+        };
+        RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .build()
+            .with_context(|| format!("Invalid regex {:?}", self.text))
+    }
+}
+
+/// A match in the document source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch {
+    pub byte_range: Range<usize>,
+    /// Source lines of the match (1-indexed)
+    pub line_start: usize,
+    pub line_end: usize,
+}
+
+impl SearchMatch {
+    fn new(source: &str, offsets: &LineOffsets, byte_range: Range<usize>) -> Self {
+        let (line_start, _) = offsets.byte_to_line_col(source, byte_range.start);
+        let (line_end, _) = offsets.byte_to_line_col(source, byte_range.end);
+        Self {
+            byte_range,
+            line_start,
+            line_end,
+        }
+    }
+
+    fn overlaps(&self, other: &Range<usize>) -> bool {
+        self.byte_range.start < other.end && other.start < self.byte_range.end
+    }
+}
+
+/// Find all matches, ordered by position
+///
+/// The raw source is scanned first. Chunk text (paragraphs, code blocks, table cells
+/// as rendered) is scanned too, so text that reads differently once rendered (entities,
+/// escapes) is still found; those hits map back to their chunk's source span.
+pub fn find_matches(
+    source: &str,
+    chunks: &[TextChunk],
+    query: &SearchQuery,
+) -> Result<Vec<SearchMatch>> {
+    if query.text.is_empty() {
+        return Ok(Vec::new());
+    }
+    let regex = query.compile()?;
+    let offsets = LineOffsets::new(source);
+
+    let mut matches: Vec<SearchMatch> = regex
+        .find_iter(source)
+        .filter(|m| !m.is_empty())
+        .map(|m| SearchMatch::new(source, &offsets, m.range()))
+        .collect();
+
+    let add = |range: Range<usize>, matches: &mut Vec<SearchMatch>| {
+        if !range.is_empty() && !matches.iter().any(|m| m.overlaps(&range)) {
+            matches.push(SearchMatch::new(source, &offsets, range));
+        }
+    };
+
+    // Rendered text: inline chunks are split at entities and escapes, so search
+    // their concatenation and map both ends back through the containing chunk
+    let rendered = RenderedText::new(source, chunks);
+    for hit in regex.find_iter(&rendered.text).filter(|m| !m.is_empty()) {
+        let range = rendered.source_byte(hit.start(), false)..rendered.source_byte(hit.end(), true);
+        add(range, &mut matches);
+    }
+
+    // Table cells don't keep their own spans: a hit selects the whole table
+    for chunk in chunks {
+        let Some(table) = &chunk.table else { continue };
+        let cells = table.header.iter().chain(table.rows.iter().flatten());
+        for cell in cells {
+            if regex.find_iter(cell).any(|m| !m.is_empty()) {
+                add(chunk.byte_range.clone(), &mut matches);
+            }
+        }
+    }
+
+    matches.sort_by_key(|m| (m.byte_range.start, m.byte_range.end));
+    Ok(matches)
+}
+
+/// Concatenated text of rendered (non-table) chunks
+struct RenderedText<'a> {
+    source: &'a str,
+    text: String,
+    /// (offset in `text`, chunk), in order
+    segments: Vec<(usize, &'a TextChunk)>,
+}
+
+impl<'a> RenderedText<'a> {
+    fn new(source: &'a str, chunks: &'a [TextChunk]) -> Self {
+        let mut text = String::new();
+        let mut segments = Vec::new();
+        for chunk in chunks.iter().filter(|c| c.table.is_none()) {
+            segments.push((text.len(), chunk));
+            text.push_str(&chunk.text);
+            if chunk.newline_after {
+                text.push('\n');
+            }
+        }
+        Self {
+            source,
+            text,
+            segments,
+        }
+    }
+
+    /// Source byte for an offset in `text` (`is_end`: offset is an exclusive end,
+    /// so a chunk boundary belongs to the chunk before it)
+    fn source_byte(&self, offset: usize, is_end: bool) -> usize {
+        let idx = self
+            .segments
+            .partition_point(|&(start, _)| start < offset || (!is_end && start == offset))
+            .saturating_sub(1);
+        let (start, chunk) = self.segments[idx];
+        let local = (offset - start).min(chunk.text.len());
+        let char_index = chunk.text[..local].chars().count();
+        source_byte_at(self.source, &chunk.text, &chunk.byte_range, char_index)
+    }
+}
+
+/// Per-document search bar state
+#[derive(Debug, Default)]
+pub struct SearchState {
+    /// Search bar is shown
+    pub open: bool,
+    pub query: SearchQuery,
+    pub matches: Vec<SearchMatch>,
+    /// Index of the focused match
+    pub current: Option<usize>,
+    /// Invalid regex message
+    pub error: Option<String>,
+    /// Query the matches were computed for (None = recompute)
+    searched: Option<SearchQuery>,
+    /// Set when the focused match changes; the view scrolls to it and clears the flag
+    pub reveal: bool,
+}
+
+impl SearchState {
+    /// Recompute matches if the query changed (or after `invalidate`)
+    /// Keeps the focus on the first match at or after the previously focused one.
+    pub fn update(&mut self, source: &str, chunks: &[TextChunk]) {
+        if self.searched.as_ref() == Some(&self.query) {
+            return;
+        }
+        let previous_start = self.current_match().map_or(0, |m| m.byte_range.start);
+
+        match find_matches(source, chunks, &self.query) {
+            Ok(matches) => {
+                self.matches = matches;
+                self.error = None;
+            }
+            Err(e) => {
+                self.matches.clear();
+                self.error = Some(format!("{:#}", e));
+            }
+        }
+        self.searched = Some(self.query.clone());
+
+        self.current = if self.matches.is_empty() {
+            None
+        } else {
+            Some(
+                self.matches
+                    .iter()
+                    .position(|m| m.byte_range.start >= previous_start)
+                    .unwrap_or(0),
+            )
+        };
+        self.reveal = self.current.is_some();
+    }
+
+    /// Force a re-search on the next `update` (source changed)
+    pub fn invalidate(&mut self) {
+        self.searched = None;
+    }
+
+    pub fn current_match(&self) -> Option<&SearchMatch> {
+        self.current.and_then(|i| self.matches.get(i))
+    }
+
+    /// Focus the next (`forward`) or previous match, wrapping around
+    pub fn step(&mut self, forward: bool) {
+        let count = self.matches.len();
+        if count == 0 {
+            return;
+        }
+        self.current = Some(match (self.current, forward) {
+            (Some(i), true) => (i + 1) % count,
+            (Some(i), false) => (i + count - 1) % count,
+            (None, true) => 0,
+            (None, false) => count - 1,
+        });
+        self.reveal = true;
+    }
+
+    /// Line of the focused match to scroll into view this frame (clears the flag)
+    pub fn take_reveal(&mut self) -> Option<usize> {
+        if std::mem::take(&mut self.reveal) {
+            self.current_match().map(|m| m.line_start)
+        } else {
+            None
+        }
+    }
+}
+
+/// A match in another document, with its source line for display
+#[derive(Debug, Clone)]
+pub struct DocumentHit {
+    pub hit: SearchMatch,
+    /// Trimmed source line containing the match start
+    pub line_text: String,
+}
+
+/// Matches in one document
+#[derive(Debug, Clone)]
+pub struct FileResults {
+    /// Index into the open documents
+    pub doc_index: usize,
+    pub filename: String,
+    pub hits: Vec<DocumentHit>,
+}
+
+/// Search every open (not yet decided) document; documents that were never shown
+/// are parsed first so their rendered text is searched too
+pub fn search_documents(
+    documents: &mut [Document],
+    query: &SearchQuery,
+) -> Result<Vec<FileResults>> {
+    let mut results = Vec::new();
+
+    for (doc_index, doc) in documents.iter_mut().enumerate() {
+        if doc.approved {
+            continue;
+        }
+        if doc.chunks.is_none() {
+            doc.chunks = Some(parse_markdown(
+                &doc.source,
+                &doc.base_path,
+                &mut doc.image_manager,
+            ));
+        }
+
+        let matches = find_matches(
+            &doc.source,
+            doc.chunks.as_deref().unwrap_or_default(),
+            query,
+        )?;
+        if matches.is_empty() {
+            continue;
+        }
+        let lines: Vec<&str> = doc.source.lines().collect();
+        let hits = matches
+            .into_iter()
+            .map(|hit| DocumentHit {
+                line_text: lines
+                    .get(hit.line_start - 1)
+                    .map_or(String::new(), |line| line.trim().to_string()),
+                hit,
+            })
+            .collect();
+        results.push(FileResults {
+            doc_index,
+            filename: doc.filename.clone(),
+            hits,
+        });
+    }
+
+    Ok(results)
+}
+
+/// Cross-document search panel state
+#[derive(Debug, Default)]
+pub struct GlobalSearch {
+    /// Panel is shown
+    pub open: bool,
+    pub query: SearchQuery,
+    pub results: Vec<FileResults>,
+    /// Invalid regex message
+    pub error: Option<String>,
+    /// Query the results were computed for (None = recompute)
+    searched: Option<SearchQuery>,
+}
+
+impl GlobalSearch {
+    /// Re-run the search if the query changed (or after `invalidate`)
+    pub fn update(&mut self, documents: &mut [Document]) {
+        if self.searched.as_ref() == Some(&self.query) {
+            return;
+        }
+        match search_documents(documents, &self.query) {
+            Ok(results) => {
+                self.results = results;
+                self.error = None;
+            }
+            Err(e) => {
+                self.results.clear();
+                self.error = Some(format!("{:#}", e));
+            }
+        }
+        self.searched = Some(self.query.clone());
+    }
+
+    /// Force a re-search on the next `update` (a document changed or was decided)
+    pub fn invalidate(&mut self) {
+        self.searched = None;
+    }
+
+    pub fn hit_count(&self) -> usize {
+        self.results.iter().map(|file| file.hits.len()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image_manager::ImageManager;
+    use crate::parsing::parse_markdown;
+    use std::path::Path;
+
+    fn query(text: &str) -> SearchQuery {
+        SearchQuery {
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn parse(source: &str) -> Vec<TextChunk> {
+        let mut image_manager = ImageManager::new(Path::new("."));
+        parse_markdown(source, Path::new("."), &mut image_manager)
+    }
+
+    #[test]
+    fn test_plain_search_is_case_insensitive_by_default() {
+        let source = "# Title\n\nThe title line.\n";
+        let matches = find_matches(source, &[], &query("title")).unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(&source[matches[0].byte_range.clone()], "Title");
+        assert_eq!((matches[1].line_start, matches[1].line_end), (3, 3));
+
+        let case_sensitive = SearchQuery {
+            case_sensitive: true,
+            ..query("title")
+        };
+        assert_eq!(find_matches(source, &[], &case_sensitive).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_regex_mode() {
+        let source = "v1.2 and v10.0\n";
+        let plain = find_matches(source, &[], &query("v1.")).unwrap();
+        assert_eq!(plain.len(), 1);
+
+        let regex = SearchQuery {
+            regex: true,
+            ..query(r"v\d+\.\d")
+        };
+        assert_eq!(find_matches(source, &[], &regex).unwrap().len(), 2);
+
+        let invalid = SearchQuery {
+            regex: true,
+            ..query("(")
+        };
+        assert!(find_matches(source, &[], &invalid).is_err());
+    }
+
+    #[test]
+    fn test_rendered_text_matches_map_to_source() {
+        // "&amp;" only reads as "&" once rendered
+        let source = "Fish &amp; chips\n";
+        let chunks = parse(source);
+        let matches = find_matches(source, &chunks, &query("fish & chips")).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line_start, 1);
+    }
+
+    #[test]
+    fn test_code_blocks_and_table_cells() {
+        let source = "```rust\nlet total = 1;\n```\n\n| Name | Total |\n|------|-------|\n| a    | 2     |\n";
+        let chunks = parse(source);
+        let matches = find_matches(source, &chunks, &query("total")).unwrap();
+        let lines: Vec<_> = matches.iter().map(|m| m.line_start).collect();
+        assert_eq!(lines, vec![2, 5]);
+    }
+
+    #[test]
+    fn test_step_wraps_and_update_keeps_position() {
+        let source = "a\na\na\n";
+        let mut search = SearchState {
+            query: query("a"),
+            ..Default::default()
+        };
+        search.update(source, &[]);
+        assert_eq!(search.current, Some(0));
+        assert_eq!(search.take_reveal(), Some(1));
+
+        search.step(false);
+        assert_eq!(search.current_match().unwrap().line_start, 3);
+        search.step(true);
+        assert_eq!(search.current, Some(0));
+        search.step(true);
+
+        // Re-running the search stays on (or after) the focused match
+        search.invalidate();
+        search.update(source, &[]);
+        assert_eq!(search.current, Some(1));
+    }
+
+    fn document(dir: &tempfile::TempDir, name: &str, source: &str) -> Document {
+        let file_path = dir.path().join(name);
+        Document::new(
+            name.to_string(),
+            source.to_string(),
+            dir.path().to_path_buf(),
+            file_path,
+            dir.path().to_path_buf(),
+            None,
+            crate::storage::ProjectType::Standalone,
+        )
+    }
+
+    #[test]
+    fn test_search_documents_groups_by_file_and_parses_lazily() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut documents = vec![
+            document(&dir, "A.md", "# Alpha\n\nUses the cache.\n"),
+            document(&dir, "B.md", "# Beta\n\nNothing here.\n"),
+            document(&dir, "C.md", "Cache first.\n\n  The CACHE again.\n"),
+        ];
+
+        let results = search_documents(&mut documents, &query("cache")).unwrap();
+        let summary: Vec<_> = results
+            .iter()
+            .map(|file| {
+                let lines: Vec<_> = file.hits.iter().map(|h| h.hit.line_start).collect();
+                (file.doc_index, file.filename.as_str(), lines)
+            })
+            .collect();
+        assert_eq!(summary, vec![(0, "A.md", vec![3]), (2, "C.md", vec![1, 3])]);
+        assert_eq!(results[1].hits[1].line_text, "The CACHE again.");
+        assert!(documents.iter().all(|doc| doc.chunks.is_some()));
+    }
+
+    #[test]
+    fn test_search_documents_skips_decided_documents() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut documents = vec![document(&dir, "A.md", "cache\n")];
+        documents[0].approved = true;
+
+        let mut global = GlobalSearch {
+            query: query("cache"),
+            ..Default::default()
+        };
+        global.update(&mut documents);
+        assert_eq!(global.hit_count(), 0);
+    }
+}
@@ -1,4 +1,9 @@
+use crate::diff::HunkContext;
+use crate::git::BaseRevision;
+use crate::models::{FrontMatter, Verdict};
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::Write as IoWrite;
 use std::path::{Path, PathBuf};
@@ -9,6 +14,211 @@ pub use hegel::storage::reviews::{
     write_hegel_reviews, HegelReviewEntry, Position, ProjectType, ReviewComment, SelectionRange,
 };
 
+/// Review comment as persisted by Mirror - hegel-cli's `ReviewComment` plus optional
+/// diff-hunk context (`--diff` mode), base revision (`--since` mode) and the document's
+/// front matter. Readers that don't know the extra fields ignore them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommentRecord {
+    #[serde(flatten)]
+    pub comment: ReviewComment,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hunk: Option<HunkContext>,
+    /// Git revision the document was reviewed against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<BaseRevision>,
+    /// SHA-256 of the working-tree content the comment was made on (with `base`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// Front matter of the reviewed document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub front_matter: Option<FrontMatter>,
+    /// Fields written by other tools, kept as-is
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl From<ReviewComment> for CommentRecord {
+    fn from(comment: ReviewComment) -> Self {
+        Self {
+            comment,
+            hunk: None,
+            base: None,
+            content_hash: None,
+            front_matter: None,
+            extra: serde_json::Map::new(),
+        }
+    }
+}
+
+/// Review entry in `.hegel/reviews.json` - superset of hegel-cli's `HegelReviewEntry`
+///
+/// Adds the review verdict, optional rejection reason and, for verdict-only entries,
+/// the document's front matter (comments carry their own). Hegel's reader ignores the
+/// extra fields; Mirror reads and writes through this type so they survive the
+/// read-modify-write cycle when later entries are appended, along with any fields
+/// other tools added.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewEntry {
+    pub comments: Vec<CommentRecord>,
+    pub timestamp: String,
+    pub session_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verdict: Option<Verdict>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub front_matter: Option<FrontMatter>,
+    /// Fields written by other tools, kept as-is
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ReviewEntry {
+    /// Create an entry timestamped now
+    pub fn new(
+        comments: Vec<CommentRecord>,
+        session_id: Option<String>,
+        verdict: Verdict,
+        reason: Option<String>,
+    ) -> Self {
+        Self {
+            comments,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            session_id,
+            verdict: Some(verdict),
+            reason,
+            front_matter: None,
+            extra: serde_json::Map::new(),
+        }
+    }
+
+    /// Attach the reviewed document's front matter
+    pub fn with_front_matter(mut self, front_matter: Option<FrontMatter>) -> Self {
+        self.front_matter = front_matter;
+        self
+    }
+}
+
+/// Read `.hegel/reviews.json` keeping Mirror's extra entry fields
+pub fn read_review_entries(root: &Path) -> Result<HashMap<String, Vec<ReviewEntry>>> {
+    let reviews_path = root.join("reviews.json");
+    if !reviews_path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let content = fs::read_to_string(&reviews_path)
+        .context(format!("Failed to read reviews file: {:?}", reviews_path))?;
+    serde_json::from_str(&content)
+        .context(format!("Failed to parse reviews file: {:?}", reviews_path))
+}
+
+/// Write `.hegel/reviews.json` (creates the directory if missing)
+pub fn write_review_entries(
+    root: &Path,
+    reviews: &HashMap<String, Vec<ReviewEntry>>,
+) -> Result<()> {
+    fs::create_dir_all(root).context(format!("Failed to create directory: {:?}", root))?;
+
+    let reviews_path = root.join("reviews.json");
+    let content =
+        serde_json::to_string_pretty(reviews).context("Failed to serialize reviews to JSON")?;
+    fs::write(&reviews_path, content)
+        .context(format!("Failed to write reviews file: {:?}", reviews_path))
+}
+
+/// Append an entry to a file's review list in `.hegel/reviews.json`
+pub fn append_review_entry(root: &Path, relative_path: &str, entry: ReviewEntry) -> Result<()> {
+    let mut reviews_map = read_review_entries(root)?;
+    reviews_map
+        .entry(relative_path.to_string())
+        .or_default()
+        .push(entry);
+    write_review_entries(root, &reviews_map)
+}
+
+/// Verdict record written as a JSON line to a `.review.N` sidecar
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerdictRecord {
+    pub verdict: Verdict,
+    pub timestamp: String,
+    pub session_id: Option<String>,
+    /// Reviewed file name (empty for legacy `LGTM - ...` lines and writers that omit it)
+    #[serde(default)]
+    pub file: String,
+    /// SHA-256 of the document content the verdict applies to (hex)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Front matter of the reviewed document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub front_matter: Option<FrontMatter>,
+}
+
+/// A single line of a `.review.N` sidecar
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReviewRecord {
+    Comment(ReviewComment),
+    Verdict(VerdictRecord),
+}
+
+/// SHA-256 hex digest of document content
+pub fn content_hash(content: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// Parse one sidecar line: JSON verdict record, JSON comment, or legacy
+/// `LGTM - <timestamp> (session: <id>)` text line
+pub fn parse_review_line(line: &str) -> Result<ReviewRecord> {
+    let line = line.trim();
+
+    if let Some(rest) = line.strip_prefix("LGTM - ") {
+        let (timestamp, session_id) = match rest.split_once(" (session: ") {
+            Some((timestamp, session)) => {
+                (timestamp, Some(session.trim_end_matches(')').to_string()))
+            }
+            None => (rest, None),
+        };
+
+        return Ok(ReviewRecord::Verdict(VerdictRecord {
+            verdict: Verdict::Lgtm,
+            timestamp: timestamp.to_string(),
+            session_id,
+            file: String::new(),
+            content_hash: None,
+            reason: None,
+            front_matter: None,
+        }));
+    }
+
+    let value: serde_json::Value =
+        serde_json::from_str(line).context(format!("Unrecognized review line: {}", line))?;
+
+    if value.get("verdict").is_some() {
+        Ok(ReviewRecord::Verdict(
+            serde_json::from_value(value).context("Failed to parse verdict record")?,
+        ))
+    } else {
+        Ok(ReviewRecord::Comment(
+            serde_json::from_value(value).context("Failed to parse review comment")?,
+        ))
+    }
+}
+
+/// Read all records from a `.review.N` sidecar (skips blank lines)
+pub fn read_review_file(path: &Path) -> Result<Vec<ReviewRecord>> {
+    let content =
+        fs::read_to_string(path).context(format!("Failed to read review file: {:?}", path))?;
+
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| parse_review_line(line).context(format!("In review file: {:?}", path)))
+        .collect()
+}
+
 /// Storage manager for review files
 pub struct ReviewStorage {
     out_dir: PathBuf,
@@ -34,35 +244,41 @@ impl ReviewStorage {
             .to_string()
     }
 
-    /// Find the next review sequence number by scanning for existing .review.N files
-    fn next_sequence_number(&self) -> Result<usize> {
-        // Ensure output directory exists
-        fs::create_dir_all(&self.out_dir).context(format!(
-            "Failed to create output directory: {:?}",
-            self.out_dir
-        ))?;
-
+    /// Existing .review.N files for this document, sorted by sequence number
+    pub fn review_files(&self) -> Vec<(usize, PathBuf)> {
         let base = self.base_name();
         let pattern = format!("{}.review.", base);
 
-        let mut max_seq = 0;
+        let mut files = Vec::new();
 
         // Scan directory for matching files
         if let Ok(entries) = fs::read_dir(&self.out_dir) {
             for entry in entries.flatten() {
                 if let Some(name) = entry.file_name().to_str() {
-                    if name.starts_with(&pattern) {
-                        // Extract number after ".review."
-                        if let Some(num_str) = name.strip_prefix(&pattern) {
-                            if let Ok(num) = num_str.parse::<usize>() {
-                                max_seq = max_seq.max(num);
-                            }
+                    // Extract number after ".review."
+                    if let Some(num_str) = name.strip_prefix(&pattern) {
+                        if let Ok(num) = num_str.parse::<usize>() {
+                            files.push((num, entry.path()));
                         }
                     }
                 }
             }
         }
 
+        files.sort_by_key(|(num, _)| *num);
+        files
+    }
+
+    /// Find the next review sequence number by scanning for existing .review.N files
+    fn next_sequence_number(&self) -> Result<usize> {
+        // Ensure output directory exists
+        fs::create_dir_all(&self.out_dir).context(format!(
+            "Failed to create output directory: {:?}",
+            self.out_dir
+        ))?;
+
+        let max_seq = self.review_files().last().map_or(0, |(num, _)| *num);
+
         Ok(max_seq + 1)
     }
 
@@ -73,29 +289,15 @@ impl ReviewStorage {
         Ok(self.out_dir.join(format!("{}.review.{}", base, seq)))
     }
 
-    /// Write all comments atomically to a new review file (batched mode)
-    pub fn write_review(
-        &self,
-        comments: Vec<(String, String, usize, usize, usize, usize)>,
-    ) -> Result<PathBuf> {
+    /// Write prepared comment records (with any diff/revision context) to a new review file
+    pub fn write_comment_records(&self, records: Vec<CommentRecord>) -> Result<PathBuf> {
         let review_path = self.review_file_path()?;
         let mut file = File::create(&review_path)
             .context(format!("Failed to create review file: {:?}", review_path))?;
 
-        for (text, comment, line_start, col_start, line_end, col_end) in comments {
-            let review_comment = ReviewComment::new(
-                self.filename.clone(),
-                self.session_id.clone(),
-                text,
-                comment,
-                line_start,
-                col_start,
-                line_end,
-                col_end,
-            );
-
-            let json_line = serde_json::to_string(&review_comment)
-                .context("Failed to serialize comment to JSON")?;
+        for record in records {
+            let json_line =
+                serde_json::to_string(&record).context("Failed to serialize comment to JSON")?;
             writeln!(file, "{}", json_line)
                 .context(format!("Failed to write to review file: {:?}", review_path))?;
         }
@@ -103,20 +305,50 @@ impl ReviewStorage {
         Ok(review_path)
     }
 
-    /// Write approval (LGTM) to a new review file
-    pub fn write_approval(&self) -> Result<PathBuf> {
+    /// Write approval (LGTM) for the given document content to a new review file
+    pub fn write_approval(
+        &self,
+        content: &str,
+        front_matter: Option<FrontMatter>,
+    ) -> Result<PathBuf> {
+        self.write_verdict(Verdict::Lgtm, content, None, front_matter)
+    }
+
+    /// Write rejection (Nope) with optional reason to a new review file
+    pub fn write_rejection(
+        &self,
+        content: &str,
+        reason: Option<String>,
+        front_matter: Option<FrontMatter>,
+    ) -> Result<PathBuf> {
+        self.write_verdict(Verdict::Nope, content, reason, front_matter)
+    }
+
+    /// Write a single verdict record (for `content`) to a new review file
+    fn write_verdict(
+        &self,
+        verdict: Verdict,
+        content: &str,
+        reason: Option<String>,
+        front_matter: Option<FrontMatter>,
+    ) -> Result<PathBuf> {
         let review_path = self.review_file_path()?;
         let mut file = File::create(&review_path)
             .context(format!("Failed to create review file: {:?}", review_path))?;
 
-        let timestamp = chrono::Utc::now().to_rfc3339();
-        let message = if let Some(sid) = &self.session_id {
-            format!("LGTM - {} (session: {})", timestamp, sid)
-        } else {
-            format!("LGTM - {}", timestamp)
+        let record = VerdictRecord {
+            verdict,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            session_id: self.session_id.clone(),
+            file: self.filename.clone(),
+            content_hash: Some(content_hash(content)),
+            reason,
+            front_matter,
         };
 
-        writeln!(file, "{}", message)
+        let json_line =
+            serde_json::to_string(&record).context("Failed to serialize verdict to JSON")?;
+        writeln!(file, "{}", json_line)
             .context(format!("Failed to write to review file: {:?}", review_path))?;
 
         Ok(review_path)
@@ -213,6 +445,132 @@ mod tests {
         assert!(json.contains("\"comments\":[]"));
     }
 
+    #[test]
+    fn test_review_entry_reads_hegel_entries_without_verdict() {
+        let json = r#"{"comments":[],"timestamp":"2025-01-01T00:00:00Z","session_id":null}"#;
+        let entry: ReviewEntry = serde_json::from_str(json).unwrap();
+
+        assert_eq!(entry.verdict, None);
+        assert_eq!(entry.reason, None);
+
+        // Absent verdict is not written back
+        let round_trip = serde_json::to_string(&entry).unwrap();
+        assert!(!round_trip.contains("verdict"));
+    }
+
+    #[test]
+    fn test_append_review_entry_keeps_unknown_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        let hegel_dir = temp_dir.path().join(".hegel");
+        fs::create_dir(&hegel_dir).unwrap();
+        let existing = serde_json::json!({
+            "test.md": [{
+                "comments": [{
+                    "file": "test.md",
+                    "session_id": null,
+                    "selection": {"start": {"line": 1, "col": 0}, "end": {"line": 1, "col": 0}},
+                    "text": "Title",
+                    "comment": "Retitle",
+                    "timestamp": "2025-01-01T00:00:00Z",
+                    "severity": "minor"
+                }],
+                "timestamp": "2025-01-01T00:00:00Z",
+                "session_id": null,
+                "reviewer": "ci-bot"
+            }]
+        });
+        fs::write(hegel_dir.join("reviews.json"), existing.to_string()).unwrap();
+
+        let entry = ReviewEntry::new(vec![], None, Verdict::Reviewed, None);
+        append_review_entry(&hegel_dir, "test.md", entry).unwrap();
+
+        let entries = read_review_entries(&hegel_dir)
+            .unwrap()
+            .remove("test.md")
+            .unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].extra["reviewer"], "ci-bot");
+        assert_eq!(entries[0].comments[0].extra["severity"], "minor");
+        assert_eq!(entries[0].comments[0].comment.comment, "Retitle");
+        assert!(entries[1].extra.is_empty());
+
+        // Known fields aren't duplicated into the extras
+        let content = fs::read_to_string(hegel_dir.join("reviews.json")).unwrap();
+        assert_eq!(content.matches("\"timestamp\"").count(), 3);
+    }
+
+    #[test]
+    fn test_content_hash_is_sha256_hex() {
+        assert_eq!(
+            content_hash(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_ne!(content_hash("# Test"), content_hash("# Test\n"));
+    }
+
+    #[test]
+    fn test_parse_legacy_lgtm_line() {
+        let record =
+            parse_review_line("LGTM - 2025-01-01T00:00:00+00:00 (session: session123)").unwrap();
+
+        let ReviewRecord::Verdict(verdict) = record else {
+            panic!("Expected verdict record");
+        };
+        assert_eq!(verdict.verdict, Verdict::Lgtm);
+        assert_eq!(verdict.timestamp, "2025-01-01T00:00:00+00:00");
+        assert_eq!(verdict.session_id.as_deref(), Some("session123"));
+        assert_eq!(verdict.content_hash, None);
+    }
+
+    #[test]
+    fn test_parse_legacy_lgtm_line_without_session() {
+        let record = parse_review_line("LGTM - 2025-01-01T00:00:00+00:00").unwrap();
+
+        let ReviewRecord::Verdict(verdict) = record else {
+            panic!("Expected verdict record");
+        };
+        assert_eq!(verdict.timestamp, "2025-01-01T00:00:00+00:00");
+        assert_eq!(verdict.session_id, None);
+    }
+
+    #[test]
+    fn test_parse_review_line_comment_and_verdict() {
+        let comment = ReviewComment::new(
+            "test.md".to_string(),
+            None,
+            "selected text".to_string(),
+            "test comment".to_string(),
+            1,
+            0,
+            1,
+            10,
+        );
+        let line = serde_json::to_string(&comment).unwrap();
+        assert_eq!(
+            parse_review_line(&line).unwrap(),
+            ReviewRecord::Comment(comment)
+        );
+
+        let line = r#"{"verdict":"nope","timestamp":"t","session_id":null,"file":"test.md","reason":"Why"}"#;
+        let ReviewRecord::Verdict(verdict) = parse_review_line(line).unwrap() else {
+            panic!("Expected verdict record");
+        };
+        assert_eq!(verdict.verdict, Verdict::Nope);
+        assert_eq!(verdict.reason.as_deref(), Some("Why"));
+
+        // Other writers may leave out the file name
+        let line = r#"{"verdict":"lgtm","timestamp":"t","session_id":null}"#;
+        let ReviewRecord::Verdict(verdict) = parse_review_line(line).unwrap() else {
+            panic!("Expected verdict record");
+        };
+        assert_eq!(verdict.file, "");
+    }
+
+    #[test]
+    fn test_parse_review_line_garbage() {
+        assert!(parse_review_line("not a review").is_err());
+    }
+
     #[test]
     fn test_compute_relative_path() {
         let temp_dir = TempDir::new().unwrap();
@@ -451,6 +809,483 @@ mod tests {
             assert!(!temp_dir.path().join("reviews.json").exists());
         }
 
+        #[test]
+        fn test_document_write_rejection_hegel_mode() {
+            let temp_dir = TempDir::new().unwrap();
+            let hegel_dir = temp_dir.path().join(".hegel");
+            fs::create_dir(&hegel_dir).unwrap();
+
+            let file_path = temp_dir.path().join("test.md");
+            fs::write(&file_path, "# Test").unwrap();
+
+            let project_type = ProjectType::Hegel {
+                root: hegel_dir.clone(),
+            };
+
+            let doc = Document::new(
+                "test.md".to_string(),
+                "# Test".to_string(),
+                temp_dir.path().to_path_buf(),
+                file_path.clone(),
+                temp_dir.path().to_path_buf(),
+                Some("session123".to_string()),
+                project_type,
+            );
+
+            doc.write_rejection(Some("Scope unclear".to_string()))
+                .unwrap();
+            doc.write_approval().unwrap();
+
+            // Verdict and reason of the first entry survive the second append
+            let reviews = read_review_entries(&hegel_dir).unwrap();
+            let entries = reviews.get("test.md").unwrap();
+            assert_eq!(entries.len(), 2);
+            assert_eq!(entries[0].verdict, Some(Verdict::Nope));
+            assert_eq!(entries[0].reason.as_deref(), Some("Scope unclear"));
+            assert!(entries[0].comments.is_empty());
+            assert_eq!(entries[1].verdict, Some(Verdict::Lgtm));
+
+            // Still readable through hegel-cli's entry type
+            let hegel_reviews = read_hegel_reviews(&hegel_dir).unwrap();
+            assert_eq!(hegel_reviews.get("test.md").unwrap().len(), 2);
+        }
+
+        #[test]
+        fn test_document_verdicts_keep_unknown_fields() {
+            let temp_dir = TempDir::new().unwrap();
+            let hegel_dir = temp_dir.path().join(".hegel");
+            fs::create_dir(&hegel_dir).unwrap();
+            let existing = serde_json::json!({
+                "test.md": [{
+                    "comments": [],
+                    "timestamp": "2025-01-01T00:00:00Z",
+                    "session_id": null,
+                    "workflow": {"phase": "spec", "step": 2}
+                }],
+                "other.md": [{
+                    "comments": [],
+                    "timestamp": "2025-01-01T00:00:00Z",
+                    "session_id": null,
+                    "reviewer": "ci-bot"
+                }]
+            });
+            fs::write(hegel_dir.join("reviews.json"), existing.to_string()).unwrap();
+
+            let file_path = temp_dir.path().join("test.md");
+            fs::write(&file_path, "# Test").unwrap();
+
+            let doc = Document::new(
+                "test.md".to_string(),
+                "# Test".to_string(),
+                temp_dir.path().to_path_buf(),
+                file_path.clone(),
+                temp_dir.path().to_path_buf(),
+                Some("session123".to_string()),
+                ProjectType::Hegel {
+                    root: hegel_dir.clone(),
+                },
+            );
+
+            doc.write_approval().unwrap();
+            doc.write_rejection(None).unwrap();
+
+            let reviews = read_review_entries(&hegel_dir).unwrap();
+            let entries = reviews.get("test.md").unwrap();
+            assert_eq!(entries.len(), 3);
+            assert_eq!(entries[0].extra["workflow"]["step"], 2);
+            assert_eq!(entries[1].verdict, Some(Verdict::Lgtm));
+            assert_eq!(entries[2].verdict, Some(Verdict::Nope));
+            assert_eq!(
+                reviews.get("other.md").unwrap()[0].extra["reviewer"],
+                "ci-bot"
+            );
+        }
+
+        #[test]
+        fn test_document_write_rejection_standalone_mode() {
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("test.md");
+            fs::write(&file_path, "# Test").unwrap();
+
+            let doc = Document::new(
+                "test.md".to_string(),
+                "# Test".to_string(),
+                temp_dir.path().to_path_buf(),
+                file_path.clone(),
+                temp_dir.path().to_path_buf(),
+                Some("session123".to_string()),
+                ProjectType::Standalone,
+            );
+
+            let path = doc.write_rejection(None).unwrap();
+            assert_eq!(path, temp_dir.path().join("test.review.1"));
+
+            let content = fs::read_to_string(&path).unwrap();
+            let record: VerdictRecord = serde_json::from_str(content.trim()).unwrap();
+            assert_eq!(record.verdict, Verdict::Nope);
+            assert_eq!(record.file, "test.md");
+            assert_eq!(record.session_id.as_deref(), Some("session123"));
+            assert_eq!(record.content_hash, Some(content_hash("# Test")));
+            assert_eq!(record.reason, None);
+        }
+
+        #[test]
+        fn test_document_write_approval_standalone_mode() {
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("test.md");
+            fs::write(&file_path, "# Test").unwrap();
+
+            let doc = Document::new(
+                "test.md".to_string(),
+                "# Test".to_string(),
+                temp_dir.path().to_path_buf(),
+                file_path.clone(),
+                temp_dir.path().to_path_buf(),
+                Some("session123".to_string()),
+                ProjectType::Standalone,
+            );
+
+            let path = doc.write_approval().unwrap();
+            let records = read_review_file(&path).unwrap();
+            assert_eq!(records.len(), 1);
+
+            let ReviewRecord::Verdict(record) = &records[0] else {
+                panic!("Expected verdict record");
+            };
+            assert_eq!(record.verdict, Verdict::Lgtm);
+            assert_eq!(record.file, "test.md");
+            assert_eq!(record.session_id.as_deref(), Some("session123"));
+            assert_eq!(record.content_hash, Some(content_hash("# Test")));
+            assert_eq!(record.front_matter, None);
+        }
+
+        #[test]
+        fn test_document_write_approval_records_front_matter() {
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("test.md");
+            let source = "---\nstatus: draft\nowner: Alex\n---\n\n# Test\n";
+            fs::write(&file_path, source).unwrap();
+
+            let doc = Document::new(
+                "test.md".to_string(),
+                source.to_string(),
+                temp_dir.path().to_path_buf(),
+                file_path.clone(),
+                temp_dir.path().to_path_buf(),
+                None,
+                ProjectType::Standalone,
+            );
+
+            let path = doc.write_approval().unwrap();
+            let content = fs::read_to_string(&path).unwrap();
+            assert!(content.contains(r#""front_matter":{"status":"draft","owner":"Alex"}"#));
+
+            let records = read_review_file(&path).unwrap();
+            let ReviewRecord::Verdict(record) = &records[0] else {
+                panic!("Expected verdict record");
+            };
+            let front_matter = record.front_matter.as_ref().unwrap();
+            assert_eq!(front_matter.get("status"), Some("draft"));
+            assert_eq!(front_matter.get("owner"), Some("Alex"));
+        }
+
+        #[test]
+        fn test_read_review_file_mixed_formats() {
+            let temp_dir = TempDir::new().unwrap();
+            let review_path = temp_dir.path().join("test.review.1");
+            let comment = ReviewComment::new(
+                "test.md".to_string(),
+                None,
+                "text".to_string(),
+                "comment".to_string(),
+                1,
+                0,
+                1,
+                4,
+            );
+            fs::write(
+                &review_path,
+                format!(
+                    "{}\n\nLGTM - 2025-01-01T00:00:00+00:00\n",
+                    serde_json::to_string(&comment).unwrap()
+                ),
+            )
+            .unwrap();
+
+            let records = read_review_file(&review_path).unwrap();
+            assert_eq!(records.len(), 2);
+            assert!(matches!(records[0], ReviewRecord::Comment(_)));
+            assert!(matches!(records[1], ReviewRecord::Verdict(_)));
+        }
+
+        #[test]
+        fn test_load_history_standalone_mode() {
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("test.md");
+            fs::write(&file_path, "# Test\n\nBody").unwrap();
+
+            let new_doc = || {
+                Document::new(
+                    "test.md".to_string(),
+                    "# Test\n\nBody".to_string(),
+                    temp_dir.path().to_path_buf(),
+                    file_path.clone(),
+                    temp_dir.path().to_path_buf(),
+                    None,
+                    ProjectType::Standalone,
+                )
+            };
+
+            // Round 1: review with a comment, round 2: legacy approval line
+            new_doc()
+                .write_review(vec![("Body".to_string(), "Expand".to_string(), 3, 0, 3, 0)])
+                .unwrap();
+            fs::write(
+                temp_dir.path().join("test.review.2"),
+                "LGTM - 2025-01-01T00:00:00+00:00\n",
+            )
+            .unwrap();
+
+            let mut doc = new_doc();
+            doc.load_history().unwrap();
+
+            assert_eq!(doc.history.len(), 2);
+            assert_eq!(doc.history[0].verdict, Some(Verdict::Reviewed));
+            assert_eq!(doc.history[0].comments[0].comment.comment, "Expand");
+            assert_eq!(doc.history[1].verdict, Some(Verdict::Lgtm));
+
+            // Latest round with comments is shown by default
+            assert!(doc.history[0].visible);
+            assert!(!doc.history[1].visible);
+        }
+
+        #[test]
+        fn test_load_history_hegel_mode() {
+            let temp_dir = TempDir::new().unwrap();
+            let hegel_dir = temp_dir.path().join(".hegel");
+            fs::create_dir(&hegel_dir).unwrap();
+
+            let file_path = temp_dir.path().join("test.md");
+            fs::write(&file_path, "# Test").unwrap();
+
+            let new_doc = || {
+                Document::new(
+                    "test.md".to_string(),
+                    "# Test".to_string(),
+                    temp_dir.path().to_path_buf(),
+                    file_path.clone(),
+                    temp_dir.path().to_path_buf(),
+                    None,
+                    ProjectType::Hegel {
+                        root: hegel_dir.clone(),
+                    },
+                )
+            };
+
+            new_doc()
+                .write_review(vec![(
+                    "# Test".to_string(),
+                    "Rename".to_string(),
+                    1,
+                    0,
+                    1,
+                    0,
+                )])
+                .unwrap();
+            new_doc()
+                .write_review(vec![(
+                    "# Test".to_string(),
+                    "Still unclear".to_string(),
+                    1,
+                    0,
+                    1,
+                    0,
+                )])
+                .unwrap();
+
+            let mut doc = new_doc();
+            doc.load_history().unwrap();
+
+            assert_eq!(doc.history.len(), 2);
+            assert_eq!(doc.history[1].comments[0].comment.comment, "Still unclear");
+            assert!(!doc.history[0].visible);
+            assert!(doc.history[1].visible);
+        }
+
+        #[test]
+        fn test_load_history_empty() {
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("test.md");
+            fs::write(&file_path, "# Test").unwrap();
+
+            let mut doc = Document::new(
+                "test.md".to_string(),
+                "# Test".to_string(),
+                temp_dir.path().to_path_buf(),
+                file_path,
+                temp_dir.path().to_path_buf(),
+                None,
+                ProjectType::Standalone,
+            );
+            doc.load_history().unwrap();
+
+            assert!(doc.history.is_empty());
+        }
+
+        fn diff_document(temp_dir: &TempDir, project_type: ProjectType) -> Document {
+            use crate::diff::DiffView;
+
+            let old = "# Test\n\nOld sentence.\n";
+            let new = "# Test\n\nNew sentence.\n";
+            let file_path = temp_dir.path().join("test.md");
+            fs::write(&file_path, new).unwrap();
+
+            let mut doc = Document::new(
+                "test.md".to_string(),
+                new.to_string(),
+                temp_dir.path().to_path_buf(),
+                file_path,
+                temp_dir.path().to_path_buf(),
+                None,
+                project_type,
+            );
+            doc.diff = Some(DiffView::new(
+                temp_dir.path().join("old.md"),
+                old.to_string(),
+                new,
+            ));
+            doc
+        }
+
+        #[test]
+        fn test_diff_review_standalone_records_hunk() {
+            let temp_dir = TempDir::new().unwrap();
+            let mut doc = diff_document(&temp_dir, ProjectType::Standalone);
+
+            // Whole hunk (targets the removed line too), then just the added line
+            doc.selection.select_lines(1, 3);
+            assert!(doc.add_comment("Why reword?".to_string()));
+            doc.selection.select_lines(3, 3);
+            assert!(doc.add_comment("Typo".to_string()));
+            let path = doc.submit_review().unwrap();
+
+            let lines: Vec<serde_json::Value> = fs::read_to_string(&path)
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect();
+
+            assert_eq!(lines[0]["hunk"]["change"], "modified");
+            assert_eq!(lines[0]["hunk"]["old_start"], 1);
+            assert_eq!(lines[0]["hunk"]["old_len"], 3);
+            assert_eq!(lines[0]["hunk"]["old_lines"], serde_json::json!([1, 3]));
+            assert_eq!(lines[1]["hunk"]["new_lines"], serde_json::json!([3, 3]));
+            assert!(lines[0]["text"]
+                .as_str()
+                .unwrap()
+                .contains("-Old sentence."));
+            assert_eq!(lines[1]["hunk"]["change"], "added");
+            assert_eq!(lines[1]["text"], "New sentence.");
+
+            // Sidecars stay readable as plain comments
+            assert!(matches!(
+                read_review_file(&path).unwrap()[0],
+                ReviewRecord::Comment(_)
+            ));
+        }
+
+        #[test]
+        fn test_diff_review_records_picked_removed_line() {
+            use crate::diff::RowSelection;
+
+            let temp_dir = TempDir::new().unwrap();
+            let mut doc = diff_document(&temp_dir, ProjectType::Standalone);
+
+            // Pick only the removed row, as a click in the diff view does
+            let diff = doc.diff.as_mut().unwrap();
+            assert_eq!(diff.hunks[0].lines[2].text, "Old sentence.");
+            let (start, end) = diff.hunks[0].anchor_lines(2, 2);
+            diff.selected_rows = Some(RowSelection {
+                hunk: 0,
+                first: 2,
+                last: 2,
+            });
+            doc.selection.select_lines(start, end);
+            assert!(doc.add_comment("Keep this".to_string()));
+            let path = doc.submit_review().unwrap();
+
+            let line: serde_json::Value =
+                serde_json::from_str(fs::read_to_string(&path).unwrap().trim()).unwrap();
+            assert_eq!(line["hunk"]["change"], "removed");
+            assert_eq!(line["hunk"]["old_lines"], serde_json::json!([3, 3]));
+            assert!(line["hunk"].get("new_lines").is_none());
+            assert_eq!(line["text"], "-Old sentence.");
+        }
+
+        #[test]
+        fn test_diff_review_hegel_keeps_hunk_on_append() {
+            let temp_dir = TempDir::new().unwrap();
+            let hegel_dir = temp_dir.path().join(".hegel");
+            fs::create_dir(&hegel_dir).unwrap();
+            let project_type = ProjectType::Hegel {
+                root: hegel_dir.clone(),
+            };
+
+            let mut doc = diff_document(&temp_dir, project_type);
+            doc.selection.select_lines(3, 3);
+            assert!(doc.add_comment("Typo".to_string()));
+            doc.submit_review().unwrap();
+            // A later entry rewrites reviews.json
+            doc.write_approval().unwrap();
+
+            let reviews = read_review_entries(&hegel_dir).unwrap();
+            let entries = reviews.values().next().unwrap();
+            assert_eq!(entries.len(), 2);
+            let hunk = entries[0].comments[0].hunk.as_ref().unwrap();
+            assert_eq!(hunk.change, crate::diff::HunkChange::Added);
+            assert_eq!((hunk.new_start, hunk.new_len), (1, 3));
+        }
+
+        #[test]
+        fn test_since_review_records_base_and_content_hash() {
+            use crate::diff::RevisionDiff;
+            use crate::git::BaseRevision;
+
+            let temp_dir = TempDir::new().unwrap();
+            let source = "# Test\n\nNew sentence.\n";
+            let file_path = temp_dir.path().join("test.md");
+            fs::write(&file_path, source).unwrap();
+
+            let mut doc = Document::new(
+                "test.md".to_string(),
+                source.to_string(),
+                temp_dir.path().to_path_buf(),
+                file_path,
+                temp_dir.path().to_path_buf(),
+                None,
+                ProjectType::Standalone,
+            );
+            let base = BaseRevision {
+                rev: "HEAD~1".to_string(),
+                commit: "a".repeat(40),
+            };
+            doc.revision = Some(RevisionDiff::new(
+                base.clone(),
+                "# Test\n".to_string(),
+                source,
+            ));
+
+            doc.selection.select_lines(3, 3);
+            assert!(doc.add_comment("Why?".to_string()));
+            let path = doc.submit_review().unwrap();
+
+            let line = fs::read_to_string(&path).unwrap();
+            let record: CommentRecord = serde_json::from_str(line.trim()).unwrap();
+            assert_eq!(record.base, Some(base));
+            assert_eq!(record.content_hash, Some(content_hash(source)));
+            assert_eq!(record.comment.comment, "Why?");
+        }
+
         #[test]
         fn test_multi_file_hegel_reviews() {
             let temp_dir = TempDir::new().unwrap();
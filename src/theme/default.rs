@@ -15,6 +15,8 @@ pub const THEME: Theme = Theme {
         corner_radius: 4.0,
         min_line_height: 16.0,
         table_cell_padding: 8.0,
+        list_indent: 24.0,
+        quote_indent: 16.0,
         table_border_width: 1.0,
     },
     colors: Colors {
@@ -27,6 +29,38 @@ pub const THEME: Theme = Theme {
         table_header_bg: egui::Color32::from_rgb(240, 240, 240),
         table_row_alt_bg: egui::Color32::from_rgb(250, 250, 250),
         selection_highlight: egui::Color32::from_rgb(80, 140, 255), // Solid blue for margin bar
+        history_markers: [
+            egui::Color32::from_rgb(240, 150, 50),  // Orange
+            egui::Color32::from_rgb(160, 100, 220), // Purple
+            egui::Color32::from_rgb(40, 170, 150),  // Teal
+            egui::Color32::from_rgb(220, 90, 120),  // Rose
+        ],
+        diff_added_bg: egui::Color32::from_rgb(220, 245, 225), // Pale green
+        diff_removed_bg: egui::Color32::from_rgb(250, 225, 225), // Pale red
+        change_markers: [
+            egui::Color32::from_rgb(60, 170, 90),  // Green
+            egui::Color32::from_rgb(70, 130, 220), // Blue
+            egui::Color32::from_rgb(210, 70, 70),  // Red
+        ],
+        search_match: egui::Color32::from_rgba_unmultiplied_const(255, 210, 0, 80), // Yellow
+        search_current: egui::Color32::from_rgba_unmultiplied_const(255, 130, 0, 140), // Orange
+        link: egui::Color32::from_rgb(9, 105, 218), // GitHub link blue
+        quote_border: egui::Color32::from_rgb(208, 215, 222),
+        quote_bg: egui::Color32::from_rgb(246, 248, 250),
+        alert_markers: [
+            egui::Color32::from_rgb(9, 105, 218),  // Blue
+            egui::Color32::from_rgb(26, 127, 55),  // Green
+            egui::Color32::from_rgb(130, 80, 223), // Purple
+            egui::Color32::from_rgb(154, 103, 0),  // Amber
+            egui::Color32::from_rgb(209, 36, 47),  // Red
+        ],
+        alert_bg: [
+            egui::Color32::from_rgb(237, 244, 255), // Pale blue
+            egui::Color32::from_rgb(236, 247, 239), // Pale green
+            egui::Color32::from_rgb(245, 240, 253), // Pale purple
+            egui::Color32::from_rgb(254, 248, 228), // Pale amber
+            egui::Color32::from_rgb(254, 238, 239), // Pale red
+        ],
     },
     layout: Layout {
         page_margin_left: 40.0,
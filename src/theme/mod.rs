@@ -37,6 +37,10 @@ pub struct Spacing {
     pub min_line_height: f32,
     /// Padding inside table cells
     pub table_cell_padding: f32,
+    /// Indentation per list nesting level (also the width of the marker column)
+    pub list_indent: f32,
+    /// Indentation per blockquote nesting level (border bar included)
+    pub quote_indent: f32,
     /// Table border width (for future table styling)
     #[allow(dead_code)]
     pub table_border_width: f32,
@@ -64,6 +68,28 @@ pub struct Colors {
     pub table_row_alt_bg: egui::Color32,
     /// Selection highlight background color
     pub selection_highlight: egui::Color32,
+    /// Margin marker colors for prior review rounds (cycled per round)
+    pub history_markers: [egui::Color32; 4],
+    /// Background for added lines in the diff view
+    pub diff_added_bg: egui::Color32,
+    /// Background for removed lines in the diff view
+    pub diff_removed_bg: egui::Color32,
+    /// Gutter markers for changes since a git revision: [added, modified, removed]
+    pub change_markers: [egui::Color32; 3],
+    /// Search match highlight (translucent, drawn over text)
+    pub search_match: egui::Color32,
+    /// Highlight for the focused search match
+    pub search_current: egui::Color32,
+    /// Link text color (links are also underlined)
+    pub link: egui::Color32,
+    /// Left border of blockquotes
+    pub quote_border: egui::Color32,
+    /// Blockquote background tint
+    pub quote_bg: egui::Color32,
+    /// Alert callout accents (border, title): [note, tip, important, warning, caution]
+    pub alert_markers: [egui::Color32; 5],
+    /// Alert callout background tints, in the same order
+    pub alert_bg: [egui::Color32; 5],
 }
 
 /// Page-level layout settings
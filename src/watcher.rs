@@ -0,0 +1,111 @@
+/// Detect on-disk changes to a reviewed file (mtime polling, no background threads)
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often the GUI polls reviewed files for changes
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Watches a single file for modification-time changes
+pub struct FileWatcher {
+    path: PathBuf,
+    last_modified: Option<SystemTime>,
+    last_poll: Instant,
+}
+
+impl FileWatcher {
+    /// Start watching from the file's current state
+    pub fn new(path: PathBuf) -> Self {
+        let last_modified = modified_time(&path);
+        Self {
+            path,
+            last_modified,
+            last_poll: Instant::now(),
+        }
+    }
+
+    /// Rate-limited check (at most once per POLL_INTERVAL), safe to call every frame
+    pub fn poll(&mut self) -> bool {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return false;
+        }
+        self.last_poll = Instant::now();
+        self.check()
+    }
+
+    /// Check now: true if the file was modified since the last check
+    /// A temporarily missing file (editor save-by-rename) is not a change
+    pub fn check(&mut self) -> bool {
+        match modified_time(&self.path) {
+            Some(modified) if Some(modified) != self.last_modified => {
+                self.last_modified = Some(modified);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_unchanged_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("SPEC.md");
+        fs::write(&path, "# Spec").unwrap();
+
+        let mut watcher = FileWatcher::new(path);
+        assert!(!watcher.check());
+    }
+
+    #[test]
+    fn test_detects_modification_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("SPEC.md");
+        fs::write(&path, "# Spec").unwrap();
+
+        let mut watcher = FileWatcher::new(path.clone());
+
+        // Bump mtime explicitly (filesystem timestamp resolution can be coarse)
+        let later = SystemTime::now() + Duration::from_secs(5);
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+
+        assert!(watcher.check());
+        assert!(!watcher.check());
+    }
+
+    #[test]
+    fn test_missing_file_is_not_a_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("SPEC.md");
+        fs::write(&path, "# Spec").unwrap();
+
+        let mut watcher = FileWatcher::new(path.clone());
+        fs::remove_file(&path).unwrap();
+
+        assert!(!watcher.check());
+    }
+
+    #[test]
+    fn test_poll_is_rate_limited() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("SPEC.md");
+        fs::write(&path, "# Spec").unwrap();
+
+        let mut watcher = FileWatcher::new(path);
+        // Created just now - first poll happens only after the interval
+        assert!(!watcher.poll());
+    }
+}
@@ -23,6 +23,23 @@ mod selection_tests {
         assert!(selection.is_dragging);
     }
 
+    #[test]
+    fn test_extend_lines_from_keyboard() {
+        let mut selection = Selection::default();
+        selection.extend_lines(1, 10);
+        assert_eq!(selection.line_range(), Some((1, 1)));
+
+        selection.extend_lines(1, 10);
+        selection.extend_lines(1, 10);
+        assert_eq!(selection.line_range(), Some((1, 3)));
+
+        // Clamped to the document
+        selection.extend_lines(-5, 10);
+        assert_eq!(selection.line_range(), Some((1, 1)));
+        selection.extend_lines(20, 10);
+        assert_eq!(selection.line_range(), Some((1, 10)));
+    }
+
     #[test]
     fn test_update_drag_changes_end_line() {
         let mut selection = Selection::default();
@@ -109,6 +126,26 @@ mod selection_tests {
         assert!(!selection.contains_line(8));
     }
 
+    #[test]
+    fn test_select_lines_is_not_dragging() {
+        let mut selection = Selection::default();
+        selection.select_lines(3, 7);
+
+        assert!(selection.is_active());
+        assert!(!selection.is_dragging);
+        assert_eq!(selection.line_range(), Some((3, 7)));
+    }
+
+    #[test]
+    fn test_line_range_normalizes_backward_selection() {
+        let mut selection = Selection::default();
+        selection.start_drag(10);
+        selection.update_drag(5);
+
+        assert_eq!(selection.line_range(), Some((5, 10)));
+        assert_eq!(Selection::default().line_range(), None);
+    }
+
     #[test]
     fn test_contains_line_with_no_selection() {
         let selection = Selection::default();
@@ -116,6 +153,160 @@ mod selection_tests {
         assert!(!selection.contains_line(1));
         assert!(!selection.contains_line(100));
     }
+
+    #[test]
+    fn test_start_drag_at_tracks_bytes() {
+        let mut selection = Selection::default();
+        selection.start_drag_at(2, 10);
+        selection.update_drag_at(2, 4);
+
+        // Backwards drag is normalized
+        assert_eq!(selection.byte_range(), Some((4, 10)));
+    }
+
+    #[test]
+    fn test_update_drag_drops_column_precision() {
+        let mut selection = Selection::default();
+        selection.start_drag_at(2, 10);
+        selection.update_drag(5);
+
+        assert_eq!(selection.byte_range(), None);
+        assert_eq!(selection.line_range(), Some((2, 5)));
+    }
+
+    #[test]
+    fn test_empty_byte_range_falls_back_to_lines() {
+        let mut selection = Selection::default();
+        selection.start_drag_at(3, 7);
+
+        assert_eq!(selection.byte_range(), None);
+        assert_eq!(selection.anchor("a\nb\nc"), Some((3, 0, 3, 0)));
+    }
+
+    #[test]
+    fn test_anchor_column_precise() {
+        let source = "# Title\n\nFirst paragraph here";
+        let mut selection = Selection::default();
+        // "paragraph" is bytes 15..24
+        selection.select_bytes(source, 15, 24);
+
+        assert_eq!(selection.line_range(), Some((3, 3)));
+        assert_eq!(selection.anchor(source), Some((3, 7, 3, 16)));
+    }
+
+    #[test]
+    fn test_select_anchor_round_trips_comment_anchor() {
+        let source = "# Title\n\nFirst paragraph here";
+        let mut selection = Selection::default();
+
+        selection.select_anchor(source, 3, 7, 3, 16);
+        assert_eq!(selection.byte_range(), Some((15, 24)));
+        assert_eq!(selection.anchor(source), Some((3, 7, 3, 16)));
+
+        // Col 0 re-selects whole lines
+        selection.select_anchor(source, 1, 0, 3, 0);
+        assert_eq!(selection.byte_range(), None);
+        assert_eq!(selection.line_range(), Some((1, 3)));
+        assert!(!selection.is_dragging);
+    }
+
+    #[test]
+    fn test_select_lines_clears_bytes() {
+        let mut selection = Selection::default();
+        selection.select_bytes("hello world", 0, 5);
+        selection.select_lines(1, 1);
+
+        assert_eq!(selection.byte_range(), None);
+    }
+}
+
+mod cursor_tests {
+    use mirror::models::{CursorMotion, LineCursor, Selection};
+
+    /// Paragraph on lines 1-2, heading on 4, list on 6-8
+    const BLOCKS: [(usize, usize); 3] = [(1, 2), (4, 4), (6, 8)];
+
+    #[test]
+    fn test_line_motion_skips_lines_outside_blocks() {
+        assert_eq!(LineCursor::target(2, CursorMotion::LineDown, 8, &BLOCKS), 4);
+        assert_eq!(LineCursor::target(4, CursorMotion::LineUp, 8, &BLOCKS), 2);
+        // Nothing further: stay put
+        assert_eq!(LineCursor::target(8, CursorMotion::LineDown, 8, &BLOCKS), 8);
+        // Without blocks every line is a stop
+        assert_eq!(LineCursor::target(2, CursorMotion::LineDown, 8, &[]), 3);
+    }
+
+    #[test]
+    fn test_block_motion() {
+        assert_eq!(
+            LineCursor::target(1, CursorMotion::BlockDown, 8, &BLOCKS),
+            4
+        );
+        assert_eq!(LineCursor::target(7, CursorMotion::BlockUp, 8, &BLOCKS), 6);
+        assert_eq!(LineCursor::target(6, CursorMotion::BlockUp, 8, &BLOCKS), 4);
+        assert_eq!(
+            LineCursor::target(7, CursorMotion::BlockDown, 8, &BLOCKS),
+            7
+        );
+        assert_eq!(LineCursor::target(3, CursorMotion::BlockDown, 8, &[]), 8);
+    }
+
+    #[test]
+    fn test_first_press_shows_cursor() {
+        let mut cursor = LineCursor::default();
+        let mut selection = Selection::default();
+        cursor.move_by(CursorMotion::LineDown, false, &mut selection, 8, &BLOCKS);
+        assert_eq!(cursor.line, Some(1));
+        assert_eq!(cursor.take_reveal(), Some(1));
+        assert_eq!(cursor.take_reveal(), None);
+
+        cursor.move_by(CursorMotion::LineDown, false, &mut selection, 8, &BLOCKS);
+        assert_eq!(cursor.line, Some(2));
+        assert!(!selection.is_active());
+    }
+
+    #[test]
+    fn test_extend_grows_selection_with_cursor() {
+        let mut cursor = LineCursor {
+            line: Some(2),
+            reveal: false,
+        };
+        let mut selection = Selection::default();
+        cursor.move_by(CursorMotion::BlockDown, true, &mut selection, 8, &BLOCKS);
+        assert_eq!(cursor.line, Some(4));
+        assert_eq!(selection.line_range(), Some((2, 4)));
+
+        cursor.move_by(CursorMotion::LineDown, true, &mut selection, 8, &BLOCKS);
+        assert_eq!(selection.line_range(), Some((2, 6)));
+
+        // A plain move collapses the selection
+        cursor.move_by(CursorMotion::LineUp, false, &mut selection, 8, &BLOCKS);
+        assert_eq!(cursor.line, Some(4));
+        assert!(!selection.is_active());
+    }
+
+    #[test]
+    fn test_extend_continues_mouse_selection() {
+        let mut cursor = LineCursor::default();
+        let mut selection = Selection::default();
+        selection.select_lines(6, 7);
+        cursor.move_by(CursorMotion::LineDown, true, &mut selection, 8, &BLOCKS);
+        assert_eq!(selection.line_range(), Some((6, 8)));
+        assert_eq!(cursor.line, Some(8));
+    }
+
+    #[test]
+    fn test_select_cursor_line_as_anchor() {
+        let mut selection = Selection::default();
+        assert!(!LineCursor::default().select_line(&mut selection));
+
+        let cursor = LineCursor {
+            line: Some(4),
+            reveal: false,
+        };
+        assert!(cursor.select_line(&mut selection));
+        assert_eq!(selection.anchor("a\nb\n\n# c\n"), Some((4, 0, 4, 0)));
+    }
 }
 
 mod comment_tests {
@@ -132,6 +323,12 @@ mod comment_tests {
         assert_eq!(comment.col_end, 20);
     }
 
+    #[test]
+    fn test_comment_location() {
+        let comment = Comment::new("Fix typo".to_string(), 5, 10, 5, 15);
+        assert_eq!(comment.location(), "[L5:C10 → L5:C15]");
+    }
+
     #[test]
     fn test_comment_format_single_line() {
         let comment = Comment::new("Fix typo".to_string(), 5, 10, 5, 15);
@@ -278,4 +475,131 @@ mod layout_tests {
         assert_eq!(layout_map.get_line_y(0), None);
         assert_eq!(layout_map.get_line_y(1), None);
     }
+
+    #[test]
+    fn test_current_section_from_heading_positions() {
+        use mirror::models::{current_section, TocEntry};
+
+        let entry = |line: usize| TocEntry {
+            level: 2,
+            title: format!("Section {}", line),
+            line,
+            section_end: line + 9,
+        };
+        let toc = vec![entry(1), entry(11), entry(21)];
+
+        // Headings recorded at y = 0, 500, 1000 (offscreen ones too)
+        let mut map = LayoutMap::new();
+        map.record_chunk(1, 1, 0.0, 20.0);
+        map.record_chunk(11, 11, 500.0, 520.0);
+        map.record_chunk(21, 21, 1000.0, 1020.0);
+
+        assert_eq!(current_section(&toc, &map, -50.0, 4.0), None);
+        assert_eq!(current_section(&toc, &map, 300.0, 4.0), Some(0));
+        // A heading scrolled to the top counts (within tolerance)
+        assert_eq!(current_section(&toc, &map, 498.0, 4.0), Some(1));
+        assert_eq!(current_section(&toc, &map, 5000.0, 4.0), Some(2));
+    }
+}
+
+mod chunk_tests {
+    use mirror::models::source_byte_at;
+    use mirror::parsing::LineOffsets;
+
+    #[test]
+    fn test_source_byte_at_verbatim_text() {
+        // Rendered text matches source span 10..15
+        let source = "Greeting: hello";
+        assert_eq!(source_byte_at(source, "hello", &(10..15), 0), 10);
+        assert_eq!(source_byte_at(source, "hello", &(10..15), 3), 13);
+        assert_eq!(source_byte_at(source, "hello", &(10..15), 5), 15);
+    }
+
+    #[test]
+    fn test_source_byte_at_transformed_text_snaps_to_bounds() {
+        // "&amp;" in source rendered as "&"
+        let source = "Fish and &amp;";
+        assert_eq!(source_byte_at(source, "&", &(9..14), 0), 9);
+        assert_eq!(source_byte_at(source, "&", &(9..14), 1), 14);
+    }
+
+    #[test]
+    fn test_source_byte_at_multibyte_text_lands_on_char_boundaries() {
+        // Entities and escapes shorten the rendered text around multibyte chars
+        let source = "Café &amp; crème \\* brûlée\n";
+        let text = "Café & crème * brûlée";
+        let range = 0..source.len() - 1;
+        let offsets = LineOffsets::new(source);
+        for char_index in 0..=text.chars().count() {
+            let byte = source_byte_at(source, text, &range, char_index);
+            assert!(source.is_char_boundary(byte));
+            offsets.byte_to_line_col(source, byte);
+        }
+
+        // Same byte length, different text: never inside a multibyte char
+        assert_eq!(source_byte_at("éa", "aé", &(0..3), 1), 0);
+        assert_eq!(source_byte_at("éa", "aé", &(0..3), 2), 3);
+    }
+}
+
+mod review_round_tests {
+    use mirror::models::{ReviewRound, Verdict};
+    use mirror::storage::{parse_review_line, ReviewComment, ReviewRecord};
+
+    fn comment(text: &str) -> ReviewComment {
+        ReviewComment::new(
+            "SPEC.md".to_string(),
+            None,
+            "snippet".to_string(),
+            text.to_string(),
+            3,
+            0,
+            4,
+            0,
+        )
+    }
+
+    #[test]
+    fn test_from_records_comment_only_sidecar_is_reviewed() {
+        let round = ReviewRound::from_records(
+            vec![
+                ReviewRecord::Comment(comment("One")),
+                ReviewRecord::Comment(comment("Two")),
+            ],
+            "",
+        );
+
+        assert_eq!(round.verdict, Some(Verdict::Reviewed));
+        assert_eq!(round.comments.len(), 2);
+        assert!(!round.timestamp.is_empty());
+        assert!(!round.visible);
+    }
+
+    #[test]
+    fn test_from_records_legacy_approval() {
+        let record = parse_review_line("LGTM - 2025-01-01T10:30:00+00:00").unwrap();
+        let round = ReviewRound::from_records(vec![record], "");
+
+        assert_eq!(round.verdict, Some(Verdict::Lgtm));
+        assert_eq!(round.label(1), "Round 1 · 2025-01-01 10:30 · LGTM");
+    }
+
+    #[test]
+    fn test_label_counts_comments() {
+        let source = "# Spec\n\nsnippet\nmore";
+        let mut round =
+            ReviewRound::from_records(vec![ReviewRecord::Comment(comment("One"))], source);
+        round.timestamp = "not a timestamp".to_string();
+
+        assert_eq!(round.label(2), "Round 2 · not a timestamp · 1 comment");
+    }
+
+    #[test]
+    fn test_label_counts_outdated() {
+        let round =
+            ReviewRound::from_records(vec![ReviewRecord::Comment(comment("One"))], "# Other");
+
+        assert_eq!(round.outdated_count(), 1);
+        assert!(round.label(1).ends_with("1 comment (1 outdated)"));
+    }
 }
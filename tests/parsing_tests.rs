@@ -2,7 +2,10 @@
 mod common;
 
 use common::parse_test_markdown;
+use mirror::diagram::{diagram_kind, render_svg, DiagramKind, Palette};
 use mirror::image_manager::ImageManager;
+use mirror::links::resolve_footnote;
+use mirror::models::{Footnote, Math};
 use mirror::parsing::parse_markdown;
 use std::path::Path;
 
@@ -169,7 +172,7 @@ fn test_position_tracking_single_line() {
 
     assert_eq!(chunks[0].line_start, 1);
     assert_eq!(chunks[0].line_end, 1);
-    assert_eq!(chunks[0].col_start, 1);
+    assert_eq!(chunks[0].byte_range, 0..11);
 }
 
 #[test]
@@ -252,3 +255,313 @@ fn test_mixed_formatting() {
     let formatted = chunks.iter().find(|c| c.bold && c.italic);
     assert!(formatted.is_some(), "Should parse bold+italic text");
 }
+
+#[test]
+fn test_toc_from_headings() {
+    use mirror::models::build_toc;
+
+    let markdown =
+        "# Spec\n\nIntro\n\n## Goals `v2`\n\nText\n\n### Detail\n\nMore\n\n## Risks\n\nEnd\n";
+    let chunks = parse_test_markdown(markdown);
+    let toc = build_toc(&chunks, markdown.lines().count());
+
+    let outline: Vec<_> = toc
+        .iter()
+        .map(|e| (e.level, e.title.as_str(), e.line, e.section_end))
+        .collect();
+    assert_eq!(
+        outline,
+        vec![
+            (1, "Spec", 1, 15),
+            // Styled heading text is joined into one title
+            (2, "Goals v2", 5, 12),
+            (3, "Detail", 9, 12),
+            (2, "Risks", 13, 15),
+        ]
+    );
+}
+
+#[test]
+fn test_toc_section_comment_counts() {
+    use mirror::models::{build_toc, Comment};
+
+    let markdown = "# A\n\ntext\n\n## B\n\ntext\n";
+    let chunks = parse_test_markdown(markdown);
+    let toc = build_toc(&chunks, markdown.lines().count());
+    let comments = vec![
+        Comment::new("intro".to_string(), 3, 0, 3, 0),
+        Comment::new("nested".to_string(), 7, 0, 7, 0),
+    ];
+
+    // Parent sections count their subsections' comments
+    assert_eq!(toc[0].comment_count(&comments), 2);
+    assert_eq!(toc[1].comment_count(&comments), 1);
+}
+
+#[test]
+fn test_parse_links() {
+    let markdown = "See [the **spec**](spec.md#goals), `code` and [`api`](https://example.com).";
+    let chunks = parse_test_markdown(markdown);
+
+    let link_of = |text: &str| {
+        chunks
+            .iter()
+            .find(|c| c.text == text)
+            .unwrap_or_else(|| panic!("no chunk {:?}", text))
+            .link
+            .as_deref()
+    };
+    assert_eq!(link_of("See "), None);
+    // Styled text inside a link keeps both its style and the destination
+    assert_eq!(link_of("the "), Some("spec.md#goals"));
+    assert_eq!(link_of("spec"), Some("spec.md#goals"));
+    assert_eq!(link_of("code"), None);
+    assert_eq!(link_of("api"), Some("https://example.com"));
+    assert_eq!(link_of("."), None);
+}
+
+#[test]
+fn test_parse_lists() {
+    use mirror::models::ListItem;
+
+    let markdown = "3. Third\n4. Fourth\n   - Nested\n\n- [x] Done\n- [ ] Todo\n\nAfter\n";
+    let chunks = parse_test_markdown(markdown);
+
+    let item = |text: &str| {
+        let chunk = chunks.iter().find(|c| c.text == text).unwrap();
+        (chunk.list_depth, chunk.list_item.clone())
+    };
+    let marker = |number, checked| Some(ListItem { number, checked });
+    // Ordered lists count up from their start number
+    assert_eq!(item("Third"), (1, marker(Some(3), None)));
+    assert_eq!(item("Fourth"), (1, marker(Some(4), None)));
+    assert_eq!(item("Nested"), (2, marker(None, None)));
+    assert_eq!(item("Done"), (1, marker(None, Some(true))));
+    assert_eq!(item("Todo"), (1, marker(None, Some(false))));
+    assert_eq!(item("After"), (0, None));
+}
+
+#[test]
+fn test_parse_item_opening_with_nested_list() {
+    use mirror::models::ListItem;
+
+    for (markdown, outer) in [("- \n  - inner\n", None), ("1.\n   1. inner\n", Some(1))] {
+        let chunks = parse_test_markdown(markdown);
+
+        // The outer marker gets an empty line of its own above the nested list
+        assert_eq!(chunks.len(), 2, "{:?}", markdown);
+        assert_eq!(chunks[0].text, "");
+        assert_eq!(chunks[0].line_start, 1);
+        assert_eq!(chunks[0].list_depth, 1);
+        assert_eq!(
+            chunks[0].list_item,
+            Some(ListItem {
+                number: outer,
+                checked: None
+            })
+        );
+        assert!(chunks[0].newline_after);
+
+        assert_eq!(chunks[1].text, "inner");
+        assert_eq!(chunks[1].list_depth, 2);
+        assert_eq!(
+            chunks[1].list_item,
+            Some(ListItem {
+                number: outer,
+                checked: None
+            })
+        );
+    }
+}
+
+#[test]
+fn test_parse_blockquotes_and_alerts() {
+    use mirror::models::AlertKind;
+
+    let markdown = "> Quoted\n> > Nested\n\n> [!WARNING]\n> Careful **here**\n\nPlain\n";
+    let chunks = parse_test_markdown(markdown);
+
+    let quote = |text: &str| {
+        let chunk = chunks.iter().find(|c| c.text == text).unwrap();
+        chunk.quote.as_ref().map(|q| (q.depth, q.alert, q.first))
+    };
+    assert_eq!(quote("Quoted"), Some((1, None, true)));
+    assert_eq!(quote("Nested"), Some((2, None, true)));
+    // The alert marker is not rendered; its content carries the alert kind
+    assert!(chunks.iter().all(|c| !c.text.contains("[!WARNING]")));
+    assert_eq!(quote("Careful "), Some((1, Some(AlertKind::Warning), true)));
+    assert_eq!(quote("here"), Some((1, Some(AlertKind::Warning), false)));
+    assert_eq!(quote("Plain"), None);
+}
+
+#[test]
+fn test_parse_strikethrough() {
+    let markdown = include_str!("fixtures/strikethrough.md");
+    let chunks = parse_test_markdown(markdown);
+
+    let chunk = |text: &str| chunks.iter().find(|c| c.text == text).unwrap();
+
+    assert!(chunk("Rewrite the parser").strikethrough);
+    assert!(!chunk(" (dropped)").strikethrough);
+
+    // Strikethrough combines with other styles
+    let old = chunk("old");
+    assert!(old.strikethrough && old.bold);
+    assert!(!chunk(" new").strikethrough);
+
+    // Inline code keeps the strikethrough of its surroundings
+    let legacy = chunk("legacy_api()");
+    assert!(legacy.strikethrough && legacy.code);
+    assert!(!chunk("api()").strikethrough);
+
+    assert!(chunk("two").strikethrough);
+}
+
+#[test]
+fn test_front_matter_is_not_rendered() {
+    let markdown = "---\ntitle: Spec\nstatus: draft\n---\n\n# Heading\n\nBody\n";
+    let chunks = parse_test_markdown(markdown);
+
+    assert!(chunks.iter().all(|c| !c.text.contains("status")));
+    let heading = chunks.iter().find(|c| c.text == "Heading").unwrap();
+    assert_eq!(heading.line_start, 6);
+
+    // A thematic break later in the document is not front matter
+    let chunks = parse_test_markdown("Intro\n\n---\n\nstatus: text\n");
+    assert!(chunks.iter().any(|c| c.text == "status: text"));
+}
+
+#[test]
+fn test_parse_footnotes() {
+    let markdown = include_str!("fixtures/footnotes.md");
+    let chunks = parse_test_markdown(markdown);
+
+    // References become superscript numbers, numbered in order of first use
+    let references: Vec<_> = chunks
+        .iter()
+        .filter(|c| matches!(c.footnote, Some(Footnote::Reference(_))))
+        .map(|c| (c.text.as_str(), c.link.as_deref(), c.line_start))
+        .collect();
+    assert_eq!(
+        references,
+        vec![
+            ("1", Some("#fn-scale"), 3),
+            ("2", Some("#fn-1"), 3),
+            ("1", Some("#fn-scale"), 7),
+        ]
+    );
+
+    // Definitions move to the footer as a numbered list, in footnote order
+    let first_definition = chunks
+        .iter()
+        .position(|c| matches!(c.footnote, Some(Footnote::Definition(_))))
+        .unwrap();
+    let footer = &chunks[first_definition..];
+    assert!(footer
+        .iter()
+        .all(|c| matches!(c.footnote, Some(Footnote::Definition(_)))));
+    let texts: Vec<_> = footer.iter().map(|c| c.text.as_str()).collect();
+    assert_eq!(
+        texts,
+        vec![
+            "Measured up to 10M rows.",
+            " \u{21a9}",
+            "Second run on a fresh cluster.",
+            " \u{21a9}"
+        ]
+    );
+    assert_eq!(footer[0].list_item.as_ref().unwrap().number, Some(1));
+    assert_eq!(footer[2].list_item.as_ref().unwrap().number, Some(2));
+    assert!(footer.iter().all(|c| c.list_depth == 1));
+    assert_eq!(footer[1].link.as_deref(), Some("#fnref-scale"));
+    assert!(footer[1].newline_after && !footer[0].newline_after);
+
+    // Anchors jump between references and definitions
+    assert_eq!(resolve_footnote(&chunks, "fn-scale"), Some(9));
+    assert_eq!(resolve_footnote(&chunks, "fnref-scale"), Some(3));
+    assert_eq!(resolve_footnote(&chunks, "fn-1"), Some(5));
+    assert_eq!(resolve_footnote(&chunks, "fn-missing"), None);
+    assert_eq!(resolve_footnote(&chunks, "setup"), None);
+}
+
+#[test]
+fn test_parse_math() {
+    let markdown = include_str!("fixtures/math.md");
+    let chunks = parse_test_markdown(markdown);
+
+    let formulas: Vec<_> = chunks
+        .iter()
+        .filter_map(|c| c.math.map(|math| (math, c.text.trim(), c.line_start)))
+        .collect();
+    assert_eq!(
+        formulas,
+        vec![
+            (Math::Inline, "L = \\frac{1}{n} \\sum_i \\ell_i", 3),
+            (
+                Math::Display,
+                "\\nabla_\\theta L = \\mathbb{E}\\left[ \\nabla_\\theta \\log p_\\theta(x) \\right]",
+                5
+            ),
+        ]
+    );
+
+    // Inline formulas sit between the paragraph's text chunks
+    let inline = chunks
+        .iter()
+        .position(|c| c.math == Some(Math::Inline))
+        .unwrap();
+    assert_eq!(chunks[inline - 1].text, "The loss ");
+    assert_eq!(chunks[inline + 1].text, " is averaged per batch.");
+
+    // Formulas in tables keep their source in the cell
+    let table = chunks.iter().find_map(|c| c.table.as_ref()).unwrap();
+    assert_eq!(table.rows[0][1], "r^2");
+
+    // Lone dollar signs are not math
+    let prices = chunks
+        .iter()
+        .position(|c| c.text == "Prices like ")
+        .unwrap();
+    let text: String = chunks[prices..].iter().map(|c| c.text.as_str()).collect();
+    assert_eq!(text, "Prices like $5 and $10 stay text.");
+    assert!(chunks[prices..].iter().all(|c| c.math.is_none()));
+}
+
+#[test]
+fn test_parse_diagram_blocks() {
+    let markdown = include_str!("fixtures/diagrams.md");
+    let chunks = parse_test_markdown(markdown);
+
+    let blocks: Vec<_> = chunks
+        .iter()
+        .filter_map(|c| {
+            Some((
+                diagram_kind(c.code_block_lang.as_deref()?)?,
+                c.text.as_str(),
+            ))
+        })
+        .collect();
+    let kinds: Vec<_> = blocks.iter().map(|(kind, _)| *kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            DiagramKind::Mermaid,
+            DiagramKind::Mermaid,
+            DiagramKind::Dot,
+            DiagramKind::Mermaid
+        ]
+    );
+
+    // Fence contents lay out; unsupported diagram types are errors (shown as source)
+    let palette = Palette {
+        text: egui::Color32::BLACK,
+        stroke: egui::Color32::GRAY,
+        fill: egui::Color32::WHITE,
+        note: egui::Color32::LIGHT_YELLOW,
+    };
+    let rendered: Vec<_> = blocks
+        .iter()
+        .map(|(kind, source)| render_svg(*kind, source, palette).is_ok())
+        .collect();
+    assert_eq!(rendered, vec![true, true, true, false]);
+}